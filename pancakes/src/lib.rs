@@ -93,3 +93,4 @@ pub mod writing {
 }
 pub mod pancake;
 pub mod plug;
+pub mod tx;
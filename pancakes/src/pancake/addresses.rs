@@ -3,3 +3,6 @@ use alloy::primitives::{Address, address};
 pub const WBNB: Address = address!("0xBB4CdB9CBd36B01bD1cBaEBf2De08d9173bc095c");
 pub const USDT: Address = address!("0x55d398326f99059fF775485246999027B3197955");
 pub const BTCB: Address = address!("0x7130d2A12B9BCbFAe4f2634d864A1Ee1Ce3EaD9c");
+pub const CAKE: Address = address!("0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82");
+pub const USD1: Address = address!("0x8d0D000Ee44948FC98c9B98A4FA4921476f08B0d");
+pub const ASTER: Address = address!("0x000Ae314E2A2172a039B26378814C252734f556A");
@@ -9,9 +9,10 @@ use alloy::providers::Provider;
 
 use crate::pancake::pancake_swap::addresses::{PANCAKE_V3_QUOTER_V2, PANCAKE_V3_SWAP_ROUTER, PANCAKE_SMART_ROUTER, WBNB};
 use crate::pancake::pancake_swap::IERC20::IERC20Instance;
-use crate::pancake::pancake_swap::path::apply_slippage_bps;
+use crate::pancake::pancake_swap::path::{apply_slippage_bps, encode_v3_path};
 use crate::{log};
 use crate::writing::cc;
+use crate::tx::{TxSide, send_replaceable};
 
 pub type TxHash = B256;
 
@@ -49,6 +50,17 @@ alloy::sol! {
         function exactInputSingle(ExactInputSingleParams calldata params)
             payable
             returns (uint256 amountOut);
+
+        struct ExactInputParams {
+            bytes   path;
+            address recipient;
+            uint256 deadline;
+            uint256 amountIn;
+            uint256 amountOutMinimum;
+        }
+        function exactInput(ExactInputParams calldata params)
+            payable
+            returns (uint256 amountOut);
     }
 
     #[sol(rpc)]
@@ -62,6 +74,8 @@ alloy::sol! {
         }
         function quoteExactInputSingle(QuoteExactInputSingleParams calldata params)
             returns (uint256 amountOut, uint160, uint32, uint256);
+        function quoteExactInput(bytes path, uint256 amountIn)
+            returns (uint256 amountOut, uint160[], uint32[], uint256);
     }
 
     #[sol(rpc)]
@@ -112,11 +126,8 @@ impl<P: Provider + Clone> PancakeV3<P> {
             log!(cc::YELLOW, "Approval not needed (allowance >= amount)");
             return Ok(());
         }
-        let pending = erc20.approve(self.router_addr, U256::MAX).from(from).send().await?;
-        let tx = *pending.tx_hash();
-                tokio::spawn(async move {
-            let _ = pending.get_receipt().await;
-        });
+        let approve = erc20.approve(self.router_addr, U256::MAX).from(from).into_transaction_request();
+        let (tx, _) = send_replaceable(&self.provider, approve, TxSide::Sell).await?;
         log!(cc::YELLOW, "Approved {:?} for router {:?} in tx {:?}", token, self.router_addr, tx);
         Ok(())
     }
@@ -223,11 +234,7 @@ impl<P: Provider + Clone> PancakeV3<P> {
         if let Some(gas_price) = gas_price_override(gas_price_wei) {
             call = call.gas_price(gas_price);
         }
-        let pending = call.send().await?;
-        let tx = *pending.tx_hash();
-                tokio::spawn(async move {
-            let _ = pending.get_receipt().await;
-        });
+        let (tx, _) = send_replaceable(&self.provider, call.into_transaction_request(), TxSide::Sell).await?;
         Ok((quoted, tx))
     }
 
@@ -311,14 +318,12 @@ impl<P: Provider + Clone> PancakeV3<P> {
         if let Some(gas_price) = gas_price_override(gas_price_wei) {
             call = call.gas_price(gas_price);
         }
-        let pending = call.send().await?;
-        let tx = *pending.tx_hash();
+        let (tx, receipt) = send_replaceable(&self.provider, call.into_transaction_request(), TxSide::Buy).await?;
         log!(cc::YELLOW, "WBNB -> {} Tx: {:?}", token_out_str, tx);
-        let receipt = pending.get_receipt().await;
-        if let Ok(receipt) = receipt {
+        if let Some(receipt) = receipt {
             log!(cc::YELLOW, "Receipt: {:?}", receipt);
         } else {
-            log!(cc::YELLOW, "Receipt error: {:?}", receipt);
+            log!(cc::YELLOW, "Receipt error: tx {:?} not mined", tx);
         }
         Ok((quoted, tx))
     }
@@ -396,11 +401,104 @@ impl<P: Provider + Clone> PancakeV3<P> {
         if let Some(gas_price) = gas_price_override(gas_price_wei) {
             call = call.gas_price(gas_price);
         }
-        let pending = call.send().await?;
-        let tx = *pending.tx_hash();
-                tokio::spawn(async move {
-            let _ = pending.get_receipt().await;
-        });
+        let side = TxSide::for_input(token_in);
+        let (tx, _) = send_replaceable(&self.provider, call.into_transaction_request(), side).await?;
+        Ok((quoted, tx))
+    }
+
+    /// Exact-in swap along a multi-hop v3 path: `tokens[i] -(fees[i])-> tokens[i+1]`.
+    pub async fn swap_exact_in_path(
+        &self,
+        from: Address,
+        tokens: &[Address],
+        fees: &[u32],
+        amount_in: U256,
+        slippage_bps: u32,
+        recipient: Address,
+        deadline_secs_from_now: u64,
+        gas_price_wei: Option<u128>,
+    ) -> Result<(U256, TxHash)> {
+        if tokens.len() < 2 || tokens.len() != fees.len() + 1 {
+            bail!("v3 path needs tokens = fees + 1 and at least two tokens");
+        }
+        if amount_in.is_zero() {
+            bail!("amount_in is zero – nothing to swap");
+        }
+        let token_in = tokens[0];
+        let path = encode_v3_path(tokens, fees);
+        log!(cc::YELLOW, "Path {:?} fees {:?}", tokens, fees);
+
+        let quoter = IQuoterV2::new(self.quoter_addr, self.provider.clone());
+        let quoted = quoter
+            .quoteExactInput(path.clone(), amount_in)
+            .call()
+            .await?
+            .amountOut;
+        let min_out = apply_slippage_bps(quoted, slippage_bps);
+        log!(cc::YELLOW, "Quoted out: {} (min {})", quoted, min_out);
+
+        self.approve_if_needed(token_in, from, amount_in).await?;
+
+        let deadline = U256::from(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                + deadline_secs_from_now,
+        );
+
+        let params = IV3SwapRouter::ExactInputParams {
+            path,
+            recipient,
+            deadline,
+            amountIn: amount_in,
+            amountOutMinimum: min_out,
+        };
+
+        let router = IV3SwapRouter::new(self.router_addr, self.provider.clone());
+        let mut call = router.exactInput(params).from(from);
+        if let Some(gas_price) = gas_price_override(gas_price_wei) {
+            call = call.gas_price(gas_price);
+        }
+        let side = TxSide::for_input(token_in);
+        let (tx, _) = send_replaceable(&self.provider, call.into_transaction_request(), side).await?;
         Ok((quoted, tx))
     }
+
+    /// Sell `percent_bps` of the balance of `tokens[0]` along a multi-hop v3 path.
+    pub async fn sell_percent_via_path(
+        &self,
+        from: Address,
+        tokens: &[Address],
+        fees: &[u32],
+        percent_bps: u16,
+        slippage_bps: u32,
+        recipient: Address,
+        deadline_secs_from_now: u64,
+        gas_price_wei: Option<u128>,
+    ) -> Result<(U256, TxHash)> {
+        if percent_bps == 0 || percent_bps > 10_000 {
+            bail!("percent_bps must be 1..=10000");
+        }
+        let token_in = *tokens.first().ok_or_else(|| anyhow::anyhow!("empty path"))?;
+        let balance = self.token_balance_of(from, token_in).await?;
+        if balance.is_zero() {
+            bail!("balance is zero, nothing to sell");
+        }
+        let amount_in = balance * U256::from(percent_bps) / U256::from(10_000u64);
+        if amount_in.is_zero() {
+            bail!("amount after applying percent is zero – token balance too small");
+        }
+        self.swap_exact_in_path(
+            from,
+            tokens,
+            fees,
+            amount_in,
+            slippage_bps,
+            recipient,
+            deadline_secs_from_now,
+            gas_price_wei,
+        )
+        .await
+    }
 }
@@ -9,6 +9,7 @@ use alloy::providers::Provider;
 use crate::pancake::pancake_swap_v2::path::apply_slippage_bps;
 use crate::{log};
 use crate::writing::cc;
+use crate::tx::{TxSide, send_replaceable};
 use crate::pancake::pancake_swap_v2::IERC20::IERC20Instance;
 use crate::pancake::pancake_swap_v2::addresses::{PANCAKE_V2_ROUTER, PANCAKE_V2_FACTORY, WBNB};
 pub type TxHash = B256;
@@ -95,16 +96,18 @@ impl<P: Provider + Clone> PancakeV2<P> {
             log!(cc::YELLOW, "Approval not needed (allowance >= amount)");
             return Ok(());
         }
-        let pending = erc20.approve(self.router_addr, U256::MAX).from(from).send().await?;
-        let tx = *pending.tx_hash();
-        let _ = pending.get_receipt().await;
+        let approve = erc20.approve(self.router_addr, U256::MAX).from(from).into_transaction_request();
+        let (tx, _) = send_replaceable(&self.provider, approve, TxSide::Sell).await?;
         log!(cc::YELLOW, "Approved {:?} for router {:?} in tx {:?}", token, self.router_addr, tx);
         Ok(())
     }
 
     async fn quote_exact_in(&self, token_in: Address, token_out: Address, amount_in: U256) -> Result<U256> {
+        self.quote_exact_in_path(vec![token_in, token_out], amount_in).await
+    }
+
+    async fn quote_exact_in_path(&self, path: Vec<Address>, amount_in: U256) -> Result<U256> {
         let router = IPancakeRouter02::new(self.router_addr, self.provider.clone());
-        let path = vec![token_in, token_out];
         let amounts: Vec<U256> = router.getAmountsOut(amount_in, path).call().await?;
         amounts
             .last()
@@ -194,10 +197,7 @@ impl<P: Provider + Clone> PancakeV2<P> {
         if let Some(gas_price) = gas_price_override(gas_price_wei) {
             call = call.gas_price(gas_price);
         }
-        let pending = call.send().await?;
-        let tx = *pending.tx_hash();
-        
-        let _ = pending.get_receipt().await;
+        let (tx, _) = send_replaceable(&self.provider, call.into_transaction_request(), TxSide::Sell).await?;
         Ok((quoted, tx))
     }
 
@@ -282,14 +282,12 @@ impl<P: Provider + Clone> PancakeV2<P> {
             call = call.gas_price(gas_price);
         }
         log!(cc::YELLOW, "Sending WBNB -> {} tx", token_out_str);
-        let pending = call.send().await?;
-        let tx = *pending.tx_hash();
+        let (tx, receipt) = send_replaceable(&self.provider, call.into_transaction_request(), TxSide::Buy).await?;
         log!(cc::YELLOW, "WBNB -> {} Tx: {:?}", token_out_str, tx);
-        let receipt = pending.get_receipt().await;
-        if let Ok(receipt) = receipt {
+        if let Some(receipt) = receipt {
             log!(cc::YELLOW, "Receipt: {:?}", receipt);
         } else {
-            log!(cc::YELLOW, "Receipt error: {:?}", receipt);
+            log!(cc::YELLOW, "Receipt error: tx {:?} not mined", tx);
         }
         Ok((quoted, tx))
     }
@@ -367,9 +365,89 @@ impl<P: Provider + Clone> PancakeV2<P> {
         if let Some(gas_price) = gas_price_override(gas_price_wei) {
             call = call.gas_price(gas_price);
         }
-        let pending = call.send().await?;
-        let tx = *pending.tx_hash();
-        let _ = pending.get_receipt().await;
+        let side = TxSide::for_input(token_in);
+        let (tx, _) = send_replaceable(&self.provider, call.into_transaction_request(), side).await?;
+        Ok((quoted, tx))
+    }
+
+    /// Exact-in swap along a multi-hop `path` (e.g. WBNB -> USDT -> token).
+    pub async fn swap_exact_in_path(
+        &self,
+        from: Address,
+        path: Vec<Address>,
+        amount_in: U256,
+        slippage_bps: u32,
+        recipient: Address,
+        deadline_secs_from_now: u64,
+        gas_price_wei: Option<u128>,
+    ) -> Result<(U256, TxHash)> {
+        if path.len() < 2 {
+            bail!("path needs at least two tokens");
+        }
+        if amount_in.is_zero() {
+            bail!("amount_in is zero – nothing to swap");
+        }
+        let token_in = path[0];
+        log!(cc::YELLOW, "Path {:?} (v2)", path);
+
+        let quoted = self.quote_exact_in_path(path.clone(), amount_in).await?;
+        let min_out = apply_slippage_bps(quoted, slippage_bps);
+        log!(cc::YELLOW, "Quoted out: {} (min {})", quoted, min_out);
+
+        self.approve_if_needed(token_in, from, amount_in).await?;
+
+        let deadline = U256::from(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                + deadline_secs_from_now,
+        );
+
+        let router = IPancakeRouter02::new(self.router_addr, self.provider.clone());
+        let mut call = router
+            .swapExactTokensForTokens(amount_in, min_out, path, recipient, deadline)
+            .from(from);
+        if let Some(gas_price) = gas_price_override(gas_price_wei) {
+            call = call.gas_price(gas_price);
+        }
+        let side = TxSide::for_input(token_in);
+        let (tx, _) = send_replaceable(&self.provider, call.into_transaction_request(), side).await?;
         Ok((quoted, tx))
     }
+
+    /// Sell `percent_bps` of the balance of `path[0]` along a multi-hop `path`.
+    pub async fn sell_percent_via_path(
+        &self,
+        from: Address,
+        path: Vec<Address>,
+        percent_bps: u16,
+        slippage_bps: u32,
+        recipient: Address,
+        deadline_secs_from_now: u64,
+        gas_price_wei: Option<u128>,
+    ) -> Result<(U256, TxHash)> {
+        if percent_bps == 0 || percent_bps > 10_000 {
+            bail!("percent_bps must be 1..=10000");
+        }
+        let token_in = *path.first().ok_or_else(|| anyhow::anyhow!("empty path"))?;
+        let balance = self.token_balance_of(from, token_in).await?;
+        if balance.is_zero() {
+            bail!("balance is zero, nothing to sell");
+        }
+        let amount_in = balance * U256::from(percent_bps) / U256::from(10_000u64);
+        if amount_in.is_zero() {
+            bail!("amount after applying percent is zero – token balance too small");
+        }
+        self.swap_exact_in_path(
+            from,
+            path,
+            amount_in,
+            slippage_bps,
+            recipient,
+            deadline_secs_from_now,
+            gas_price_wei,
+        )
+        .await
+    }
 }
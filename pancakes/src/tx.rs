@@ -0,0 +1,358 @@
+//! Sending with stuck-transaction replacement and lifecycle tracking. A
+//! transaction that is not mined within `after_blocks` is rebroadcast with the
+//! same nonce and a bumped gas price, up to `max_bumps` times. Policies are set
+//! per side by the app.
+//!
+//! Every send is tracked as submitted -> pending -> mined / reverted / dropped.
+//! Reverts are replayed with `eth_call` to decode the reason, and a reverted or
+//! dropped transaction is returned as an error.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+
+use alloy::consensus::Transaction as _;
+use alloy::eips::BlockId;
+use alloy::primitives::{Address, B256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
+use alloy::sol_types::decode_revert_reason;
+
+use crate::log;
+use crate::pancake::addresses::WBNB;
+use crate::writing::cc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxSide {
+    Buy,
+    /// Sells and the approvals that gate them
+    Sell,
+}
+
+impl TxSide {
+    /// Spending WBNB is a buy, anything else a sell.
+    pub fn for_input(token_in: Address) -> Self {
+        if token_in == WBNB { TxSide::Buy } else { TxSide::Sell }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReplacePolicy {
+    /// Blocks to wait before rebroadcasting; 0 = never replace
+    pub after_blocks: u64,
+    /// Gas price bump per replacement in % (nodes require at least 10)
+    pub bump_pct: u64,
+    pub max_bumps: u32,
+    /// Ceiling for replacement gas prices (wei)
+    pub max_gas_price: u128,
+}
+
+impl ReplacePolicy {
+    pub const DISABLED: ReplacePolicy =
+        ReplacePolicy { after_blocks: 0, bump_pct: 0, max_bumps: 0, max_gas_price: 0 };
+}
+
+static POLICIES: RwLock<[ReplacePolicy; 2]> = RwLock::new([ReplacePolicy::DISABLED; 2]);
+
+pub fn set_replace_policy(side: TxSide, policy: ReplacePolicy) {
+    if let Ok(mut p) = POLICIES.write() {
+        p[side as usize] = policy;
+    }
+}
+
+pub fn replace_policy(side: TxSide) -> ReplacePolicy {
+    POLICIES.read().map(|p| p[side as usize]).unwrap_or(ReplacePolicy::DISABLED)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Accepted by the node
+    Submitted,
+    /// Waiting to be mined, possibly replaced
+    Pending,
+    Mined,
+    Reverted,
+    /// No longer known to the node and never mined
+    Dropped,
+}
+
+#[derive(Clone, Debug)]
+pub struct TxRecord {
+    /// First hash, then each replacement
+    pub hashes: Vec<B256>,
+    pub side: TxSide,
+    pub status: TxStatus,
+    pub submitted_at: Instant,
+    pub block: Option<u64>,
+    pub gas_used: Option<u64>,
+    pub effective_gas_price: Option<u128>,
+    pub revert_reason: Option<String>,
+}
+
+const TRACKED_MAX: usize = 256;
+
+static TRACKER: Lazy<Mutex<VecDeque<TxRecord>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+fn track(hash: B256, side: TxSide) {
+    let mut t = TRACKER.lock().unwrap();
+    if t.len() >= TRACKED_MAX {
+        t.pop_front();
+    }
+    t.push_back(TxRecord {
+        hashes: vec![hash],
+        side,
+        status: TxStatus::Submitted,
+        submitted_at: Instant::now(),
+        block: None,
+        gas_used: None,
+        effective_gas_price: None,
+        revert_reason: None,
+    });
+}
+
+fn update(hash: B256, f: impl FnOnce(&mut TxRecord)) {
+    let mut t = TRACKER.lock().unwrap();
+    if let Some(r) = t.iter_mut().rev().find(|r| r.hashes.contains(&hash)) {
+        f(r);
+    }
+}
+
+/// Tracked record for `hash` (the first hash or any replacement).
+pub fn tx_record(hash: B256) -> Option<TxRecord> {
+    TRACKER.lock().unwrap().iter().rev().find(|r| r.hashes.contains(&hash)).cloned()
+}
+
+pub fn tx_status(hash: B256) -> Option<TxStatus> {
+    tx_record(hash).map(|r| r.status)
+}
+
+/// Readable revert reason: `Error(string)` (Pancake's `INSUFFICIENT_OUTPUT_AMOUNT`,
+/// four.meme's short codes), `Panic(uint256)` or a raw string; custom errors are
+/// reported by selector.
+pub fn decode_revert(data: &[u8]) -> String {
+    // Raw bytes that merely happen to be UTF-8 (empty data, custom error selectors) are not a reason
+    if let Some(reason) = decode_revert_reason(data).filter(|r| !r.is_empty() && !r.contains(char::is_control)) {
+        return reason;
+    }
+    match data.len() {
+        0 => "reverted without reason".to_string(),
+        1..=3 => format!("revert data 0x{}", hex::encode(data)),
+        _ => format!("custom error 0x{} (data 0x{})", hex::encode(&data[..4]), hex::encode(&data[4..])),
+    }
+}
+
+/// Replay `tx` against the state before `block` to recover why it reverted.
+async fn replay_revert<P: Provider>(provider: &P, tx: &TransactionRequest, block: Option<u64>) -> String {
+    let at = block.map(|b| BlockId::number(b.saturating_sub(1))).unwrap_or_else(BlockId::latest);
+    let mut replay = tx.clone();
+    replay.nonce = None;
+    match provider.call(replay).block(at).await {
+        Ok(_) => "unknown (replay succeeded)".to_string(),
+        Err(e) => match e.as_error_resp() {
+            Some(payload) => match payload.as_revert_data() {
+                Some(data) => decode_revert(&data),
+                None => payload.message.to_string(),
+            },
+            None => e.to_string(),
+        },
+    }
+}
+
+/// Record the outcome of a mined tx; a revert is decoded and returned as an error.
+async fn settle<P: Provider>(
+    provider: &P,
+    tx: &TransactionRequest,
+    hash: B256,
+    receipt: TransactionReceipt,
+) -> Result<(B256, Option<TransactionReceipt>)> {
+    let reverted = !receipt.status();
+    let reason = if reverted {
+        Some(replay_revert(provider, tx, receipt.block_number).await)
+    } else {
+        None
+    };
+    update(hash, |r| {
+        r.status = if reverted { TxStatus::Reverted } else { TxStatus::Mined };
+        r.block = receipt.block_number;
+        r.gas_used = Some(receipt.gas_used);
+        r.effective_gas_price = Some(receipt.effective_gas_price);
+        r.revert_reason = reason.clone();
+    });
+    log!(
+        cc::YELLOW,
+        "Tx {:?} {} in block {:?}: gas used {} @ {} wei",
+        hash,
+        if reverted { "reverted" } else { "mined" },
+        receipt.block_number,
+        receipt.gas_used,
+        receipt.effective_gas_price
+    );
+    match reason {
+        Some(reason) => {
+            log!(cc::RED, "Tx {:?} revert reason: {}", hash, reason);
+            Err(anyhow!("tx {:?} reverted: {}", hash, reason))
+        }
+        None => Ok((hash, Some(receipt))),
+    }
+}
+
+const POLL: Duration = Duration::from_millis(1000);
+
+/// Send `tx` and wait for it to be mined, replacing it when it gets stuck.
+/// Returns the hash that was mined and its receipt, or the last hash sent and
+/// no receipt when it is still pending. Reverted and dropped txs are errors.
+pub async fn send_replaceable<P: Provider>(
+    provider: &P,
+    tx: TransactionRequest,
+    side: TxSide,
+) -> Result<(B256, Option<TransactionReceipt>)> {
+    let policy = replace_policy(side);
+    let pending = provider.send_transaction(tx.clone()).await?;
+    let first = *pending.tx_hash();
+    track(first, side);
+    if policy.after_blocks == 0 {
+        update(first, |r| r.status = TxStatus::Pending);
+        return match pending.get_receipt().await {
+            Ok(receipt) => settle(provider, &tx, first, receipt).await,
+            Err(e) => {
+                log!(cc::YELLOW, "Receipt for tx {:?} unavailable: {}", first, e);
+                Ok((first, None))
+            }
+        };
+    }
+    drop(pending);
+
+    let mut hashes = vec![first];
+    // Pinned from the broadcast tx on the first replacement
+    let mut pinned: Option<(u64, u64, u128)> = None;
+    let mut bumps = 0u32;
+    // RPC errors while polling say nothing about the tx; log them and poll again
+    let mut deadline = None;
+    loop {
+        for h in &hashes {
+            match provider.get_transaction_receipt(*h).await {
+                Ok(Some(receipt)) => return settle(provider, &tx, *h, receipt).await,
+                Ok(None) => {}
+                Err(e) => log!(cc::YELLOW, "Receipt poll for tx {:?} failed: {}", h, e),
+            }
+        }
+        update(first, |r| r.status = TxStatus::Pending);
+        let block = match provider.get_block_number().await {
+            Ok(block) => block,
+            Err(e) => {
+                log!(cc::YELLOW, "Block number poll failed: {}", e);
+                tokio::time::sleep(POLL).await;
+                continue;
+            }
+        };
+        let due = *deadline.get_or_insert(block + policy.after_blocks);
+        if block >= due {
+            if bumps >= policy.max_bumps {
+                let last = *hashes.last().unwrap_or(&first);
+                let mut unknown = false;
+                for h in &hashes {
+                    match provider.get_transaction_by_hash(*h).await {
+                        Ok(Some(_)) => {
+                            log!(cc::YELLOW, "Tx {:?} still pending after {} replacement(s)", last, bumps);
+                            return Ok((last, None));
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            log!(cc::YELLOW, "Lookup of tx {:?} failed: {}", h, e);
+                            unknown = true;
+                        }
+                    }
+                }
+                // Only call it dropped when every lookup answered
+                if unknown {
+                    tokio::time::sleep(POLL).await;
+                    continue;
+                }
+                update(first, |r| r.status = TxStatus::Dropped);
+                log!(cc::RED, "Tx {:?} dropped after {} replacement(s)", last, bumps);
+                return Err(anyhow!("tx {:?} dropped", last));
+            }
+            bumps += 1;
+            deadline = Some(block + policy.after_blocks);
+            if pinned.is_none() {
+                pinned = match provider.get_transaction_by_hash(first).await {
+                    Ok(sent) => sent.map(|sent| {
+                        let price = sent.gas_price().unwrap_or_else(|| sent.max_fee_per_gas());
+                        (sent.nonce(), sent.gas_limit(), price)
+                    }),
+                    Err(e) => {
+                        log!(cc::YELLOW, "Lookup of tx {:?} failed: {}", first, e);
+                        None
+                    }
+                };
+            }
+            let Some((nonce, gas_limit, price)) = pinned else {
+                continue;
+            };
+            let next = (price * (100 + policy.bump_pct.max(10) as u128) / 100).min(policy.max_gas_price);
+            if next <= price {
+                log!(cc::YELLOW, "Tx {:?} not replaced: gas price already at the {} wei cap", hashes.last(), policy.max_gas_price);
+                continue;
+            }
+            let mut replacement = tx.clone();
+            replacement.nonce = Some(nonce);
+            replacement.gas = Some(gas_limit);
+            replacement.gas_price = Some(next);
+            replacement.max_fee_per_gas = None;
+            replacement.max_priority_fee_per_gas = None;
+            replacement.transaction_type = None;
+            match provider.send_transaction(replacement).await {
+                Ok(p) => {
+                    log!(
+                        cc::YELLOW,
+                        "Replaced stuck tx {:?} (nonce {}) at {} wei: {:?}",
+                        hashes.last(),
+                        nonce,
+                        next,
+                        p.tx_hash()
+                    );
+                    hashes.push(*p.tx_hash());
+                    update(first, |r| r.hashes.push(*p.tx_hash()));
+                    pinned = Some((nonce, gas_limit, next));
+                }
+                // "nonce too low" here means one of ours just got mined
+                Err(e) => log!(cc::YELLOW, "Replacement for nonce {} not sent: {}", nonce, e),
+            }
+        }
+        tokio::time::sleep(POLL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+    use alloy::sol_types::{Panic, Revert, SolError};
+
+    #[test]
+    fn decode_revert_error_string() {
+        let data = Revert::from("PancakeRouter: INSUFFICIENT_OUTPUT_AMOUNT").abi_encode();
+        assert_eq!(decode_revert(&data), "revert: PancakeRouter: INSUFFICIENT_OUTPUT_AMOUNT");
+    }
+
+    #[test]
+    fn decode_revert_panic() {
+        let data = Panic { code: U256::from(0x11) }.abi_encode();
+        assert!(decode_revert(&data).contains("overflow"));
+    }
+
+    #[test]
+    fn decode_revert_empty_and_short() {
+        assert_eq!(decode_revert(&[]), "reverted without reason");
+        assert_eq!(decode_revert(&[0xab, 0xcd]), "revert data 0xabcd");
+    }
+
+    #[test]
+    fn decode_revert_custom_error() {
+        let data = [0x12, 0x34, 0x56, 0x78, 0x00, 0x01];
+        assert_eq!(decode_revert(&data), "custom error 0x12345678 (data 0x0001)");
+    }
+}
@@ -1,37 +1,60 @@
 use {
+    crate::app::copy_trade::due_copy_entry,
+    crate::app::entry_filters::{self, EntryCtx},
     crate::app::pair_state::detect_source,
     crate::app::pair_state::extract_price_f64,
     crate::app::pair_state::PairSource,
     crate::app::pair_streams::pair_metrics,
+    crate::libs::cache::{
+        load_positions_cache, quarantine_positions_cache, save_positions_cache, CachedPosition,
+        PositionsCache,
+    },
+    crate::libs::copytrade::{self, short_wallet},
+    crate::libs::devwatch,
+    crate::libs::fills,
+    crate::libs::gas,
+    crate::libs::governor,
+    crate::libs::honeypot,
     crate::libs::lookup::save_log_to_file,
-    crate::libs::sim::{DexType, SimEngine},
+    crate::libs::nonce,
+    crate::libs::risk,
+    crate::libs::sim::{parse_tp_ladder, DexType, SimEngine, SimPosition},
+    crate::libs::sizing::{self, SizingInputs},
+    crate::libs::strategy::{
+        strategy_by_name, ExitParams, ExitReason, Intent, PairUpdate, PositionView, Strategy,
+    },
     crate::libs::tui::ConfigStore,
+    crate::libs::writing::cc,
     crate::libs::ws::pairs::PairInfo,
+    crate::log,
     crate::shared::should_avoid_name,
     anyhow::{anyhow, Result},
     fourmeme::abi::ITokenManagerHelper3,
     fourmeme::addresses::TOKEN_MANAGER_HELPER_3,
     pancakes::pancake::pancake_swap::addresses::PANCAKE_V3_SWAP_ROUTER,
     pancakes::pancake::pancake_swap_v2::addresses::PANCAKE_V2_ROUTER,
-    std::collections::{HashMap, HashSet},
+    std::collections::{HashMap, HashSet, VecDeque},
     std::future::Future,
     std::pin::Pin,
+    std::sync::atomic::{AtomicU64, Ordering},
     std::sync::Arc,
-    std::time::Instant,
+    std::time::{Instant, SystemTime, UNIX_EPOCH},
     tokio::sync::{mpsc, RwLock},
     tokio::time::Duration,
 };
 
 use crate::router::FmRouter;
+use crate::routy::route::{price_in_wbnb, Route};
 use crate::routy::{v2 as routy_v2, v3 as routy_v3, wbnb};
 use alloy::primitives::utils::parse_units;
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{Address, B256, U256};
 use alloy::providers::{Provider, WalletProvider};
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 use pancakes::pancake::pancake_swap::addresses::WBNB;
 use pancakes::pancake::pancake_swap::PancakeV3;
 use pancakes::pancake::pancake_swap_v2::PancakeV2;
+use pancakes::tx::{send_replaceable, tx_status, TxSide, TxStatus};
 use tokio::sync::Mutex;
 
 alloy::sol! {
@@ -47,12 +70,21 @@ alloy::sol! {
     }
 }
 
-async fn safe_balance_of<P>(provider: P, token: Address, owner: Address) -> U256
+async fn balance_of<P>(provider: P, token: Address, owner: Address) -> Result<U256>
 where
     P: Provider + Clone,
 {
     let c = IERC20Lite::new(token, provider);
-    c.balanceOf(owner).call().await.unwrap_or(U256::ZERO)
+    Ok(c.balanceOf(owner).call().await?)
+}
+
+async fn safe_balance_of<P>(provider: P, token: Address, owner: Address) -> U256
+where
+    P: Provider + Clone,
+{
+    balance_of(provider, token, owner)
+        .await
+        .unwrap_or(U256::ZERO)
 }
 
 pub fn pair_key_addr(a: alloy::primitives::Address) -> String {
@@ -63,19 +95,7 @@ pub fn pair_key_str(s: &str) -> String {
     s.trim().to_ascii_lowercase()
 }
 
-const DEFAULT_GAS_WEI: u128 = 1_000_000_000; // 1 gwei
-const GWEI_DECIMALS: u8 = 9;
-
-fn gas_price_wei_u128(config_store: &ConfigStore) -> u128 {
-    config_store
-        .get("max_gwei")
-        .and_then(|v| parse_units(v.as_str(), GWEI_DECIMALS).ok())
-        .and_then(|wei| wei.try_into().ok())
-        .filter(|wei| *wei > 0)
-        .unwrap_or(DEFAULT_GAS_WEI)
-}
-
-fn wei_to_bnb(wei: U256) -> f64 {
+pub(crate) fn wei_to_bnb(wei: U256) -> f64 {
     wei.try_into()
         .map(|v: u128| v as f64 / 1e18f64)
         .unwrap_or(0.0)
@@ -89,6 +109,94 @@ fn wrap_ratio_pct_value(config_store: &ConfigStore) -> u64 {
         .unwrap_or(80)
 }
 
+fn slippage_pct_to_bps(pct: f64) -> u32 {
+    (pct * 100.0).round().clamp(0.0, 9_900.0) as u32
+}
+
+/// Buy slippage in bps (`buy_slippage_pct`).
+fn buy_slippage_bps(config_store: &ConfigStore) -> u32 {
+    let pct = config_store
+        .get("buy_slippage_pct")
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(0.5);
+    slippage_pct_to_bps(pct)
+}
+
+/// Initial sell slippage in bps (`sell_slippage_pct`).
+fn sell_slippage_bps(config_store: &ConfigStore) -> u32 {
+    let pct = config_store
+        .get("sell_slippage_pct")
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(0.5);
+    slippage_pct_to_bps(pct)
+}
+
+/// Highest slippage a sell may escalate to after INSUFFICIENT_OUTPUT_AMOUNT reverts.
+pub(crate) fn sell_slippage_cap_bps(config_store: &ConfigStore) -> u32 {
+    let pct = config_store
+        .get("sell_slippage_max_pct")
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(10.0);
+    slippage_pct_to_bps(pct).max(sell_slippage_bps(config_store))
+}
+
+fn is_slippage_revert(msg: &str) -> bool {
+    msg.contains("INSUFFICIENT_OUTPUT_AMOUNT")
+        || msg.contains("Too little received")
+        || msg.contains("Slippage")
+        || msg.contains("slippage")
+}
+
+/// Run a sell with `start_bps` slippage; on a slippage revert retry with doubled
+/// slippage until `cap_bps` is reached. The nonce of the failed attempt is
+/// handed back before the retry.
+async fn sell_with_slippage_escalation<T, F, Fut>(
+    from: Address,
+    label: &str,
+    start_bps: u32,
+    cap_bps: u32,
+    mut sell: F,
+) -> Result<T>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut slippage_bps = start_bps.max(1);
+    loop {
+        match sell(slippage_bps).await {
+            Ok(v) => return Ok(v),
+            Err(e) if slippage_bps < cap_bps && is_slippage_revert(&e.to_string()) => {
+                nonce::on_send_error(from, &e.to_string());
+                let next = slippage_bps.saturating_mul(2).min(cap_bps);
+                save_log_to_file(&format!(
+                    "[trade] {} reverted at {} bps slippage, retrying at {} bps",
+                    label, slippage_bps, next
+                ));
+                slippage_bps = next;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Stream price (quote per token) in WBNB terms, routed through the pair's quote.
+/// FourMeme prices are already native BNB; unroutable v2/v3 quotes yield None.
+pub fn wbnb_price(src: PairSource, pair_info: &PairInfo, price_in_quote: f64) -> Option<f64> {
+    if src == PairSource::FourMeme {
+        return Some(price_in_quote);
+    }
+    let route = Route::for_pair(pair_info.addr1, pair_info.addr2, pair_info.fee)?;
+    price_in_wbnb(&route, price_in_quote)
+}
+
+/// "WBNB" for direct routes, "WBNB>USDT" style for routed buys
+fn route_label(route: &Route, pair_info: &PairInfo) -> String {
+    match route.via {
+        Some(_) => format!("WBNB>{}", pair_info.symbol_quote),
+        None => "WBNB".to_string(),
+    }
+}
+
 fn record_buy_failure(trader: &mut RealTrader, pair_key: &str) -> u32 {
     let cnt = BUY_FAILS
         .entry(pair_key.to_string())
@@ -108,16 +216,207 @@ fn clear_buy_failures(pair_key: &str) {
     BUY_FAILS.remove(pair_key);
 }
 
-fn contains_cjk(s: &str) -> bool {
-    s.chars().any(|c| {
-        let u = c as u32;
-        (0x4E00..=0x9FFF).contains(&u)
-            || (0x3400..=0x4DBF).contains(&u)
-            || (0x20000..=0x2A6DF).contains(&u)
-            || (0x2A700..=0x2B73F).contains(&u)
-            || (0x2B740..=0x2B81F).contains(&u)
-            || (0x2B820..=0x2CEAF).contains(&u)
-    })
+/// Re-read wallet funds after a landed trade, so sizing does not wait for the
+/// balance ticker.
+async fn refresh_funds<P: Provider>(provider: &P, from: Address) {
+    let bnb = provider.get_balance(from).await;
+    let wbnb = IERC20Lite::new(WBNB, provider).balanceOf(from).call().await;
+    if let (Ok(bnb), Ok(wbnb)) = (bnb, wbnb) {
+        sizing::set_funds(sizing::Funds {
+            bnb: wei_to_bnb(bnb),
+            wbnb: wei_to_bnb(wbnb),
+        });
+    }
+}
+
+/// Entry price, BNB spent and tokens received for a landed buy, read from its
+/// receipt. Falls back to the stream price and intended size when the receipt
+/// lacks them (tokens are then None).
+async fn buy_fill<P: Provider>(
+    provider: &P,
+    tx: B256,
+    from: Address,
+    token: Address,
+    stream_price: f64,
+    intended_bnb: f64,
+) -> (f64, f64, Option<U256>) {
+    refresh_funds(provider, from).await;
+    let Some(fill) = fills::fetch_fill(provider, tx, from, token, TxSide::Buy).await else {
+        return (stream_price, intended_bnb, None);
+    };
+    let spent_bnb = fill.bnb.unwrap_or(intended_bnb);
+    let entry_price = match fills::token_decimals(provider, token).await {
+        Some(decimals) => fill.price(decimals).unwrap_or(stream_price),
+        None => stream_price,
+    };
+    save_log_to_file(&format!(
+        "[trade] FILL buy {:#x} tokens={} spent={:.6} BNB fee={:.6} gas={:.6} entry={:.10} (stream {:.10})",
+        token, fill.tokens, spent_bnb, fill.fee_bnb, fill.gas_bnb, entry_price, stream_price
+    ));
+    (
+        entry_price,
+        spent_bnb,
+        Some(fill.tokens).filter(|t| !t.is_zero()),
+    )
+}
+
+/// Token balance after a sent buy landed: mined per the tx tracker, or, while
+/// it is still pending or untracked, grown after a short wait. None when the
+/// tx reverted, was dropped or the balance never grew.
+async fn bought_balance<P>(
+    provider: P,
+    tx: B256,
+    token: Address,
+    owner: Address,
+    bal_before: U256,
+) -> Option<U256>
+where
+    P: Provider + Clone,
+{
+    match tx_status(tx) {
+        Some(TxStatus::Mined) => Some(safe_balance_of(provider, token, owner).await),
+        Some(TxStatus::Reverted | TxStatus::Dropped) => None,
+        _ => {
+            tokio::time::sleep(Duration::from_millis(2000)).await;
+            let bal_after = safe_balance_of(provider, token, owner).await;
+            (bal_after > bal_before).then_some(bal_after)
+        }
+    }
+}
+
+/// A sent real buy and what booking it needs.
+struct SentBuy<'a> {
+    pair_key: &'a str,
+    pair_info: &'a PairInfo,
+    dex_type: DexType,
+    token: Address,
+    tx: B256,
+    /// Token balance before the buy
+    bal_before: U256,
+    /// " via WBNB" style route for the log; empty for four.meme
+    route: String,
+    fee: Option<u32>,
+    via: Option<Address>,
+    /// Stream price and sized amount, used when the receipt lacks the fill
+    price: f64,
+    amount_bnb: f64,
+    tp_pct: Option<f64>,
+    sl_pct: Option<f64>,
+    copied_from: Option<Address>,
+    gas_price_wei: u128,
+}
+
+/// Book a sent buy: confirm it landed, read its fill from the receipt, feed
+/// the governor, mirror it into the sim engine at the fill and record the
+/// real position. A buy that did not land counts as a failed attempt.
+async fn record_landed_buy<P>(
+    provider: &P,
+    buy: SentBuy<'_>,
+    sim_engine: Option<&Arc<tokio::sync::Mutex<SimEngine>>>,
+    config_store: &ConfigStore,
+) where
+    P: Provider + Clone + WalletProvider + Send + Sync + 'static,
+{
+    let from = provider.default_signer_address();
+    let label = match buy.dex_type {
+        DexType::V2 => "V2",
+        DexType::V3 => "V3",
+        DexType::FourMeme => "FM",
+    };
+    let symbol = &buy.pair_info.symbol_base;
+    let Some(bal_after) =
+        bought_balance(provider.clone(), buy.tx, buy.token, from, buy.bal_before).await
+    else {
+        let mut trader = REAL_TRADER.lock().await;
+        let attempts = record_buy_failure(&mut trader, buy.pair_key);
+        save_log_to_file(&format!(
+            "[trade] ✗ {} BUY {} tx={} not confirmed and token balance did not increase (before={} status={:?} attempts={})",
+            label, symbol, buy.tx, buy.bal_before, tx_status(buy.tx), attempts
+        ));
+        return;
+    };
+
+    let (entry_price, spent_bnb, tokens) = buy_fill(
+        provider,
+        buy.tx,
+        from,
+        buy.token,
+        buy.price,
+        buy.amount_bnb,
+    )
+    .await;
+    save_log_to_file(&format!(
+        "[trade] ✓ {} BUY {}{} ({} BNB) tx={} token balance={}",
+        label, symbol, buy.route, spent_bnb, buy.tx, bal_after
+    ));
+    clear_buy_failures(buy.pair_key);
+    governor::record_buy("trade", spent_bnb, config_store);
+    if let Some(se_arc) = sim_engine {
+        let mut se = se_arc.lock().await;
+        se.add_real_position(
+            buy.pair_key.to_string(),
+            buy.dex_type,
+            symbol.clone(),
+            buy.pair_info.symbol_quote.clone(),
+            entry_price,
+            spent_bnb,
+            buy.tp_pct,
+            buy.sl_pct,
+            buy.pair_info.liquidity_usd,
+        );
+    }
+    let mut trader = REAL_TRADER.lock().await;
+    trader.record_buy(RealPosition {
+        pair_address: buy.pair_key.to_string(),
+        dex_type: buy.dex_type,
+        token_out: buy.token,
+        base_symbol: symbol.clone(),
+        quote_symbol: buy.pair_info.symbol_quote.clone(),
+        entry_price,
+        peak_price: entry_price,
+        buy_amount_bnb: spent_bnb,
+        tokens_held: tokens.unwrap_or(bal_after.saturating_sub(buy.bal_before)),
+        cost_basis_bnb: spent_bnb,
+        remaining_fraction: 1.0,
+        realized_bnb: 0.0,
+        opened_at: Instant::now(),
+        tp_tiers_hit: 0,
+        fee: buy.fee,
+        via: buy.via,
+        frozen: false,
+        frozen_hit: None,
+        copied_from: buy.copied_from,
+    });
+    drop(trader);
+    queue_allowance_job(provider.clone(), buy.dex_type, buy.token, buy.gas_price_wei);
+}
+
+/// Token balance after a sent sell landed: mined per the tx tracker, or, while
+/// it is still pending or untracked, dropped within `retries` polls. None when
+/// the tx reverted, was dropped or the balance never dropped.
+async fn sold_balance<P>(
+    provider: P,
+    tx: B256,
+    token: Address,
+    owner: Address,
+    bal_before: U256,
+    retries: usize,
+) -> Option<U256>
+where
+    P: Provider + Clone,
+{
+    match tx_status(tx) {
+        Some(TxStatus::Mined) => Some(safe_balance_of(provider, token, owner).await),
+        Some(TxStatus::Reverted | TxStatus::Dropped) => None,
+        _ => {
+            let bal_after = safe_balance_of(provider.clone(), token, owner).await;
+            if bal_after < bal_before {
+                Some(bal_after)
+            } else {
+                wait_for_balance_drop(provider, token, owner, bal_before, retries, 400).await
+            }
+        }
+    }
 }
 
 async fn wait_for_balance_drop<P>(
@@ -282,9 +581,8 @@ where
     if gas_price_wei > 0 {
         call = call.gas_price(gas_price_wei);
     }
-    let pending = call.send().await?;
-    let tx = *pending.tx_hash();
-    let _ = pending.get_receipt().await;
+    let (tx, _) =
+        send_replaceable(&provider, call.into_transaction_request(), TxSide::Sell).await?;
     save_log_to_file(&format!(
         "[allow] sent approval for {:#x} -> {:#x} tx={}",
         token, spender, tx
@@ -344,6 +642,7 @@ where
         match send_allowance(provider.clone(), market, token, gas_price_wei).await {
             Ok(_) => return Ok(()),
             Err(e) => {
+                nonce::on_send_error(provider.default_signer_address(), &e.to_string());
                 last_err = Some(e);
                 // Small backoff before retry
                 tokio::time::sleep(Duration::from_millis(300 * (i as u64 + 1))).await;
@@ -447,6 +746,7 @@ where
             }
         }
         Err(e) => {
+            nonce::on_send_error(from, &e.to_string());
             save_log_to_file(&format!(
                 "[trade] SKIP {}: failed to wrap BNB: {}",
                 pair_label, e
@@ -464,17 +764,287 @@ struct RealPosition {
     dex_type: DexType,
     token_out: Address,
     base_symbol: String,
+    quote_symbol: String,
     entry_price: f64,
+    peak_price: f64,
     buy_amount_bnb: f64,
+    /// Tokens still held, raw units
+    tokens_held: U256,
+    /// BNB cost of the part still held (buy size less the sold shares)
+    cost_basis_bnb: f64,
+    /// Share of the original size still held (1.0 until the first sell)
+    remaining_fraction: f64,
+    /// PnL booked by partial sells so far
+    realized_bnb: f64,
     opened_at: Instant,
+    tp_tiers_hit: usize,
+    /// V3 pool fee tier the position was bought through
+    fee: Option<u32>,
+    /// Intermediate quote for WBNB -> quote -> token routes
+    via: Option<Address>,
+    /// Frozen from Results: exits are logged but never sent
+    frozen: bool,
+    /// Exit last logged as held back by the freeze (not persisted)
+    frozen_hit: Option<String>,
+    /// Watched wallet this position copies; its sell exits the position
+    copied_from: Option<Address>,
+}
+
+/// Trailing stop settings from config as (trail % below peak, activation PnL %).
+pub fn trailing_stop_cfg(config_store: &ConfigStore) -> (Option<f64>, f64) {
+    let trail_enabled = config_store
+        .get("trail_enabled")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    let trail_pct = if trail_enabled {
+        config_store
+            .get("trail_pct")
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| *v > 0.0)
+    } else {
+        None
+    };
+    let activate_pct = config_store
+        .get("trail_activate_pct")
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(30.0);
+    (trail_pct, activate_pct)
+}
+
+/// Share of their holdings (%) the dev may sell before open positions exit;
+/// None when the rule is off.
+pub fn dev_sell_exit_cfg(config_store: &ConfigStore) -> Option<f64> {
+    let enabled = config_store
+        .get("dev_sell_exit")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    config_store
+        .get("dev_sell_pct")
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| *v > 0.0)
+        .or(Some(50.0))
+}
+
+/// Take-profit ladder from config as (pnl %, sell % of original size) tiers.
+/// Empty when the ladder is disabled.
+pub fn tp_ladder_cfg(config_store: &ConfigStore) -> Vec<(f64, f64)> {
+    let ladder_enabled = config_store
+        .get("tp_ladder_enabled")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    if !ladder_enabled {
+        return Vec::new();
+    }
+    config_store
+        .get("tp_ladder")
+        .map(|v| parse_tp_ladder(v.as_str()))
+        .unwrap_or_default()
+}
+
+/// Exit settings from config; `tp_ladder` comes from `tp_ladder_cfg`.
+pub fn exit_params_cfg<'a>(
+    config_store: &ConfigStore,
+    tp_ladder: &'a [(f64, f64)],
+) -> ExitParams<'a> {
+    let enabled_pct = |flag: &str, key: &str| {
+        let enabled = config_store
+            .get(flag)
+            .map(|v| v.as_str() == "true")
+            .unwrap_or(false);
+        if enabled {
+            config_store.get(key).and_then(|v| v.parse::<f64>().ok())
+        } else {
+            None
+        }
+    };
+    let (trail_pct, trail_activate_pct) = trailing_stop_cfg(config_store);
+    ExitParams {
+        tp_pct: enabled_pct("tp_enabled", "tp_pct"),
+        sl_pct: enabled_pct("sl_enabled", "sl_pct"),
+        tp_ladder,
+        trail_pct,
+        trail_activate_pct,
+        max_hold_secs: config_store
+            .get("max_hold_secs")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0),
+        max_hold_pnl: config_store
+            .get("max_hold_pnl")
+            .map(|v| v.as_str() == "true")
+            .unwrap_or(true),
+        dev_sell_pct: dev_sell_exit_cfg(config_store),
+    }
+}
+
+/// Entry/exit strategy selected in config.
+pub fn strategy_cfg(config_store: &ConfigStore) -> &'static dyn Strategy {
+    let name = config_store
+        .get("strategy")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "default".to_string());
+    strategy_by_name(&name)
+}
+
+/// Strategy input for a pair update priced at `price` (WBNB).
+pub fn pair_update(price: f64, buy_count: u32, pair_info: &PairInfo) -> PairUpdate {
+    PairUpdate {
+        price,
+        liquidity_usd: pair_info.liquidity_usd,
+        buy_count,
+        sell_count: pair_info.sell_count,
+        unique_buyers: pair_info.unique_buyers,
+        dev_sold_pct: devwatch::dev_sold_pct(pair_info.pair),
+    }
+}
+
+/// Max round-trip tax (%) when the pre-buy honeypot check is enabled, and
+/// whether quote-only reports (no `eth_simulateV1`) may pass.
+pub fn honeypot_cfg(config_store: &ConfigStore) -> Option<(f64, bool)> {
+    let enabled = config_store
+        .get("honeypot_check")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    let max_tax_pct = config_store
+        .get("max_tax_pct")
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(10.0);
+    let quote_only_ok = config_store
+        .get("honeypot_quote_only")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    Some((max_tax_pct, quote_only_ok))
+}
+
+/// Max bytecode risk score when the token risk scan is enabled.
+pub fn risk_cfg(config_store: &ConfigStore) -> Option<u32> {
+    let enabled = config_store
+        .get("risk_check")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    Some(
+        config_store
+            .get("max_risk_score")
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(50),
+    )
+}
+
+/// Token to risk-scan for a Pancake pair. four.meme tokens all come from the
+/// launchpad template and are not scanned.
+pub fn risk_scan_token(src: PairSource, pair_info: &PairInfo) -> Option<Address> {
+    match src {
+        PairSource::V2 | PairSource::V3 => {
+            Route::for_pair(pair_info.addr1, pair_info.addr2, None).map(|r| r.token)
+        }
+        _ => None,
+    }
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl RealPosition {
+    fn to_cached(&self) -> CachedPosition {
+        CachedPosition {
+            pair_address: self.pair_address.clone(),
+            dex_type: self.dex_type,
+            token_out: format!("{:#x}", self.token_out),
+            base_symbol: self.base_symbol.clone(),
+            quote_symbol: self.quote_symbol.clone(),
+            entry_price: self.entry_price,
+            peak_price: self.peak_price,
+            buy_amount_bnb: self.buy_amount_bnb,
+            tokens_held: Some(self.tokens_held.to_string()),
+            cost_basis_bnb: Some(self.cost_basis_bnb),
+            remaining_fraction: Some(self.remaining_fraction),
+            realized_bnb: self.realized_bnb,
+            opened_at: unix_now_secs().saturating_sub(self.opened_at.elapsed().as_secs()),
+            tp_tiers_hit: self.tp_tiers_hit,
+            fee: self.fee,
+            via: self.via.map(|a| format!("{:#x}", a)),
+            frozen: self.frozen,
+            copied_from: self.copied_from.map(|a| format!("{:#x}", a)),
+        }
+    }
+
+    fn from_cached(c: &CachedPosition) -> Option<Self> {
+        let token_out = c.token_out.parse::<Address>().ok()?;
+        // Instant can't be serialized; rebuild it from the wall-clock age
+        let age = Duration::from_secs(unix_now_secs().saturating_sub(c.opened_at));
+        let opened_at = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+        Some(Self {
+            pair_address: pair_key_str(&c.pair_address),
+            dex_type: c.dex_type,
+            token_out,
+            base_symbol: c.base_symbol.clone(),
+            quote_symbol: c.quote_symbol.clone(),
+            entry_price: c.entry_price,
+            peak_price: c.peak_price.max(c.entry_price),
+            buy_amount_bnb: c.buy_amount_bnb,
+            // Older ledgers lack these: full size, tokens read back on restore
+            tokens_held: c
+                .tokens_held
+                .as_deref()
+                .and_then(|t| t.parse::<U256>().ok())
+                .unwrap_or_default(),
+            cost_basis_bnb: c.cost_basis_bnb.unwrap_or(c.buy_amount_bnb),
+            remaining_fraction: c.remaining_fraction.unwrap_or(1.0),
+            realized_bnb: c.realized_bnb,
+            opened_at,
+            tp_tiers_hit: c.tp_tiers_hit,
+            fee: c.fee,
+            via: c.via.as_deref().and_then(|a| a.parse::<Address>().ok()),
+            frozen: c.frozen,
+            frozen_hit: None,
+            copied_from: c
+                .copied_from
+                .as_deref()
+                .and_then(|a| a.parse::<Address>().ok()),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 enum SellTrigger {
     TakeProfit(f64),
+    /// Ladder tier (index, trigger pnl %)
+    TakeProfitTier(usize, f64),
     StopLoss(f64),
+    Trailing(f64),
     MaxHold(u64),
     Manual,
+    /// Share of pool liquidity pulled (%)
+    LiquidityPull(f64),
+    /// Share of holdings the dev sold (%)
+    DevSold(f64),
+    /// Copied wallet sold
+    CopyExit(Address),
+}
+
+impl From<ExitReason> for SellTrigger {
+    fn from(reason: ExitReason) -> Self {
+        match reason {
+            ExitReason::TakeProfit(tp) => SellTrigger::TakeProfit(tp),
+            ExitReason::TakeProfitTier(idx, at) => SellTrigger::TakeProfitTier(idx, at),
+            ExitReason::StopLoss(sl) => SellTrigger::StopLoss(sl),
+            ExitReason::Trailing(trail) => SellTrigger::Trailing(trail),
+            ExitReason::MaxHold(secs) => SellTrigger::MaxHold(secs),
+            ExitReason::DevSold(pct) => SellTrigger::DevSold(pct),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -483,21 +1053,116 @@ struct SellPlan {
     dex_type: DexType,
     token_out: Address,
     base_symbol: String,
-    spent_bnb: f64,
+    /// BNB cost of the part still held
+    cost_basis_bnb: f64,
+    /// PnL already realized by earlier partial sells
+    realized_bnb: f64,
     pnl_pct: f64,
     trigger: SellTrigger,
     percent_points: u32,
+    fee: Option<u32>,
+    via: Option<Address>,
+}
+
+/// Pairs closed in real mode that are never bought again
+const DO_NOT_REBUY_CAP: usize = 2000;
+
+/// `do_not_rebuy` in insertion order; past [`DO_NOT_REBUY_CAP`] the oldest
+/// pairs are forgotten so the set and the persisted ledger stay bounded.
+#[derive(Default)]
+struct RebuyBlocklist {
+    set: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl RebuyBlocklist {
+    fn contains(&self, pair_key: &str) -> bool {
+        self.set.contains(pair_key)
+    }
+
+    fn insert(&mut self, pair_key: String) -> bool {
+        if !self.set.insert(pair_key.clone()) {
+            return false;
+        }
+        self.order.push_back(pair_key);
+        while self.order.len() > DO_NOT_REBUY_CAP {
+            if let Some(old) = self.order.pop_front() {
+                self.set.remove(&old);
+            }
+        }
+        true
+    }
+
+    fn remove(&mut self, pair_key: &str) {
+        if self.set.remove(pair_key) {
+            self.order.retain(|k| k != pair_key);
+        }
+    }
+
+    /// Oldest first, as persisted
+    fn to_vec(&self) -> Vec<String> {
+        self.order.iter().cloned().collect()
+    }
 }
 
 #[derive(Default)]
 struct RealTrader {
     positions: HashMap<String, RealPosition>,
     closing: HashSet<String>,
-    do_not_rebuy: HashSet<String>,
+    do_not_rebuy: RebuyBlocklist,
 }
 
 static REAL_TRADER: Lazy<Mutex<RealTrader>> = Lazy::new(|| Mutex::new(RealTrader::default()));
 
+/// Latest ledger snapshot (with its sequence number) for the writer task, so
+/// the file write never runs under the `REAL_TRADER` lock. Only the newest
+/// snapshot is written.
+static LEDGER_TX: Lazy<tokio::sync::watch::Sender<Option<(u64, PositionsCache)>>> =
+    Lazy::new(|| {
+        let (tx, mut rx) = tokio::sync::watch::channel(None::<(u64, PositionsCache)>);
+        tokio::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let Some(snapshot) = rx.borrow_and_update().clone() else {
+                    continue;
+                };
+                write_ledger(snapshot).await;
+            }
+        });
+        tx
+    });
+/// Sequence number of the last snapshot handed to the writer
+static LEDGER_SEQ: AtomicU64 = AtomicU64::new(0);
+/// Sequence number of the last snapshot on disk; also serializes the writes
+static LEDGER_WRITTEN: std::sync::Mutex<u64> = std::sync::Mutex::new(0);
+
+/// Write `snapshot` unless a newer one is already on disk.
+async fn write_ledger((seq, ledger): (u64, PositionsCache)) {
+    let saved = tokio::task::spawn_blocking(move || {
+        let mut written = LEDGER_WRITTEN.lock().unwrap_or_else(|e| e.into_inner());
+        if seq <= *written {
+            return Ok(());
+        }
+        save_positions_cache(&ledger)?;
+        *written = seq;
+        Ok::<_, anyhow::Error>(())
+    })
+    .await;
+    match saved {
+        Ok(Err(e)) => save_log_to_file(&format!("[trade] failed to persist positions: {}", e)),
+        Err(e) => save_log_to_file(&format!("[trade] positions writer failed: {}", e)),
+        Ok(Ok(())) => {}
+    }
+}
+
+/// Write the latest ledger snapshot now; call before exiting so the last
+/// update is not lost with the writer task.
+pub async fn flush_positions() {
+    let snapshot = LEDGER_TX.borrow().clone();
+    if let Some(snapshot) = snapshot {
+        write_ledger(snapshot).await;
+    }
+}
+
 impl RealTrader {
     fn has_position_or_blocked(&self, pair_key: &str) -> bool {
         self.positions.contains_key(pair_key)
@@ -505,10 +1170,29 @@ impl RealTrader {
             || self.do_not_rebuy.contains(pair_key)
     }
 
+    /// Queue the ledger for `.cache/positions.json` (in-flight `closing` is not
+    /// persisted). The write happens on the writer task, off this lock.
+    fn persist(&self) {
+        let ledger = PositionsCache {
+            positions: self
+                .positions
+                .values()
+                .map(RealPosition::to_cached)
+                .collect(),
+            do_not_rebuy: self.do_not_rebuy.to_vec(),
+        };
+        let seq = LEDGER_SEQ.fetch_add(1, Ordering::Relaxed) + 1;
+        LEDGER_TX.send_replace(Some((seq, ledger)));
+    }
+
     fn record_buy(&mut self, pos: RealPosition) {
+        if let Some(wallet) = pos.copied_from {
+            copytrade::mark_copied(&pos.pair_address, wallet);
+        }
         self.closing.remove(&pos.pair_address);
         self.do_not_rebuy.remove(&pos.pair_address);
         self.positions.insert(pos.pair_address.clone(), pos);
+        self.persist();
     }
 
     fn reserve_close(&mut self, pair_key: &str) -> bool {
@@ -528,15 +1212,59 @@ impl RealTrader {
 
     fn finish_sell(&mut self, pair_key: &str) {
         self.closing.remove(pair_key);
+        copytrade::forget_copied(pair_key);
         if let Some(pos) = self.positions.remove(pair_key) {
             self.do_not_rebuy.insert(pos.pair_address);
+            self.persist();
+        }
+    }
+
+    /// A partial sell ended: keep the position and, if it landed, book it and
+    /// advance the ladder when it was a tier. A skipped sell changes nothing.
+    fn finish_partial(&mut self, plan: &SellPlan, outcome: &SellOutcome) {
+        self.closing.remove(&plan.pair_key);
+        let SellOutcome::Landed(sold) = outcome else {
+            return;
+        };
+        if let Some(pos) = self.positions.get_mut(&plan.pair_key) {
+            if let SellTrigger::TakeProfitTier(idx, _) = plan.trigger {
+                pos.tp_tiers_hit = pos.tp_tiers_hit.max(idx + 1);
+            }
+            self.record_sell(plan, sold);
+            self.persist();
         }
     }
 
+    /// Book a landed partial sell: the sold share of the tokens and cost basis
+    /// leaves the position and its PnL is added to the realized BNB. Without a
+    /// fill the plan's share is taken off at the estimated PnL.
+    fn record_sell(&mut self, plan: &SellPlan, sold: &SoldLeg) {
+        let Some(pos) = self.positions.get_mut(&plan.pair_key) else {
+            return;
+        };
+        let kept = 1.0 - plan.sold_fraction();
+        pos.realized_bnb += sell_pnl(plan, Some(sold));
+        pos.cost_basis_bnb *= kept;
+        pos.remaining_fraction *= kept;
+        pos.tokens_held = pos.tokens_held.saturating_sub(sold.tokens);
+    }
+
+    /// Flip the freeze flag of a position; returns the new state.
+    fn toggle_freeze(&mut self, pair_key: &str) -> Option<bool> {
+        let pos = self.positions.get_mut(pair_key)?;
+        pos.frozen = !pos.frozen;
+        pos.frozen_hit = None;
+        let frozen = pos.frozen;
+        self.persist();
+        Some(frozen)
+    }
+
     fn remove_position(&mut self, pair_key: &str) -> bool {
         self.closing.remove(pair_key);
+        copytrade::forget_copied(pair_key);
         if let Some(pos) = self.positions.remove(pair_key) {
             self.do_not_rebuy.insert(pos.pair_address);
+            self.persist();
             true
         } else {
             false
@@ -548,81 +1276,67 @@ impl RealTrader {
     }
 
     fn block_rebuy(&mut self, pair_key: &str) {
-        self.do_not_rebuy.insert(pair_key.to_string());
+        if self.do_not_rebuy.insert(pair_key.to_string()) {
+            self.persist();
+        }
     }
 
     fn sell_decision(
         &mut self,
         pair_key: &str,
-        current_price: f64,
+        update: &PairUpdate,
         config_store: &ConfigStore,
     ) -> Option<SellPlan> {
         if self.closing.contains(pair_key) {
             return None;
         }
 
-        let pos = self.positions.get(pair_key)?;
+        let pos = self.positions.get_mut(pair_key)?;
         if pos.entry_price <= 0.0 {
             return None;
         }
+        let current_price = update.price;
+        if current_price > pos.peak_price {
+            pos.peak_price = current_price;
+        }
 
         let pnl_pct = ((current_price / pos.entry_price) - 1.0) * 100.0;
 
-        let tp_enabled = config_store
-            .get("tp_enabled")
-            .map(|v| v.as_str() == "true")
-            .unwrap_or(false);
-        let tp_pct = if tp_enabled {
-            config_store
-                .get("tp_pct")
-                .and_then(|v| v.parse::<f64>().ok())
-        } else {
-            None
+        let ladder = tp_ladder_cfg(config_store);
+        let exit = exit_params_cfg(config_store, &ladder);
+        let view = PositionView {
+            entry_price: pos.entry_price,
+            peak_price: pos.peak_price,
+            held_secs: pos.opened_at.elapsed().as_secs(),
+            tp_tiers_hit: pos.tp_tiers_hit,
         };
-
-        let sl_enabled = config_store
-            .get("sl_enabled")
-            .map(|v| v.as_str() == "true")
-            .unwrap_or(false);
-        let sl_pct = if sl_enabled {
-            config_store
-                .get("sl_pct")
-                .and_then(|v| v.parse::<f64>().ok())
-        } else {
-            None
+        let Intent::Sell { fraction, reason } =
+            strategy_cfg(config_store).on_update(pair_key, update, Some(&view), &exit)
+        else {
+            return None;
         };
-
-        let mut trigger: Option<SellTrigger> = None;
-        if let Some(tp) = tp_pct {
-            if pnl_pct >= tp {
-                trigger = Some(SellTrigger::TakeProfit(tp));
-            }
-        }
-        if trigger.is_none() {
-            if let Some(sl) = sl_pct {
-                if pnl_pct <= -sl {
-                    trigger = Some(SellTrigger::StopLoss(sl));
-                }
-            }
-        }
-
-        if trigger.is_none() {
-            let max_hold_secs = config_store
-                .get("max_hold_secs")
-                .and_then(|v| v.parse::<u64>().ok())
-                .unwrap_or(0);
-            if max_hold_secs > 0 && pos.opened_at.elapsed().as_secs() >= max_hold_secs {
-                let max_hold_pnl_enabled = config_store
-                    .get("max_hold_pnl")
-                    .map(|v| v.as_str() == "true")
-                    .unwrap_or(true);
-                if !max_hold_pnl_enabled || pnl_pct <= 50.0 {
-                    trigger = Some(SellTrigger::MaxHold(max_hold_secs));
-                }
+        // Tiers are shares of the original size; manual partials shrink what is held
+        let fraction = match reason {
+            ExitReason::TakeProfitTier(idx, _) => ladder
+                .get(idx)
+                .map(|(_, sell_pct)| (sell_pct / 100.0 / pos.remaining_fraction).min(1.0))
+                .unwrap_or(fraction),
+            _ => fraction,
+        };
+        let percent_points = ((fraction * 100.0).round() as u32).clamp(1, 100);
+        let tr = SellTrigger::from(reason);
+        if pos.frozen {
+            // Log once per exit reason while it keeps triggering
+            let what = describe_trigger(&tr);
+            if pos.frozen_hit.as_deref() != Some(what.as_str()) {
+                save_log_to_file(&format!(
+                    "[trade] FROZEN {} ({}) would sell {}% @ {:+.2}% reason={}",
+                    pos.base_symbol, pair_key, percent_points, pnl_pct, what
+                ));
+                pos.frozen_hit = Some(what);
             }
+            return None;
         }
-
-        let tr = trigger?;
         // reserve close BEFORE returning plan (prevents double-sells)
         self.closing.insert(pair_key.to_string());
 
@@ -631,32 +1345,329 @@ impl RealTrader {
             dex_type: pos.dex_type,
             token_out: pos.token_out,
             base_symbol: pos.base_symbol.clone(),
-            spent_bnb: pos.buy_amount_bnb,
+            cost_basis_bnb: pos.cost_basis_bnb,
+            realized_bnb: pos.realized_bnb,
             pnl_pct,
             trigger: tr,
-            percent_points: 100,
+            percent_points,
+            fee: pos.fee,
+            via: pos.via,
         })
     }
 }
 
-fn describe_trigger(trigger: &SellTrigger) -> String {
-    match trigger {
-        SellTrigger::TakeProfit(tp) => format!("TP {:.2}%", tp),
-        SellTrigger::StopLoss(sl) => format!("SL -{:.2}%", sl),
-        SellTrigger::MaxHold(secs) => format!("Max hold {}s", secs),
-        SellTrigger::Manual => "Manual".to_string(),
-    }
-}
-
-async fn execute_sell_plan<P>(
-    plan: &SellPlan,
-    provider: P,
-    config_store: &ConfigStore,
-) -> Result<()>
+/// Load the persisted ledger into `REAL_TRADER`, dropping entries whose token
+/// balance is gone (sold elsewhere or while offline). Entries whose balance
+/// cannot be read are kept as cached. Returns restored count.
+pub async fn restore_real_positions<P>(provider: P) -> usize
 where
     P: Provider + Clone + WalletProvider + Send + Sync + 'static,
 {
-    let from = provider.default_signer_address();
+    let ledger = match load_positions_cache() {
+        Ok(l) => l,
+        Err(e) => {
+            // Keep the file out of reach of the next save; it may still hold open positions
+            let moved = match quarantine_positions_cache() {
+                Ok(aside) => format!("moved to {}", aside.display()),
+                Err(err) => format!("could not move it aside: {}", err),
+            };
+            let warning = format!(
+                "Positions cache unreadable ({}); {}. Open real positions are NOT restored.",
+                e, moved
+            );
+            save_log_to_file(&format!("[startup] {}", warning));
+            log!(cc::RED, "{}", warning);
+            return 0;
+        }
+    };
+    let from = provider.default_signer_address();
+    let mut trader = REAL_TRADER.lock().await;
+    for key in ledger.do_not_rebuy.iter() {
+        trader.do_not_rebuy.insert(pair_key_str(key));
+    }
+    for cached in ledger.positions.iter() {
+        let Some(mut pos) = RealPosition::from_cached(cached) else {
+            save_log_to_file(&format!(
+                "[startup] skip cached position {}: bad token address {}",
+                cached.pair_address, cached.token_out
+            ));
+            continue;
+        };
+        let bal = match balance_of(provider.clone(), pos.token_out, from).await {
+            Ok(bal) => bal,
+            Err(e) => {
+                // a failed RPC must not drop an open position: keep it as cached
+                save_log_to_file(&format!(
+                    "[startup] kept cached position {} ({}) unchecked: balance read failed: {}",
+                    pos.base_symbol, pos.pair_address, e
+                ));
+                if let Some(wallet) = pos.copied_from {
+                    copytrade::mark_copied(&pos.pair_address, wallet);
+                }
+                trader.positions.insert(pos.pair_address.clone(), pos);
+                continue;
+            }
+        };
+        if bal.is_zero() {
+            save_log_to_file(&format!(
+                "[startup] drop cached position {} ({}): token balance=0",
+                pos.base_symbol, pos.pair_address
+            ));
+            trader.do_not_rebuy.insert(pos.pair_address.clone());
+            continue;
+        }
+        if pos.tokens_held.is_zero() {
+            pos.tokens_held = bal;
+        }
+        if let Some(wallet) = pos.copied_from {
+            copytrade::mark_copied(&pos.pair_address, wallet);
+        }
+        save_log_to_file(&format!(
+            "[startup] restored {} ({}) entry:{:.8} size:{:.6} BNB remaining:{:.0}% realized:{:+.6} BNB balance={}",
+            pos.base_symbol,
+            pos.pair_address,
+            pos.entry_price,
+            pos.buy_amount_bnb,
+            pos.remaining_fraction * 100.0,
+            pos.realized_bnb,
+            bal
+        ));
+        trader.positions.insert(pos.pair_address.clone(), pos);
+    }
+    trader.persist();
+    trader.positions.len()
+}
+
+/// Toggle the freeze flag of a real position. Frozen positions are never
+/// auto-sold. Returns the new state, or None without a real position.
+pub async fn toggle_real_freeze(pair_address: &str) -> Option<bool> {
+    let mut trader = REAL_TRADER.lock().await;
+    trader.toggle_freeze(&pair_key_str(pair_address))
+}
+
+/// Pools of held V2/V3 real positions as (pool, is V3, token held).
+pub async fn real_position_pools() -> Vec<(Address, bool, Address)> {
+    let trader = REAL_TRADER.lock().await;
+    trader
+        .positions
+        .values()
+        .filter_map(|p| {
+            let pool = p.pair_address.parse::<Address>().ok()?;
+            match p.dex_type {
+                DexType::V2 => Some((pool, false, p.token_out)),
+                DexType::V3 => Some((pool, true, p.token_out)),
+                DexType::FourMeme => None,
+            }
+        })
+        .collect()
+}
+
+/// Token addresses currently held as managed real positions.
+pub async fn real_position_tokens() -> HashSet<Address> {
+    let trader = REAL_TRADER.lock().await;
+    trader.positions.values().map(|p| p.token_out).collect()
+}
+
+/// Mirror restored real positions into the SimEngine so they show up in Results.
+pub async fn mirror_real_positions(
+    sim_engine: &Arc<tokio::sync::Mutex<SimEngine>>,
+    config_store: &ConfigStore,
+) {
+    let tp_enabled = config_store
+        .get("tp_enabled")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    let sl_enabled = config_store
+        .get("sl_enabled")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    let tp_pct = if tp_enabled {
+        config_store
+            .get("tp_pct")
+            .and_then(|v| v.parse::<f64>().ok())
+    } else {
+        None
+    };
+    let sl_pct = if sl_enabled {
+        config_store
+            .get("sl_pct")
+            .and_then(|v| v.parse::<f64>().ok())
+    } else {
+        None
+    };
+    let ladder = tp_ladder_cfg(config_store);
+    let positions: Vec<RealPosition> = {
+        let mut trader = REAL_TRADER.lock().await;
+        // Ledgers written before partial tracking only know the ladder tiers sold
+        for pos in trader.positions.values_mut() {
+            if pos.remaining_fraction >= 1.0 && pos.tp_tiers_hit > 0 {
+                let sold_pct: f64 = ladder.iter().take(pos.tp_tiers_hit).map(|(_, s)| s).sum();
+                pos.remaining_fraction = (1.0 - sold_pct / 100.0).max(0.0);
+                pos.cost_basis_bnb = pos.buy_amount_bnb * pos.remaining_fraction;
+            }
+        }
+        trader.positions.values().cloned().collect()
+    };
+    let mut se = sim_engine.lock().await;
+    for pos in positions {
+        se.add_real_position(
+            pos.pair_address.clone(),
+            pos.dex_type,
+            pos.base_symbol.clone(),
+            pos.quote_symbol.clone(),
+            pos.entry_price,
+            pos.buy_amount_bnb,
+            tp_pct,
+            sl_pct,
+            None,
+        );
+        // Carry over what partial sells already took off the position
+        se.restore_real_progress(
+            &pos.pair_address,
+            pos.remaining_fraction,
+            pos.realized_bnb,
+            pos.tp_tiers_hit,
+        );
+        se.set_freeze(&pos.pair_address, pos.frozen);
+    }
+}
+
+fn describe_trigger(trigger: &SellTrigger) -> String {
+    match trigger {
+        SellTrigger::TakeProfit(tp) => format!("TP {:.2}%", tp),
+        SellTrigger::TakeProfitTier(idx, at) => format!("TP tier {} @ +{:.2}%", idx + 1, at),
+        SellTrigger::StopLoss(sl) => format!("SL -{:.2}%", sl),
+        SellTrigger::Trailing(trail) => format!("Trailing -{:.2}% from peak", trail),
+        SellTrigger::MaxHold(secs) => format!("Max hold {}s", secs),
+        SellTrigger::Manual => "Manual".to_string(),
+        SellTrigger::LiquidityPull(pct) => format!("Liquidity pull -{:.1}%", pct),
+        SellTrigger::DevSold(pct) => format!("Dev sold {:.0}%", pct),
+        SellTrigger::CopyExit(wallet) => format!("Copy exit ({} sold)", short_wallet(*wallet)),
+    }
+}
+
+/// What a landed sell moved for the wallet.
+#[derive(Clone, Copy, Debug)]
+struct SoldLeg {
+    /// Tokens sold, raw units
+    tokens: U256,
+    /// BNB received; None when the receipt does not show it
+    received_bnb: Option<f64>,
+}
+
+/// Result of running a sell plan.
+#[derive(Clone, Copy, Debug)]
+enum SellOutcome {
+    /// Nothing was sold: no tokens held or the sold share rounds to 0
+    Skipped,
+    Landed(SoldLeg),
+}
+
+impl SellOutcome {
+    fn sold(&self) -> Option<&SoldLeg> {
+        match self {
+            SellOutcome::Skipped => None,
+            SellOutcome::Landed(sold) => Some(sold),
+        }
+    }
+}
+
+/// Tokens sold and BNB received by a landed sell, read from its receipt.
+/// Tokens fall back to the balance drop when the receipt lacks them.
+async fn sell_fill<P: Provider>(
+    provider: &P,
+    tx: B256,
+    from: Address,
+    token: Address,
+    balance_drop: U256,
+) -> SoldLeg {
+    refresh_funds(provider, from).await;
+    let Some(fill) = fills::fetch_fill(provider, tx, from, token, TxSide::Sell).await else {
+        return SoldLeg {
+            tokens: balance_drop,
+            received_bnb: None,
+        };
+    };
+    save_log_to_file(&format!(
+        "[trade] FILL sell {:#x} tokens={} received={} fee={:.6} gas={:.6}",
+        token,
+        fill.tokens,
+        fill.bnb
+            .map(|b| format!("{:.6} BNB", b))
+            .unwrap_or_else(|| "?".to_string()),
+        fill.fee_bnb,
+        fill.gas_bnb
+    ));
+    SoldLeg {
+        tokens: Some(fill.tokens)
+            .filter(|t| !t.is_zero())
+            .unwrap_or(balance_drop),
+        received_bnb: fill.bnb,
+    }
+}
+
+impl SellPlan {
+    /// Share of the held size this plan sells.
+    fn sold_fraction(&self) -> f64 {
+        self.percent_points.min(100) as f64 / 100.0
+    }
+}
+
+/// Realized PnL of a landed sell from its fill: BNB received minus the sold
+/// share of the cost basis.
+fn filled_pnl(plan: &SellPlan, sold: Option<&SoldLeg>) -> Option<f64> {
+    sold.and_then(|s| s.received_bnb)
+        .map(|r| r - plan.cost_basis_bnb * plan.sold_fraction())
+}
+
+/// Realized PnL of a landed sell at its fill, or estimated from the plan
+/// (cost basis x PnL % x sold share) when the fill is unknown.
+fn sell_pnl(plan: &SellPlan, sold: Option<&SoldLeg>) -> f64 {
+    filled_pnl(plan, sold)
+        .unwrap_or_else(|| plan.cost_basis_bnb * plan.pnl_pct / 100.0 * plan.sold_fraction())
+}
+
+/// Feed a landed sell into the risk governor. A closing sell is judged on
+/// the position's total PnL, so a runner that gave back part of a banked
+/// partial does not count as a loss.
+fn govern_sell(plan: &SellPlan, sold: &SoldLeg, config_store: &ConfigStore) {
+    let pnl = sell_pnl(plan, Some(sold));
+    let position_pnl = (plan.percent_points >= 100).then_some(plan.realized_bnb + pnl);
+    governor::record_pnl("trade", pnl, position_pnl, config_store);
+}
+
+/// Book a mirrored full close at its filled PnL; returns the position's total PnL.
+fn mirror_close_pnl(se: &mut SimEngine, pos: &SimPosition, filled: Option<f64>) -> f64 {
+    match filled {
+        Some(actual) => {
+            let marked = pos.pnl_wbnb - pos.realized_pnl_wbnb;
+            se.book_real_pnl(&pos.pair_address, marked, actual);
+            pos.realized_pnl_wbnb + actual
+        }
+        None => pos.pnl_wbnb,
+    }
+}
+
+/// Book a mirrored partial sell at its filled PnL; returns the realized PnL.
+fn mirror_partial_pnl(se: &mut SimEngine, pair_key: &str, marked: f64, filled: Option<f64>) -> f64 {
+    match filled {
+        Some(actual) => {
+            se.book_real_pnl(pair_key, marked, actual);
+            actual
+        }
+        None => marked,
+    }
+}
+
+async fn execute_sell_plan<P>(
+    plan: &SellPlan,
+    provider: P,
+    config_store: &ConfigStore,
+) -> Result<SellOutcome>
+where
+    P: Provider + Clone + WalletProvider + Send + Sync + 'static,
+{
+    let from = provider.default_signer_address();
     let bal_before = safe_balance_of(provider.clone(), plan.token_out, from).await;
     if bal_before.is_zero() {
         save_log_to_file(&format!(
@@ -665,10 +1676,31 @@ where
             plan.pair_key,
             describe_trigger(&plan.trigger)
         ));
-        return Ok(());
+        return Ok(SellOutcome::Skipped);
     }
     let percent_bps: u16 = (plan.percent_points.min(100) as u16).saturating_mul(100);
-    let gas_price_wei = gas_price_wei_u128(config_store);
+    // Emergency exits start at the slippage cap and outbid the regular gas price
+    let emergency = matches!(
+        plan.trigger,
+        SellTrigger::LiquidityPull(_) | SellTrigger::DevSold(_)
+    );
+    let slippage_cap_bps = sell_slippage_cap_bps(config_store);
+    let slippage_bps = if emergency {
+        slippage_cap_bps
+    } else {
+        sell_slippage_bps(config_store)
+    };
+    let sell_label = format!("sell {} ({})", plan.base_symbol, plan.pair_key);
+    let route = Route {
+        token: plan.token_out,
+        via: plan.via,
+        fee: plan.fee,
+    };
+    let gas_price_wei = if emergency {
+        gas::emergency_gas_price_wei(&provider, config_store).await
+    } else {
+        gas::gas_price_wei(&provider, config_store).await
+    };
     let gas_price_wei_override = U256::from(gas_price_wei);
     let amount_in = bal_before * U256::from(percent_bps) / U256::from(10_000u64);
     if amount_in.is_zero() {
@@ -678,12 +1710,12 @@ where
             plan.pair_key,
             describe_trigger(&plan.trigger)
         ));
-        return Ok(());
+        return Ok(SellOutcome::Skipped);
     }
+    let sold;
     match plan.dex_type {
         DexType::V2 => {
             let pancake = PancakeV2::new(provider.clone());
-            let token_in_s = format!("{:#x}", plan.token_out);
             ensure_sell_allowance(
                 provider.clone(),
                 DexType::V2,
@@ -692,33 +1724,39 @@ where
                 gas_price_wei,
             )
             .await?;
-            let (_quoted, tx) = routy_v2::sell_pct_to_wbnb(
-                &pancake,
+            let (_quoted, tx) = sell_with_slippage_escalation(
                 from,
-                token_in_s.as_str(),
-                percent_bps,
-                Some(gas_price_wei),
+                &sell_label,
+                slippage_bps,
+                slippage_cap_bps,
+                |bps| {
+                    routy_v2::sell_pct_via(
+                        &pancake,
+                        from,
+                        &route,
+                        percent_bps,
+                        bps,
+                        Some(gas_price_wei),
+                    )
+                },
             )
             .await?;
-            let bal_after = safe_balance_of(provider.clone(), plan.token_out, from).await;
-            let final_after = if bal_after < bal_before {
-                Some(bal_after)
-            } else {
-                wait_for_balance_drop(provider.clone(), plan.token_out, from, bal_before, 4, 400)
-                    .await
-            };
-            let _bal_after = match final_after {
+            let final_after =
+                sold_balance(provider.clone(), tx, plan.token_out, from, bal_before, 4).await;
+            let bal_after = match final_after {
                 Some(b) => b,
                 None => {
                     save_log_to_file(&format!(
-                        "[trade] ✗ V2 SELL {} ({}) tx={} but token balance did not decrease (before={} after={})",
+                        "[trade] ✗ V2 SELL {} ({}) tx={} not confirmed and token balance did not decrease (before={} status={:?})",
                         plan.base_symbol,
                         plan.pair_key,
                         tx,
                         bal_before,
-                        bal_after
+                        tx_status(tx)
+                    ));
+                    return Err(anyhow!(
+                        "sell tx not confirmed and token balance did not decrease"
                     ));
-                    return Err(anyhow!("sell tx mined but token balance did not decrease"));
                 }
             };
             save_log_to_file(&format!(
@@ -727,14 +1765,15 @@ where
                 plan.pair_key,
                 plan.pnl_pct,
                 describe_trigger(&plan.trigger),
-                plan.spent_bnb,
+                plan.cost_basis_bnb,
                 plan.percent_points,
             ));
             save_log_to_file(&format!("[trade] tx={}", tx));
+            let drop = bal_before.saturating_sub(bal_after);
+            sold = sell_fill(&provider, tx, from, plan.token_out, drop).await;
         }
         DexType::V3 => {
             let pancake = PancakeV3::new(provider.clone());
-            let token_in_s = format!("{:#x}", plan.token_out);
             ensure_sell_allowance(
                 provider.clone(),
                 DexType::V3,
@@ -743,33 +1782,39 @@ where
                 gas_price_wei,
             )
             .await?;
-            let (_quoted, tx) = routy_v3::sell_pct_to_wbnb(
-                &pancake,
+            let (_quoted, tx) = sell_with_slippage_escalation(
                 from,
-                token_in_s.as_str(),
-                percent_bps,
-                Some(gas_price_wei),
+                &sell_label,
+                slippage_bps,
+                slippage_cap_bps,
+                |bps| {
+                    routy_v3::sell_pct_via(
+                        &pancake,
+                        from,
+                        &route,
+                        percent_bps,
+                        bps,
+                        Some(gas_price_wei),
+                    )
+                },
             )
             .await?;
-            let bal_after = safe_balance_of(provider.clone(), plan.token_out, from).await;
-            let final_after = if bal_after < bal_before {
-                Some(bal_after)
-            } else {
-                wait_for_balance_drop(provider.clone(), plan.token_out, from, bal_before, 4, 400)
-                    .await
-            };
-            let _bal_after = match final_after {
+            let final_after =
+                sold_balance(provider.clone(), tx, plan.token_out, from, bal_before, 4).await;
+            let bal_after = match final_after {
                 Some(b) => b,
                 None => {
                     save_log_to_file(&format!(
-                        "[trade] ✗ V3 SELL {} ({}) tx={} but token balance did not decrease (before={} after={})",
+                        "[trade] ✗ V3 SELL {} ({}) tx={} not confirmed and token balance did not decrease (before={} status={:?})",
                         plan.base_symbol,
                         plan.pair_key,
                         tx,
                         bal_before,
-                        bal_after
+                        tx_status(tx)
+                    ));
+                    return Err(anyhow!(
+                        "sell tx not confirmed and token balance did not decrease"
                     ));
-                    return Err(anyhow!("sell tx mined but token balance did not decrease"));
                 }
             };
             save_log_to_file(&format!(
@@ -778,10 +1823,12 @@ where
                 plan.pair_key,
                 plan.pnl_pct,
                 describe_trigger(&plan.trigger),
-                plan.spent_bnb,
+                plan.cost_basis_bnb,
                 plan.percent_points,
             ));
             save_log_to_file(&format!("[trade] tx={}", tx));
+            let drop = bal_before.saturating_sub(bal_after);
+            sold = sell_fill(&provider, tx, from, plan.token_out, drop).await;
         }
         DexType::FourMeme => {
             let router = FmRouter::new(provider.clone());
@@ -793,41 +1840,49 @@ where
                 gas_price_wei,
             )
             .await?;
-            let sell_call = router.sell_percent_pct(
+            let (_est, tx) = sell_with_slippage_escalation(
                 from,
-                plan.token_out,
-                plan.percent_points.max(1),
-                Some(gas_price_wei_override),
-            );
-            let sell_res = tokio::time::timeout(Duration::from_secs(20), sell_call)
-                .await
-                .map_err(|_| {
-                    anyhow!(
-                        "sell timeout for token {:#x} pct {}",
+                &sell_label,
+                slippage_bps,
+                slippage_cap_bps,
+                |bps| {
+                    let sell_call = router.sell_percent_pct(
+                        from,
                         plan.token_out,
-                        plan.percent_points
-                    )
-                })??;
-            let (_est, tx) = sell_res;
-            let bal_after = safe_balance_of(provider.clone(), plan.token_out, from).await;
-            let final_after = if bal_after < bal_before {
-                Some(bal_after)
-            } else {
-                wait_for_balance_drop(provider.clone(), plan.token_out, from, bal_before, 6, 400)
-                    .await
-            };
-            let _bal_after = match final_after {
+                        plan.percent_points.max(1),
+                        bps,
+                        Some(gas_price_wei_override),
+                    );
+                    async move {
+                        tokio::time::timeout(Duration::from_secs(20), sell_call)
+                            .await
+                            .map_err(|_| {
+                                anyhow!(
+                                    "sell timeout for token {:#x} pct {}",
+                                    plan.token_out,
+                                    plan.percent_points
+                                )
+                            })?
+                    }
+                },
+            )
+            .await?;
+            let final_after =
+                sold_balance(provider.clone(), tx, plan.token_out, from, bal_before, 6).await;
+            let bal_after = match final_after {
                 Some(b) => b,
                 None => {
                     save_log_to_file(&format!(
-                        "[trade] ✗ FM SELL {} ({}) tx={:?} but token balance did not decrease (before={} after={})",
+                        "[trade] ✗ FM SELL {} ({}) tx={:?} not confirmed and token balance did not decrease (before={} status={:?})",
                         plan.base_symbol,
                         plan.pair_key,
                         tx,
                         bal_before,
-                        bal_after
+                        tx_status(tx)
+                    ));
+                    return Err(anyhow!(
+                        "sell tx not confirmed and token balance did not decrease"
                     ));
-                    return Err(anyhow!("sell tx mined but token balance did not decrease"));
                 }
             };
             save_log_to_file(&format!(
@@ -836,13 +1891,15 @@ where
                 plan.pair_key,
                 plan.pnl_pct,
                 describe_trigger(&plan.trigger),
-                plan.spent_bnb,
+                plan.cost_basis_bnb,
                 plan.percent_points,
             ));
             save_log_to_file(&format!("[trade] tx={:?}", tx));
+            let drop = bal_before.saturating_sub(bal_after);
+            sold = sell_fill(&provider, tx, from, plan.token_out, drop).await;
         }
     }
-    Ok(())
+    Ok(SellOutcome::Landed(sold))
 }
 
 pub async fn manual_sell<P>(
@@ -853,6 +1910,120 @@ pub async fn manual_sell<P>(
     sold_pairs: Option<&Arc<RwLock<HashSet<String>>>>,
     config_store: &ConfigStore,
 ) -> Result<bool>
+where
+    P: Provider + Clone + WalletProvider + Send + Sync + 'static,
+{
+    sell_position(
+        pair_address,
+        percent_points,
+        SellTrigger::Manual,
+        provider,
+        sim_engine,
+        sold_pairs,
+        config_store,
+    )
+    .await
+}
+
+/// Sell the whole real position on `pair_address` right away because its pool
+/// lost `removed_pct` of its liquidity. Frozen positions are only logged.
+pub async fn emergency_exit<P>(
+    pair_address: &str,
+    removed_pct: f64,
+    provider: P,
+    sim_engine: &Arc<tokio::sync::Mutex<SimEngine>>,
+    sold_pairs: Option<&Arc<RwLock<HashSet<String>>>>,
+    config_store: &ConfigStore,
+) -> Result<bool>
+where
+    P: Provider + Clone + WalletProvider + Send + Sync + 'static,
+{
+    let pair_key = pair_key_str(pair_address);
+    {
+        let trader = REAL_TRADER.lock().await;
+        if let Some(pos) = trader.positions.get(&pair_key).filter(|p| p.frozen) {
+            save_log_to_file(&format!(
+                "[trade] FROZEN {} ({}) would emergency-sell: liquidity pull -{:.1}%",
+                pos.base_symbol, pair_key, removed_pct
+            ));
+            return Ok(false);
+        }
+    }
+    save_log_to_file(&format!(
+        "[trade] ⚠ EMERGENCY EXIT {}: liquidity pull -{:.1}%",
+        pair_key, removed_pct
+    ));
+    sell_position(
+        &pair_key,
+        100,
+        SellTrigger::LiquidityPull(removed_pct),
+        provider,
+        sim_engine,
+        sold_pairs,
+        config_store,
+    )
+    .await
+}
+
+/// Sell the whole real position on `pair_address` because `wallet`, whose buy
+/// it copied, sold. Frozen positions and positions copying another wallet are
+/// left alone.
+pub async fn copy_exit<P>(
+    pair_address: &str,
+    wallet: Address,
+    provider: P,
+    sim_engine: &Arc<tokio::sync::Mutex<SimEngine>>,
+    sold_pairs: Option<&Arc<RwLock<HashSet<String>>>>,
+    config_store: &ConfigStore,
+) -> Result<bool>
+where
+    P: Provider + Clone + WalletProvider + Send + Sync + 'static,
+{
+    let pair_key = pair_key_str(pair_address);
+    {
+        let trader = REAL_TRADER.lock().await;
+        let Some(pos) = trader.positions.get(&pair_key) else {
+            return Ok(false);
+        };
+        if pos.copied_from != Some(wallet) {
+            return Ok(false);
+        }
+        if pos.frozen {
+            save_log_to_file(&format!(
+                "[trade] FROZEN {} ({}) would copy-exit: {} sold",
+                pos.base_symbol,
+                pair_key,
+                short_wallet(wallet)
+            ));
+            return Ok(false);
+        }
+    }
+    save_log_to_file(&format!(
+        "[trade] 👥 COPY EXIT {}: {} sold",
+        pair_key,
+        short_wallet(wallet)
+    ));
+    sell_position(
+        &pair_key,
+        100,
+        SellTrigger::CopyExit(wallet),
+        provider,
+        sim_engine,
+        sold_pairs,
+        config_store,
+    )
+    .await
+}
+
+async fn sell_position<P>(
+    pair_address: &str,
+    percent_points: u32,
+    trigger: SellTrigger,
+    provider: P,
+    sim_engine: &Arc<tokio::sync::Mutex<SimEngine>>,
+    sold_pairs: Option<&Arc<RwLock<HashSet<String>>>>,
+    config_store: &ConfigStore,
+) -> Result<bool>
 where
     P: Provider + Clone + WalletProvider + Send + Sync + 'static,
 {
@@ -864,7 +2035,8 @@ where
 
         if !trader.positions.contains_key(&pair_address) {
             save_log_to_file(&format!(
-                "[trade] manual sell ignored: no real position {}",
+                "[trade] sell ignored ({}): no real position {}",
+                describe_trigger(&trigger),
                 pair_address
             ));
             return Ok(false);
@@ -872,7 +2044,8 @@ where
 
         if pct >= 100 && !trader.reserve_close(&pair_address) {
             save_log_to_file(&format!(
-                "[trade] manual sell ignored: already closing {}",
+                "[trade] sell ignored ({}): already closing {}",
+                describe_trigger(&trigger),
                 pair_address
             ));
             return Ok(false);
@@ -891,14 +2064,20 @@ where
             dex_type: pos.dex_type,
             token_out: pos.token_out,
             base_symbol: pos.base_symbol.clone(),
-            spent_bnb: pos.buy_amount_bnb,
+            cost_basis_bnb: pos.cost_basis_bnb,
+            realized_bnb: pos.realized_bnb,
             pnl_pct,
-            trigger: SellTrigger::Manual,
+            trigger,
             percent_points: pct,
+            fee: pos.fee,
+            via: pos.via,
         }
     };
 
     let exec_res = execute_sell_plan(&plan, provider.clone(), config_store).await;
+    if let Err(e) = &exec_res {
+        nonce::on_send_error(provider.default_signer_address(), &e.to_string());
+    }
 
     if pct >= 100 {
         let mut trader = REAL_TRADER.lock().await;
@@ -917,15 +2096,33 @@ where
         }
     }
 
-    exec_res?;
+    let outcome = exec_res?;
+    let sold = outcome.sold();
+    if let Some(sold) = sold {
+        govern_sell(&plan, sold, config_store);
+    }
+    let filled = filled_pnl(&plan, sold);
+    if pct < 100 {
+        // Nothing was sold: the position and its mirror stay as they are
+        let Some(sold) = sold else {
+            return Ok(false);
+        };
+        let mut trader = REAL_TRADER.lock().await;
+        trader.record_sell(&plan, sold);
+        trader.persist();
+    }
 
     {
         let mut se = sim_engine.lock().await;
-        if pct >= 100 {
+        if pct >= 100 && sold.is_none() {
+            // Nothing held to sell: drop the mirror without booking a PnL
+            se.remove_position(&pair_address);
+        } else if pct >= 100 {
             if let Some(pos) = se.take_position(&pair_address) {
+                let pnl = mirror_close_pnl(&mut se, &pos, filled);
                 save_log_to_file(&format!(
                     "[trade] mirror close {} ({}) PnL: {:+.6} WBNB",
-                    pos.base_token, pos.pair_address, pos.pnl_wbnb
+                    pos.base_token, pos.pair_address, pnl
                 ));
                 if let Some(sp) = sold_pairs {
                     let mut s = sp.write().await;
@@ -934,7 +2131,8 @@ where
             }
         } else {
             let fraction = (pct as f64) / 100.0;
-            if let Some((realized, closed)) = se.partial_take(&pair_address, fraction) {
+            if let Some((marked, closed)) = se.partial_take(&pair_address, fraction) {
+                let realized = mirror_partial_pnl(&mut se, &pair_address, marked, filled);
                 save_log_to_file(&format!(
                     "[trade] mirror partial {}% ({}) realized: {:+.6} WBNB{}",
                     pct,
@@ -1037,177 +2235,125 @@ pub async fn auto_trade(
     let src = detect_source(&l1);
 
     // Trade decision only (state updates moved to pair_streams::update_pairs_state)
-    if sim_mode {
-        if let Some(current_price) = price_opt {
-            let pair_addr_str = format!("{:#x}", pair_info.pair);
-
-            // Only consider buying if we don't already have position/pending
-            if !sim_engine.has_position_or_pending(&pair_addr_str) {
-                let enabled = config_store
-                    .get("enabled")
-                    .map(|v| v.as_str() == "true")
-                    .unwrap_or(false);
-
-                if enabled && current_price > 0.0 {
-                    let avoid_cn = config_store
-                        .get("avoid_chinese")
-                        .map(|v| v.as_str() == "true")
-                        .unwrap_or(false);
-                    if avoid_cn
-                        && (contains_cjk(&pair_info.symbol_base)
-                            || contains_cjk(&pair_info.symbol_quote))
-                    {
-                        return Ok(());
-                    }
-                    if should_avoid_name(&pair_info.symbol_base)
-                        || should_avoid_name(&pair_info.symbol_quote)
-                    {
-                        return Ok(());
-                    }
-                    let freshness_secs = config_store
-                        .get("freshness_secs")
-                        .and_then(|v| v.parse::<u64>().ok())
-                        .unwrap_or(30);
-                    let min_pnl_pct = config_store
-                        .get("min_pnl_pct")
-                        .and_then(|v| v.parse::<f64>().ok())
-                        .unwrap_or(100.0);
-
-                    // 1. Check if quote token is accepted
-                    let accepted_quotes_str = config_store
-                        .get("accepted_quotes")
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|| "BNB,CAKE,USDT,USD1,ASTER,WBNB".to_string());
-                    let accepted_quotes: Vec<String> = accepted_quotes_str
-                        .split(',')
-                        .map(|s| s.trim().to_uppercase())
-                        .collect();
-
-                    // quote check
-                    if !accepted_quotes.contains(&pair_info.symbol_quote.to_uppercase()) {
-                        return Ok(());
-                    }
-
-                    // liquidity check
-                    if src != PairSource::FourMeme {
-                        let min_liquidity = config_store
-                            .get("min_liquidity")
-                            .and_then(|v| v.parse::<f64>().ok())
-                            .unwrap_or(1000.0);
-                        let liq_threshold = min_liquidity.max(5.0);
-
-                        if let Some(liq) = pair_info.liquidity_usd {
-                            if liq < liq_threshold {
-                                save_log_to_file(&format!(
-                                    "[sim] REJECTED {}: Liq ${:.0} < min ${:.0}",
-                                    pair_info.symbol_base, liq, liq_threshold
-                                ));
-                                return Ok(());
-                            }
-                        } else {
-                            return Ok(()); // No liquidity data
-                        }
-                    }
+    if !sim_mode {
+        return Ok(());
+    }
+    let Some(current_price) = price_opt else {
+        return Ok(());
+    };
+    let pair_addr_str = format!("{:#x}", pair_info.pair);
 
-                    // minimum buys check
-                    let min_buys = config_store
-                        .get("min_buys")
-                        .and_then(|v| v.parse::<u32>().ok())
-                        .unwrap_or(3);
+    // Only consider buying if we don't already have position/pending
+    if sim_engine.has_position_or_pending(&pair_addr_str) || current_price <= 0.0 {
+        return Ok(());
+    }
 
-                    if buy_count < min_buys {
-                        save_log_to_file(&format!(
-                            "[sim] WAITING {}: {} buys < min {}",
-                            pair_info.symbol_base, buy_count, min_buys
-                        ));
-                        return Ok(());
-                    }
+    // A watched wallet's buy queued this entry: market filters and strategy are skipped
+    let copy = due_copy_entry(config_store, pair_info.pair);
+
+    // Safety reports are produced in the background; wait for them here
+    let ctx = EntryCtx {
+        mode: "sim",
+        src,
+        pair_info: &pair_info,
+        pair_key: &pair_addr_str,
+        buy_count,
+        config_store,
+        tax: pair_info.tax,
+        risk: risk_scan_token(src, &pair_info).and_then(risk::cached_report),
+    };
+    let basic = match copy {
+        Some(_) => entry_filters::copy_basic(&ctx),
+        None => entry_filters::basic(&ctx),
+    };
+    if !basic.is_accept() || !entry_filters::safety(&ctx).is_accept() {
+        return Ok(());
+    }
+    let update = pair_update(current_price, buy_count, &pair_info);
+    let exit = exit_params_cfg(config_store, sim_engine.tp_ladder());
+    if copy.is_none()
+        && !entry_filters::strategy(&ctx, sim_engine.strategy(), &update, &exit).is_accept()
+    {
+        return Ok(());
+    }
 
-                    let dex_enabled = match src {
-                        PairSource::V2 => config_store
-                            .get("dexes")
-                            .map(|v| v.as_str().contains("v2"))
-                            .unwrap_or(false),
-                        PairSource::V3 => config_store
-                            .get("dexes")
-                            .map(|v| v.as_str().contains("v3"))
-                            .unwrap_or(false),
-                        PairSource::FourMeme => config_store
-                            .get("dexes")
-                            .map(|v| v.as_str().contains("fm"))
-                            .unwrap_or(false),
-                        _ => false,
-                    };
+    let (buy_amount, size_note) = match copy {
+        Some((wallet, bnb)) => (bnb, format!("copy {}", short_wallet(wallet))),
+        None => {
+            let size = sizing::position_size(
+                config_store,
+                &SizingInputs {
+                    spendable_bnb: sizing::cached_funds()
+                        .map(|f| f.spendable(wrap_ratio_pct_value(config_store))),
+                    liquidity_usd: pair_info.liquidity_usd,
+                    sl_pct: exit.sl_pct,
+                    realized_pnl_bnb: sim_engine.stats().total_pnl_realized,
+                },
+            );
+            (size.bnb, size.note)
+        }
+    };
+    if buy_amount <= 0.0 {
+        return Ok(());
+    }
 
-                    if dex_enabled {
-                        let buy_amount = config_store
-                            .get("buy_amount_wbnb")
-                            .and_then(|v| v.parse::<f64>().ok())
-                            .unwrap_or(0.00001);
-
-                        let tp_enabled = config_store
-                            .get("tp_enabled")
-                            .map(|v| v.as_str() == "true")
-                            .unwrap_or(false);
-                        let sl_enabled = config_store
-                            .get("sl_enabled")
-                            .map(|v| v.as_str() == "true")
-                            .unwrap_or(false);
-
-                        let tp_pct = if tp_enabled {
-                            config_store
-                                .get("tp_pct")
-                                .and_then(|v| v.parse::<f64>().ok())
-                        } else {
-                            None
-                        };
+    let tp_enabled = config_store
+        .get("tp_enabled")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    let sl_enabled = config_store
+        .get("sl_enabled")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+
+    let tp_pct = if tp_enabled {
+        config_store
+            .get("tp_pct")
+            .and_then(|v| v.parse::<f64>().ok())
+    } else {
+        None
+    };
 
-                        let sl_pct = if sl_enabled {
-                            config_store
-                                .get("sl_pct")
-                                .and_then(|v| v.parse::<f64>().ok())
-                        } else {
-                            None
-                        };
-
-                        let dex_type = match src {
-                            PairSource::V2 => DexType::V2,
-                            PairSource::V3 => DexType::V3,
-                            PairSource::FourMeme => DexType::FourMeme,
-                            _ => DexType::V2,
-                        };
-
-                        if let Some((pnl_pct, first_seen)) = pair_metrics(&pair_addr_str.as_str()) {
-                            if pnl_pct < min_pnl_pct
-                                && first_seen.elapsed().as_secs() > freshness_secs
-                            {
-                                return Ok(());
-                            }
-                        }
+    let sl_pct = if sl_enabled {
+        config_store
+            .get("sl_pct")
+            .and_then(|v| v.parse::<f64>().ok())
+    } else {
+        None
+    };
 
-                        let submitted = sim_engine.submit_buy(
-                            pair_addr_str.clone(),
-                            dex_type,
-                            pair_info.symbol_base.clone(),
-                            pair_info.symbol_quote.clone(),
-                            buy_amount,
-                            tp_pct,
-                            sl_pct,
-                        );
+    let dex_type = match src {
+        PairSource::V2 => DexType::V2,
+        PairSource::V3 => DexType::V3,
+        PairSource::FourMeme => DexType::FourMeme,
+        _ => DexType::V2,
+    };
 
-                        if submitted {
-                            save_log_to_file(&format!(
-                                "[sim] ✓ SUBMITTED {} @ {:.8} (buys:{} liq:${:.0})",
-                                pair_info.symbol_base,
-                                current_price,
-                                buy_count,
-                                pair_info.liquidity_usd.unwrap_or(0.0)
-                            ));
-                        }
-                    }
-                }
-            }
+    let submitted = sim_engine.submit_buy(
+        pair_addr_str.clone(),
+        dex_type,
+        pair_info.symbol_base.clone(),
+        pair_info.symbol_quote.clone(),
+        buy_amount,
+        tp_pct,
+        sl_pct,
+    );
+
+    if submitted {
+        if let Some((wallet, _)) = copy {
+            copytrade::cancel_entry(pair_info.pair);
+            copytrade::mark_copied(&pair_addr_str, wallet);
         }
+        governor::record_buy("sim", buy_amount, config_store);
+        save_log_to_file(&format!(
+            "[sim] ✓ SUBMITTED {} @ {:.8} size:{:.6} WBNB [{}] (buys:{} liq:${:.0})",
+            pair_info.symbol_base,
+            current_price,
+            buy_amount,
+            size_note,
+            buy_count,
+            pair_info.liquidity_usd.unwrap_or(0.0)
+        ));
     }
     Ok(())
 }
@@ -1226,8 +2372,8 @@ where
     if l1.contains("Price: ?") {
         return Ok(());
     }
-    let price_opt = extract_price_f64(&l1);
     let src = detect_source(&l1);
+    let price_opt = extract_price_f64(&l1).and_then(|p| wbnb_price(src, &pair_info, p));
     let pair_key = format!("{:#x}", pair_info.pair);
 
     if let Some(current_price) = price_opt {
@@ -1238,13 +2384,21 @@ where
         // First, attempt to exit existing position based on TP/SL/MaxHold
         if let Some(plan) = {
             let mut trader = REAL_TRADER.lock().await;
-            trader.sell_decision(&pair_key, current_price, config_store)
+            let update = pair_update(current_price, buy_count, &pair_info);
+            trader.sell_decision(&pair_key, &update, config_store)
         } {
             let res = execute_sell_plan(&plan, provider.clone(), config_store).await;
+            let sold = res.as_ref().ok().and_then(SellOutcome::sold);
+            if let Some(sold) = sold {
+                govern_sell(&plan, sold, config_store);
+            }
+            let filled = filled_pnl(&plan, sold);
 
+            let partial = plan.percent_points < 100;
             {
                 let mut trader = REAL_TRADER.lock().await;
                 match &res {
+                    Ok(outcome) if partial => trader.finish_partial(&plan, outcome),
                     Ok(_) => trader.finish_sell(&pair_key),
                     Err(e) => {
                         let msg = e.to_string();
@@ -1261,10 +2415,25 @@ where
 
             if let Some(se_arc) = sim_engine {
                 let mut se = se_arc.lock().await;
-                if let Some(pos) = se.take_position(&pair_key) {
+                if partial {
+                    if sold.is_some() {
+                        let fraction = plan.percent_points as f64 / 100.0;
+                        if let Some((marked, _)) = se.record_tp_tier(&pair_key, fraction) {
+                            let realized = mirror_partial_pnl(&mut se, &pair_key, marked, filled);
+                            save_log_to_file(&format!(
+                                "[trade] mirror partial {}% ({}) realized: {:+.6} WBNB",
+                                plan.percent_points, pair_key, realized
+                            ));
+                        }
+                    }
+                } else if res.is_ok() && sold.is_none() {
+                    // Nothing held to sell: drop the mirror without booking a PnL
+                    se.remove_position(&pair_key);
+                } else if let Some(pos) = se.take_position(&pair_key) {
+                    let pnl = mirror_close_pnl(&mut se, &pos, filled);
                     save_log_to_file(&format!(
                         "[trade] mirror close {} ({}) PnL: {:+.6} WBNB",
-                        pos.base_token, pos.pair_address, pos.pnl_wbnb
+                        pos.base_token, pos.pair_address, pnl
                     ));
                 } else {
                     save_log_to_file(&format!(
@@ -1275,41 +2444,36 @@ where
             }
 
             if let Err(e) = res {
+                nonce::on_send_error(provider.default_signer_address(), &e.to_string());
                 save_log_to_file(&format!("[trade] sell failed for {}: {}", pair_key, e));
             }
             return Ok(());
         }
 
-        let enabled = config_store
-            .get("enabled")
-            .map(|v| v.as_str() == "true")
-            .unwrap_or(false);
-        if !enabled {
-            return Ok(());
-        }
+        // A watched wallet's buy queued this entry: market filters and strategy are skipped
+        let copy = due_copy_entry(config_store, pair_info.pair);
+        let copied_from = copy.map(|(wallet, _)| wallet);
 
-        let avoid_cn = config_store
-            .get("avoid_chinese")
-            .map(|v| v.as_str() == "true")
-            .unwrap_or(false);
-        if avoid_cn
-            && (contains_cjk(&pair_info.symbol_base) || contains_cjk(&pair_info.symbol_quote))
-        {
-            return Ok(());
-        }
-        if should_avoid_name(&pair_info.symbol_base) || should_avoid_name(&pair_info.symbol_quote) {
+        let mut ctx = EntryCtx {
+            mode: "trade",
+            src,
+            pair_info: &pair_info,
+            pair_key: &pair_key,
+            buy_count,
+            config_store,
+            tax: pair_info
+                .tax
+                .or_else(|| honeypot::cached_report(pair_info.pair)),
+            risk: risk_scan_token(src, &pair_info).and_then(risk::cached_report),
+        };
+        let basic = match copy {
+            Some(_) => entry_filters::copy_basic(&ctx),
+            None => entry_filters::basic(&ctx),
+        };
+        if !basic.is_accept() {
             return Ok(());
         }
 
-        let freshness_secs = config_store
-            .get("freshness_secs")
-            .and_then(|v| v.parse::<u64>().ok())
-            .unwrap_or(30);
-        let min_pnl_pct = config_store
-            .get("min_pnl_pct")
-            .and_then(|v| v.parse::<f64>().ok())
-            .unwrap_or(100.0);
-
         {
             let trader = REAL_TRADER.lock().await;
             if trader.has_position_or_blocked(&pair_key) {
@@ -1330,101 +2494,6 @@ where
             }
         }
 
-        let accepted_quotes_str = config_store
-            .get("accepted_quotes")
-            .map(|v| v.to_string())
-            .unwrap_or_else(|| "BNB,CAKE,USDT,USD1,ASTER,WBNB".to_string());
-        let accepted_quotes: Vec<String> = accepted_quotes_str
-            .split(',')
-            .map(|s| s.trim().to_uppercase())
-            .collect();
-        if !accepted_quotes.contains(&pair_info.symbol_quote.to_uppercase()) {
-            return Ok(());
-        }
-
-        if src != PairSource::FourMeme {
-            let min_liquidity = config_store
-                .get("min_liquidity")
-                .and_then(|v| v.parse::<f64>().ok())
-                .unwrap_or(1000.0);
-            let liq_threshold = min_liquidity.max(5.0);
-            if let Some(liq) = pair_info.liquidity_usd {
-                if liq < liq_threshold {
-                    save_log_to_file(&format!(
-                        "[trade] REJECTED {}: Liq ${:.0} < min ${:.0}",
-                        pair_info.symbol_base, liq, liq_threshold
-                    ));
-                    return Ok(());
-                }
-            } else {
-                return Ok(());
-            }
-        }
-
-        if let Some((pnl_pct, first_seen)) = pair_metrics(&pair_key.as_str()) {
-            if pnl_pct < min_pnl_pct && first_seen.elapsed().as_secs() > freshness_secs {
-                return Ok(());
-            }
-        }
-
-        // minimum buys in recent window
-        let min_buys = config_store
-            .get("min_buys")
-            .and_then(|v| v.parse::<u32>().ok())
-            .unwrap_or(3);
-        if buy_count < min_buys {
-            save_log_to_file(&format!(
-                "[trade] WAITING {}: {} buys < min {}",
-                pair_info.symbol_base, buy_count, min_buys
-            ));
-            return Ok(());
-        }
-
-        // dex enablement filter
-        let dex_enabled = match src {
-            PairSource::V2 => config_store
-                .get("dexes")
-                .map(|v| v.as_str().contains("v2"))
-                .unwrap_or(false),
-            PairSource::V3 => config_store
-                .get("dexes")
-                .map(|v| v.as_str().contains("v3"))
-                .unwrap_or(false),
-            PairSource::FourMeme => config_store
-                .get("dexes")
-                .map(|v| v.as_str().contains("fm"))
-                .unwrap_or(false),
-            _ => false,
-        };
-        if !dex_enabled {
-            return Ok(());
-        }
-
-        // amount of BNB (or WBNB) to spend
-        let buy_amount_bnb = config_store
-            .get("buy_amount_wbnb")
-            .and_then(|v| v.parse::<f64>().ok())
-            .unwrap_or(0.00001);
-        if buy_amount_bnb <= 0.0 {
-            return Ok(());
-        }
-        let amt_str = format!("{}", buy_amount_bnb);
-        let amount_wei: U256 = parse_units(amt_str.as_str(), 18)
-            .map(Into::into)
-            .unwrap_or(U256::ZERO);
-        if amount_wei.is_zero() {
-            return Ok(());
-        }
-
-        // Guard: only buy if PnL >= 100% or pair age <= 30s
-        if let Some((pnl_pct, first_seen)) =
-            crate::app::pair_streams::pair_metrics(&pair_key.as_str())
-        {
-            if pnl_pct < 100.0 && first_seen.elapsed() > Duration::from_secs(30) {
-                return Ok(());
-            }
-        }
-
         // TP/SL settings (mirror sim)
         let tp_enabled = config_store
             .get("tp_enabled")
@@ -1453,20 +2522,126 @@ where
 
         let wrap_ratio_pct = wrap_ratio_pct_value(config_store);
         let from = provider.default_signer_address();
-        let gas_price_wei = gas_price_wei_u128(config_store);
+
+        // amount of WBNB to spend, per the sizing mode; funds come from the
+        // balance ticker and fills, and are only read here before the first tick
+        let funds = match sizing::cached_funds() {
+            Some(funds) => funds,
+            None => sizing::Funds {
+                bnb: wei_to_bnb(provider.get_balance(from).await.unwrap_or(U256::ZERO)),
+                wbnb: wei_to_bnb(safe_balance_of(provider.clone(), WBNB, from).await),
+            },
+        };
+        let realized_pnl_bnb = match sim_engine {
+            Some(se_arc) => se_arc.lock().await.stats().total_pnl_realized,
+            None => 0.0,
+        };
+        let (buy_amount_bnb, size_note) = match copy {
+            // Copy size is fixed but never more than the wallet can spend
+            Some((wallet, bnb)) => (
+                bnb.min(funds.spendable(wrap_ratio_pct)),
+                format!("copy {}", short_wallet(wallet)),
+            ),
+            None => {
+                let size = sizing::position_size(
+                    config_store,
+                    &SizingInputs {
+                        spendable_bnb: Some(funds.spendable(wrap_ratio_pct)),
+                        liquidity_usd: pair_info.liquidity_usd,
+                        sl_pct,
+                        realized_pnl_bnb,
+                    },
+                );
+                (size.bnb, size.note)
+            }
+        };
+        if buy_amount_bnb <= 0.0 {
+            return Ok(());
+        }
+        let amt_str = format!("{:.18}", buy_amount_bnb);
+        let amount_wei: U256 = parse_units(amt_str.as_str(), 18)
+            .map(Into::into)
+            .unwrap_or(U256::ZERO);
+        if amount_wei.is_zero() {
+            return Ok(());
+        }
+        let gas_price_wei = gas::gas_price_wei(&provider, config_store).await;
         let gas_price_wei_override = U256::from(gas_price_wei);
 
+        // The background check simulates the fixed buy_amount_wbnb; a sized
+        // buy well above it is checked again at its own size
+        let tax_resized =
+            ctx.tax.is_some() && honeypot::cached_report_for(pair_info.pair, amount_wei).is_none();
+        if tax_resized {
+            ctx.tax = None;
+        }
+
+        // Run the safety checks the background tasks have not started yet;
+        // fail closed when a check cannot run. A check attempted within its
+        // retry window is left to the background task and the entry waits.
+        if ctx.tax.is_none()
+            && honeypot_cfg(config_store).is_some()
+            && (tax_resized || honeypot::check_due(pair_info.pair))
+        {
+            let dex_type = match src {
+                PairSource::V3 => DexType::V3,
+                PairSource::FourMeme => DexType::FourMeme,
+                _ => DexType::V2,
+            };
+            match honeypot::check_pair(provider.clone(), from, dex_type, &pair_info, amount_wei)
+                .await
+            {
+                Ok(r) => ctx.tax = Some(r),
+                Err(e) => {
+                    entry_filters::reject(&ctx, "honeypot", format!("tax check failed: {}", e));
+                    return Ok(());
+                }
+            }
+        }
+        if ctx.risk.is_none() && risk_cfg(config_store).is_some() {
+            if let Some(token) = risk_scan_token(src, &pair_info).filter(|t| risk::scan_due(*t)) {
+                match risk::check_token(
+                    provider.clone(),
+                    pair_info.pair,
+                    token,
+                    &pair_info.symbol_base,
+                )
+                .await
+                {
+                    Ok(r) => ctx.risk = Some(r),
+                    Err(e) => {
+                        entry_filters::reject(&ctx, "risk", format!("risk scan failed: {}", e));
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        if !entry_filters::safety(&ctx).is_accept() {
+            return Ok(());
+        }
+        let update = pair_update(current_price, buy_count, &pair_info);
+        let ladder = tp_ladder_cfg(config_store);
+        let exit = exit_params_cfg(config_store, &ladder);
+        if copy.is_none()
+            && !entry_filters::strategy(&ctx, strategy_cfg(config_store), &update, &exit)
+                .is_accept()
+        {
+            return Ok(());
+        }
+        if copy.is_some() {
+            // One attempt per copied buy
+            copytrade::cancel_entry(pair_info.pair);
+        }
+        save_log_to_file(&format!(
+            "[trade] ENTRY {} size:{:.6} WBNB [{}]",
+            pair_info.symbol_base, buy_amount_bnb, size_note
+        ));
+
         match src {
             PairSource::V2 => {
-                // Only place WBNB->TOKEN if the pair includes WBNB directly
-                let token_out: Option<Address> = if pair_info.addr1 == WBNB {
-                    Some(pair_info.addr2)
-                } else if pair_info.addr2 == WBNB {
-                    Some(pair_info.addr1)
-                } else {
-                    None
-                };
-                if let Some(token_out) = token_out {
+                // WBNB -> TOKEN directly, or WBNB -> quote -> TOKEN for routable quotes
+                if let Some(route) = Route::for_pair(pair_info.addr1, pair_info.addr2, None) {
+                    let token_out = route.token;
                     if !ensure_wbnb_topup(
                         provider.clone(),
                         from,
@@ -1483,79 +2658,78 @@ where
 
                     let bal_before = safe_balance_of(provider.clone(), token_out, from).await;
 
-                    let dex_type = DexType::V2;
                     let pancake = PancakeV2::new(provider.clone());
-                    let token_out_s = format!("{:#x}", token_out);
-                    let (_quoted, tx) = routy_v2::swap_wbnb_to(
+                    let swap = routy_v2::swap_wbnb_via(
                         &pancake,
                         from,
-                        token_out_s.as_str(),
+                        &route,
                         amount_wei,
+                        buy_slippage_bps(config_store),
                         Some(gas_price_wei),
                     )
-                    .await?;
-
-                    tokio::time::sleep(std::time::Duration::from_millis(2000)).await;
-                    let bal_after = safe_balance_of(provider.clone(), token_out, from).await;
-                    if bal_after <= bal_before {
-                        save_log_to_file(&format!(
-                            "[trade] ✗ V2 BUY {} tx={} but token balance did not increase (before={} after={})",
-                            pair_info.symbol_base, tx, bal_before, bal_after
-                        ));
-                        return Ok(());
-                    }
-
-                    save_log_to_file(&format!(
-                        "[trade] ✓ V2 BUY {} via WBNB ({} BNB) tx={} token balance={}",
-                        pair_info.symbol_base, buy_amount_bnb, tx, bal_after
-                    ));
-                    clear_buy_failures(&pair_key);
-                    if let Some(se_arc) = sim_engine {
-                        let mut se = se_arc.lock().await;
-                        let _ = se.submit_buy(
-                            pair_key.clone(),
-                            dex_type,
-                            pair_info.symbol_base.clone(),
-                            pair_info.symbol_quote.clone(),
-                            buy_amount_bnb,
-                            tp_pct,
-                            sl_pct,
-                        );
-                        se.add_real_position(
-                            pair_key.clone(),
-                            dex_type,
-                            pair_info.symbol_base.clone(),
-                            pair_info.symbol_quote.clone(),
-                            current_price,
-                            buy_amount_bnb,
+                    .await;
+                    let tx = match swap {
+                        Ok((_quoted, tx)) => tx,
+                        Err(e) => {
+                            nonce::on_send_error(from, &e.to_string());
+                            let mut trader = REAL_TRADER.lock().await;
+                            let attempts = record_buy_failure(&mut trader, &pair_key);
+                            save_log_to_file(&format!(
+                                "[trade] V2 BUY failed {} attempts={} err={}",
+                                pair_key, attempts, e
+                            ));
+                            return Ok(());
+                        }
+                    };
+                    record_landed_buy(
+                        &provider,
+                        SentBuy {
+                            pair_key: &pair_key,
+                            pair_info: &pair_info,
+                            dex_type: DexType::V2,
+                            token: token_out,
+                            tx,
+                            bal_before,
+                            route: format!(" via {}", route_label(&route, &pair_info)),
+                            fee: None,
+                            via: route.via,
+                            price: current_price,
+                            amount_bnb: buy_amount_bnb,
                             tp_pct,
                             sl_pct,
-                            pair_info.liquidity_usd,
-                        );
-                    }
-                    let mut trader = REAL_TRADER.lock().await;
-                    trader.record_buy(RealPosition {
-                        pair_address: pair_key.clone(),
-                        dex_type,
-                        token_out,
-                        base_symbol: pair_info.symbol_base.clone(),
-                        entry_price: current_price,
-                        buy_amount_bnb,
-                        opened_at: Instant::now(),
-                    });
-                    queue_allowance_job(provider.clone(), dex_type, token_out, gas_price_wei);
+                            copied_from,
+                            gas_price_wei,
+                        },
+                        sim_engine,
+                        config_store,
+                    )
+                    .await;
                 }
             }
             PairSource::V3 => {
-                // Only place WBNB->TOKEN if the pair includes WBNB directly
-                let token_out: Option<Address> = if pair_info.addr1 == WBNB {
-                    Some(pair_info.addr2)
-                } else if pair_info.addr2 == WBNB {
-                    Some(pair_info.addr1)
-                } else {
-                    None
-                };
-                if let Some(token_out) = token_out {
+                // WBNB -> TOKEN directly, or WBNB -> quote -> TOKEN for routable quotes
+                if let Some(mut route) =
+                    Route::for_pair(pair_info.addr1, pair_info.addr2, pair_info.fee)
+                {
+                    let token_out = route.token;
+                    // Stream-provided tier, else probe the factory for the deepest pool;
+                    // resolved before wrapping so a failed probe leaves WBNB untouched
+                    if route.fee.is_none() {
+                        match routy_v3::deepest_fee_tier(provider.clone(), token_out, route.quote())
+                            .await
+                        {
+                            Ok(fee) => route.fee = Some(fee),
+                            Err(e) => {
+                                let mut trader = REAL_TRADER.lock().await;
+                                let attempts = record_buy_failure(&mut trader, &pair_key);
+                                save_log_to_file(&format!(
+                                    "[trade] V3 BUY failed {} attempts={} fee tier: {}",
+                                    pair_key, attempts, e
+                                ));
+                                return Ok(());
+                            }
+                        }
+                    }
                     if !ensure_wbnb_topup(
                         provider.clone(),
                         from,
@@ -1572,75 +2746,61 @@ where
 
                     let bal_before = safe_balance_of(provider.clone(), token_out, from).await;
 
-                    let dex_type = DexType::V3;
                     let pancake = PancakeV3::new(provider.clone());
-                    let token_out_s = format!("{:#x}", token_out);
-                    let (_quoted, tx) = routy_v3::swap_wbnb_to(
+                    let swap = routy_v3::swap_wbnb_via(
                         &pancake,
                         from,
-                        token_out_s.as_str(),
+                        &route,
                         amount_wei,
+                        buy_slippage_bps(config_store),
                         Some(gas_price_wei),
                     )
-                    .await?;
-                    tokio::time::sleep(std::time::Duration::from_millis(2000)).await;
-
-                    let bal_after = safe_balance_of(provider.clone(), token_out, from).await;
-                    if bal_after <= bal_before {
-                        save_log_to_file(&format!(
-                            "[trade] ✗ V3 BUY {} tx={} but token balance did not increase (before={} after={})",
-                            pair_info.symbol_base, tx, bal_before, bal_after
-                        ));
-                        return Ok(());
-                    }
-
-                    save_log_to_file(&format!(
-                        "[trade] ✓ V3 BUY {} via WBNB ({} BNB) tx={} token balance={}",
-                        pair_info.symbol_base, buy_amount_bnb, tx, bal_after
-                    ));
-                    clear_buy_failures(&pair_key);
-                    if let Some(se_arc) = sim_engine {
-                        let mut se = se_arc.lock().await;
-                        let _ = se.submit_buy(
-                            pair_key.clone(),
-                            dex_type,
-                            pair_info.symbol_base.clone(),
-                            pair_info.symbol_quote.clone(),
-                            buy_amount_bnb,
-                            tp_pct,
-                            sl_pct,
-                        );
-                        se.add_real_position(
-                            pair_key.clone(),
-                            dex_type,
-                            pair_info.symbol_base.clone(),
-                            pair_info.symbol_quote.clone(),
-                            current_price,
-                            buy_amount_bnb,
+                    .await;
+                    let tx = match swap {
+                        Ok((_quoted, tx)) => tx,
+                        Err(e) => {
+                            nonce::on_send_error(from, &e.to_string());
+                            let mut trader = REAL_TRADER.lock().await;
+                            let attempts = record_buy_failure(&mut trader, &pair_key);
+                            save_log_to_file(&format!(
+                                "[trade] V3 BUY failed {} attempts={} err={}",
+                                pair_key, attempts, e
+                            ));
+                            return Ok(());
+                        }
+                    };
+                    record_landed_buy(
+                        &provider,
+                        SentBuy {
+                            pair_key: &pair_key,
+                            pair_info: &pair_info,
+                            dex_type: DexType::V3,
+                            token: token_out,
+                            tx,
+                            bal_before,
+                            route: format!(" via {}", route_label(&route, &pair_info)),
+                            fee: route.fee,
+                            via: route.via,
+                            price: current_price,
+                            amount_bnb: buy_amount_bnb,
                             tp_pct,
                             sl_pct,
-                            pair_info.liquidity_usd,
-                        );
-                    }
-                    let mut trader = REAL_TRADER.lock().await;
-                    trader.record_buy(RealPosition {
-                        pair_address: pair_key.clone(),
-                        dex_type,
-                        token_out,
-                        base_symbol: pair_info.symbol_base.clone(),
-                        entry_price: current_price,
-                        buy_amount_bnb,
-                        opened_at: Instant::now(),
-                    });
-                    queue_allowance_job(provider.clone(), dex_type, token_out, gas_price_wei);
+                            copied_from,
+                            gas_price_wei,
+                        },
+                        sim_engine,
+                        config_store,
+                    )
+                    .await;
                 }
             }
             PairSource::FourMeme => {
                 // FourMeme uses native BNB; base token is addr1 (pair set to base)
+                // `fm_slippage_bps` stays as an explicit override of the buy slippage
                 let slippage_bps: u32 = config_store
                     .get("fm_slippage_bps")
                     .and_then(|v| v.parse::<u32>().ok())
-                    .unwrap_or(100);
+                    .unwrap_or_else(|| buy_slippage_bps(config_store));
                 let token = pair_info.addr1;
                 let router = FmRouter::new(provider.clone());
                 let bal_before = safe_balance_of(provider.clone(), token, from).await;
@@ -1656,11 +2816,9 @@ where
                     )
                     .await;
                 let (_est_amount, tx) = match buy_res {
-                    Ok(v) => {
-                        clear_buy_failures(&pair_key);
-                        v
-                    }
+                    Ok(v) => v,
                     Err(e) => {
+                        nonce::on_send_error(from, &e.to_string());
                         let mut trader = REAL_TRADER.lock().await;
                         let attempts = record_buy_failure(&mut trader, &pair_key);
                         save_log_to_file(&format!(
@@ -1671,54 +2829,29 @@ where
                     }
                 };
 
-                tokio::time::sleep(std::time::Duration::from_millis(2000)).await;
-                let bal_after = safe_balance_of(provider.clone(), token, from).await;
-                if bal_after <= bal_before {
-                    save_log_to_file(&format!(
-                        "[trade] ✗ FM BUY {} tx={:?} but token balance did not increase (before={} after={})",
-                        pair_info.symbol_base, tx, bal_before, bal_after
-                    ));
-                    return Ok(());
-                }
-
-                save_log_to_file(&format!(
-                    "[trade] ✓ FM BUY {} ({} BNB) tx={:?} token balance={}",
-                    pair_info.symbol_base, buy_amount_bnb, tx, bal_after
-                ));
-                if let Some(se_arc) = sim_engine {
-                    let mut se = se_arc.lock().await;
-                    let _ = se.submit_buy(
-                        pair_key.clone(),
-                        DexType::FourMeme,
-                        pair_info.symbol_base.clone(),
-                        pair_info.symbol_quote.clone(),
-                        buy_amount_bnb,
-                        tp_pct,
-                        sl_pct,
-                    );
-                    se.add_real_position(
-                        pair_key.clone(),
-                        DexType::FourMeme,
-                        pair_info.symbol_base.clone(),
-                        pair_info.symbol_quote.clone(),
-                        current_price,
-                        buy_amount_bnb,
+                record_landed_buy(
+                    &provider,
+                    SentBuy {
+                        pair_key: &pair_key,
+                        pair_info: &pair_info,
+                        dex_type: DexType::FourMeme,
+                        token,
+                        tx,
+                        bal_before,
+                        route: String::new(),
+                        fee: None,
+                        via: None,
+                        price: current_price,
+                        amount_bnb: buy_amount_bnb,
                         tp_pct,
                         sl_pct,
-                        pair_info.liquidity_usd,
-                    );
-                }
-                let mut trader = REAL_TRADER.lock().await;
-                trader.record_buy(RealPosition {
-                    pair_address: pair_key.clone(),
-                    dex_type: DexType::FourMeme,
-                    token_out: token,
-                    base_symbol: pair_info.symbol_base.clone(),
-                    entry_price: current_price,
-                    buy_amount_bnb,
-                    opened_at: Instant::now(),
-                });
-                queue_allowance_job(provider.clone(), DexType::FourMeme, token, gas_price_wei);
+                        copied_from,
+                        gas_price_wei,
+                    },
+                    sim_engine,
+                    config_store,
+                )
+                .await;
             }
             _ => {}
         }
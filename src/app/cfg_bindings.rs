@@ -1,6 +1,11 @@
 use {
+    crate::app::auto_trade::{dev_sell_exit_cfg, tp_ladder_cfg, trailing_stop_cfg},
     crate::libs::cache::save_autotrade_cache,
+    crate::libs::gas::next_gas_mode,
+    crate::libs::governor,
     crate::libs::sim::SimEngine,
+    crate::libs::sizing::next_sizing_mode,
+    crate::libs::strategy::{next_strategy_name, strategy_by_name},
     crate::libs::tui::{ConfigAreas, ConfigStore},
     ratatui::prelude::*,
 };
@@ -80,6 +85,18 @@ pub fn cfg_bindings(
         toggle_key(config_store, "tp_enabled");
         let _ = save_autotrade_cache(config_store);
     }
+    if contains(config_areas.tp_ladder_toggle) {
+        toggle_key(config_store, "tp_ladder_enabled");
+        sim_engine.set_tp_ladder(tp_ladder_cfg(config_store));
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.tp_ladder_input) {
+        *focused_field = Some("tp_ladder".to_string());
+        *input_buffer = config_store
+            .get("tp_ladder")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "50:30,100:30".to_string());
+    }
     if contains(config_areas.max_hold_pnl_toggle) {
         toggle_key(config_store, "max_hold_pnl");
         let enabled_now = config_store
@@ -89,17 +106,202 @@ pub fn cfg_bindings(
         sim_engine.set_max_hold_pnl_enabled(enabled_now);
         let _ = save_autotrade_cache(config_store);
     }
+    if contains(config_areas.breaker_reset_btn) {
+        governor::reset(config_store);
+    }
+    if contains(config_areas.max_daily_loss_input) {
+        *focused_field = Some("max_daily_loss_bnb".to_string());
+        *input_buffer = config_store
+            .get("max_daily_loss_bnb")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "0.05".to_string());
+    }
+    if contains(config_areas.max_losing_streak_input) {
+        *focused_field = Some("max_losing_streak".to_string());
+        *input_buffer = config_store
+            .get("max_losing_streak")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "5".to_string());
+    }
+    if contains(config_areas.max_bnb_per_hour_input) {
+        *focused_field = Some("max_bnb_per_hour".to_string());
+        *input_buffer = config_store
+            .get("max_bnb_per_hour")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "0.5".to_string());
+    }
+    if contains(config_areas.sizing_btn) {
+        let current = config_store
+            .get("sizing_mode")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "fixed".to_string());
+        config_store.insert("sizing_mode".into(), next_sizing_mode(&current).to_string());
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.size_balance_input) {
+        *focused_field = Some("size_balance_pct".to_string());
+        *input_buffer = config_store
+            .get("size_balance_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "5".to_string());
+    }
+    if contains(config_areas.size_liq_input) {
+        *focused_field = Some("size_liq_pct".to_string());
+        *input_buffer = config_store
+            .get("size_liq_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "1".to_string());
+    }
+    if contains(config_areas.risk_per_trade_input) {
+        *focused_field = Some("risk_per_trade_pct".to_string());
+        *input_buffer = config_store
+            .get("risk_per_trade_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "1".to_string());
+    }
+    if contains(config_areas.compound_input) {
+        *focused_field = Some("compound_pct".to_string());
+        *input_buffer = config_store
+            .get("compound_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "50".to_string());
+    }
+    if contains(config_areas.gas_mode_btn) {
+        let current = config_store
+            .get("gas_mode")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "fixed".to_string());
+        config_store.insert("gas_mode".into(), next_gas_mode(&current).to_string());
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.gas_oracle_pct_input) {
+        *focused_field = Some("gas_oracle_pct".to_string());
+        *input_buffer = config_store
+            .get("gas_oracle_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "10".to_string());
+    }
+    if contains(config_areas.replace_after_blocks_input) {
+        *focused_field = Some("replace_after_blocks".to_string());
+        *input_buffer = config_store
+            .get("replace_after_blocks")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "5".to_string());
+    }
+    if contains(config_areas.buy_gas_bump_input) {
+        *focused_field = Some("buy_gas_bump_pct".to_string());
+        *input_buffer = config_store
+            .get("buy_gas_bump_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "15".to_string());
+    }
+    if contains(config_areas.sell_gas_bump_input) {
+        *focused_field = Some("sell_gas_bump_pct".to_string());
+        *input_buffer = config_store
+            .get("sell_gas_bump_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "30".to_string());
+    }
+    if contains(config_areas.emergency_max_gwei_input) {
+        *focused_field = Some("emergency_max_gwei".to_string());
+        *input_buffer = config_store
+            .get("emergency_max_gwei")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "3.0".to_string());
+    }
+    if contains(config_areas.liq_guard_toggle) {
+        toggle_key(config_store, "liq_guard");
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.liq_pull_input) {
+        *focused_field = Some("liq_pull_pct".to_string());
+        *input_buffer = config_store
+            .get("liq_pull_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "30".to_string());
+    }
+    if contains(config_areas.dev_sell_toggle) {
+        toggle_key(config_store, "dev_sell_exit");
+        sim_engine.set_dev_sell_exit(dev_sell_exit_cfg(config_store));
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.dev_sell_input) {
+        *focused_field = Some("dev_sell_pct".to_string());
+        *input_buffer = config_store
+            .get("dev_sell_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "50".to_string());
+    }
+    if contains(config_areas.copy_trade_toggle) {
+        toggle_key(config_store, "copy_trade");
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.copy_buy_input) {
+        *focused_field = Some("copy_buy_bnb".to_string());
+        *input_buffer = config_store
+            .get("copy_buy_bnb")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "0.01".to_string());
+    }
+    if contains(config_areas.copy_delay_input) {
+        *focused_field = Some("copy_delay_secs".to_string());
+        *input_buffer = config_store
+            .get("copy_delay_secs")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "0".to_string());
+    }
+    if contains(config_areas.copy_wallet_input) {
+        *focused_field = Some("copy_wallet".to_string());
+        input_buffer.clear();
+    }
+    if contains(config_areas.strategy_btn) {
+        let current = config_store
+            .get("strategy")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "default".to_string());
+        let next = next_strategy_name(&current);
+        config_store.insert("strategy".into(), next.to_string());
+        sim_engine.set_strategy(strategy_by_name(next));
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.momentum_min_buyers_input) {
+        *focused_field = Some("momentum_min_buyers".to_string());
+        *input_buffer = config_store
+            .get("momentum_min_buyers")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "10".to_string());
+    }
     if contains(config_areas.sl_toggle) {
         toggle_key(config_store, "sl_enabled");
         let _ = save_autotrade_cache(config_store);
     }
-    if contains(config_areas.slippage_input) {
-        *focused_field = Some("slippage_pct".to_string());
+    if contains(config_areas.trail_toggle) {
+        toggle_key(config_store, "trail_enabled");
+        let (trail_pct, activate_pct) = trailing_stop_cfg(config_store);
+        sim_engine.set_trailing_stop(trail_pct, activate_pct);
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.buy_slippage_input) {
+        *focused_field = Some("buy_slippage_pct".to_string());
         *input_buffer = config_store
-            .get("slippage_pct")
+            .get("buy_slippage_pct")
             .map(|v| v.to_string())
             .unwrap_or_else(|| "0.5".to_string());
     }
+    if contains(config_areas.sell_slippage_input) {
+        *focused_field = Some("sell_slippage_pct".to_string());
+        *input_buffer = config_store
+            .get("sell_slippage_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "0.5".to_string());
+    }
+    if contains(config_areas.sell_slippage_max_input) {
+        *focused_field = Some("sell_slippage_max_pct".to_string());
+        *input_buffer = config_store
+            .get("sell_slippage_max_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "10".to_string());
+    }
     if contains(config_areas.max_gwei_input) {
         *focused_field = Some("max_gwei".to_string());
         *input_buffer = config_store
@@ -128,6 +330,32 @@ pub fn cfg_bindings(
             .map(|v| v.to_string())
             .unwrap_or_else(|| "3".to_string());
     }
+    if contains(config_areas.honeypot_toggle) {
+        toggle_key(config_store, "honeypot_check");
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.max_tax_input) {
+        *focused_field = Some("max_tax_pct".to_string());
+        *input_buffer = config_store
+            .get("max_tax_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "10".to_string());
+    }
+    if contains(config_areas.honeypot_quote_only_toggle) {
+        toggle_key(config_store, "honeypot_quote_only");
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.risk_toggle) {
+        toggle_key(config_store, "risk_check");
+        let _ = save_autotrade_cache(config_store);
+    }
+    if contains(config_areas.max_risk_input) {
+        *focused_field = Some("max_risk_score".to_string());
+        *input_buffer = config_store
+            .get("max_risk_score")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "50".to_string());
+    }
     if contains(config_areas.max_hold_input) {
         *focused_field = Some("max_hold_secs".to_string());
         *input_buffer = config_store
@@ -149,6 +377,20 @@ pub fn cfg_bindings(
             .map(|v| v.to_string())
             .unwrap_or_else(|| "5".to_string());
     }
+    if contains(config_areas.trail_pct_input) {
+        *focused_field = Some("trail_pct".to_string());
+        *input_buffer = config_store
+            .get("trail_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "15".to_string());
+    }
+    if contains(config_areas.trail_activate_input) {
+        *focused_field = Some("trail_activate_pct".to_string());
+        *input_buffer = config_store
+            .get("trail_activate_pct")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "30".to_string());
+    }
     if contains(config_areas.avoid_chinese_toggle) {
         toggle_key(config_store, "avoid_chinese");
         let _ = save_autotrade_cache(config_store);
@@ -0,0 +1,165 @@
+//! Copy trading. Buys by watched wallets (`libs::copytrade`) queue an entry on
+//! the pair that `auto_trade` / `auto_trade_real` take on the next pair update
+//! once `copy_delay_secs` passed; a sell by the wallet exits the position that
+//! copied it, in sim or on-chain.
+
+use {
+    crate::app::auto_trade::{copy_exit, pair_key_addr},
+    crate::libs::copytrade::{self, short_wallet, CopySignal},
+    crate::libs::lookup::save_log_to_file,
+    crate::libs::nonce,
+    crate::libs::sim::SimEngine,
+    crate::libs::tui::ConfigStore,
+    alloy::primitives::Address,
+    alloy::providers::{Provider, WalletProvider},
+    std::collections::HashSet,
+    std::sync::atomic::{AtomicBool, Ordering},
+    std::sync::Arc,
+    std::time::Duration,
+    tokio::sync::{broadcast, Mutex, RwLock},
+    tokio::task::JoinHandle,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct CopyCfg {
+    /// Position size in BNB
+    pub buy_bnb: f64,
+    /// Wait after the wallet's buy before entering
+    pub delay: Duration,
+}
+
+/// Copy-trading settings; None when copy trading is off.
+pub fn copy_trade_cfg(config_store: &ConfigStore) -> Option<CopyCfg> {
+    let enabled = config_store
+        .get("copy_trade")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    let buy_bnb = config_store
+        .get("copy_buy_bnb")
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| *v > 0.0)?;
+    let delay_secs = config_store
+        .get("copy_delay_secs")
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    Some(CopyCfg {
+        buy_bnb,
+        delay: Duration::from_secs(delay_secs),
+    })
+}
+
+/// Watched wallet whose entry on `pair` is due, with the size to buy.
+pub fn due_copy_entry(config_store: &ConfigStore, pair: Address) -> Option<(Address, f64)> {
+    let cfg = copy_trade_cfg(config_store)?;
+    copytrade::ready_entry(pair).map(|wallet| (wallet, cfg.buy_bnb))
+}
+
+pub fn spawn_copy_trader<P>(
+    provider: P,
+    sim_engine: Arc<Mutex<SimEngine>>,
+    sim_mode: Arc<AtomicBool>,
+    sold_pairs: Arc<RwLock<HashSet<String>>>,
+    config_store: ConfigStore,
+) -> JoinHandle<()>
+where
+    P: Provider + Clone + WalletProvider + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let mut rx = copytrade::subscribe();
+        loop {
+            let signal = match rx.recv().await {
+                Ok(s) => s,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    save_log_to_file(&format!("[copy] dropped {} signals", n));
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            };
+            let Some(cfg) = copy_trade_cfg(&config_store) else {
+                continue;
+            };
+            if signal.is_buy {
+                copytrade::queue_entry(signal.pair, signal.wallet, cfg.delay);
+                save_log_to_file(&format!(
+                    "[copy] queued entry on {:#x} copying {} (in {}s)",
+                    signal.pair,
+                    short_wallet(signal.wallet),
+                    cfg.delay.as_secs()
+                ));
+                continue;
+            }
+            on_copy_sell(
+                signal,
+                &provider,
+                &sim_engine,
+                sim_mode.load(Ordering::Relaxed),
+                &sold_pairs,
+                &config_store,
+            )
+            .await;
+        }
+    })
+}
+
+async fn on_copy_sell<P>(
+    signal: CopySignal,
+    provider: &P,
+    sim_engine: &Arc<Mutex<SimEngine>>,
+    sim_mode: bool,
+    sold_pairs: &Arc<RwLock<HashSet<String>>>,
+    config_store: &ConfigStore,
+) where
+    P: Provider + Clone + WalletProvider + Send + Sync + 'static,
+{
+    // The wallet left before our delayed entry came due
+    copytrade::cancel_entry(signal.pair);
+    let pair_key = pair_key_addr(signal.pair);
+    if copytrade::copied_from(&pair_key) != Some(signal.wallet) {
+        return;
+    }
+    if sim_mode {
+        let mut se = sim_engine.lock().await;
+        if let Some(pos) = se.open_position(&pair_key).filter(|p| p.frozen) {
+            save_log_to_file(&format!(
+                "[sim] FROZEN {} ({}) would copy-exit: {} sold",
+                pos.base_token,
+                pair_key,
+                short_wallet(signal.wallet)
+            ));
+            return;
+        }
+        if let Some(pos) = se.take_position(&pair_key) {
+            save_log_to_file(&format!(
+                "[sim] 👥 COPY EXIT closed {} ({}) {} sold, PnL: {:+.6} WBNB",
+                pos.base_token,
+                pos.pair_address,
+                short_wallet(signal.wallet),
+                pos.pnl_wbnb
+            ));
+        }
+        copytrade::forget_copied(&pair_key);
+        return;
+    }
+    let provider = provider.clone();
+    let sim_engine = sim_engine.clone();
+    let sold_pairs = sold_pairs.clone();
+    let config_store = config_store.clone();
+    tokio::spawn(async move {
+        if let Err(e) = copy_exit(
+            &pair_key,
+            signal.wallet,
+            provider.clone(),
+            &sim_engine,
+            Some(&sold_pairs),
+            &config_store,
+        )
+        .await
+        {
+            nonce::on_send_error(provider.default_signer_address(), &e.to_string());
+            save_log_to_file(&format!("[trade] copy exit failed for {}: {}", pair_key, e));
+        }
+    });
+}
@@ -0,0 +1,455 @@
+//! Entry-filter pipeline shared by sim and real trading. Each filter returns a
+//! typed `Verdict`; the first non-accept verdict stops the chain and is kept in
+//! a per-pair rejection trail the Home tab can show.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+use crate::app::pair_state::PairSource;
+use crate::app::pair_streams::pair_metrics;
+use crate::libs::governor;
+use crate::libs::honeypot::TaxReport;
+use crate::libs::lookup::save_log_to_file;
+use crate::libs::risk::RiskReport;
+use crate::libs::strategy::{ExitParams, Intent, PairUpdate, Strategy};
+use crate::libs::tui::ConfigStore;
+use crate::libs::ws::pairs::PairInfo;
+use crate::shared::should_avoid_name;
+
+/// Outcome of a single filter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Accept,
+    /// Not yet (e.g. too few buys, safety check still running)
+    Wait(String),
+    Reject(String),
+}
+
+impl Verdict {
+    pub fn is_accept(&self) -> bool {
+        matches!(self, Verdict::Accept)
+    }
+}
+
+/// Everything the filters look at for one pair update.
+pub struct EntryCtx<'a> {
+    /// Log prefix: "sim" or "trade"
+    pub mode: &'static str,
+    pub src: PairSource,
+    pub pair_info: &'a PairInfo,
+    pub pair_key: &'a str,
+    pub buy_count: u32,
+    pub config_store: &'a ConfigStore,
+    pub tax: Option<TaxReport>,
+    pub risk: Option<RiskReport>,
+}
+
+struct EntryFilter {
+    name: &'static str,
+    /// Quiet filters still land in the trail but are not written to the log
+    quiet: bool,
+    check: fn(&EntryCtx) -> Verdict,
+}
+
+/// Cheap config/metadata filters, run on every update.
+const BASIC_FILTERS: [EntryFilter; 9] = [
+    EntryFilter {
+        name: "enabled",
+        quiet: true,
+        check: enabled_filter,
+    },
+    EntryFilter {
+        name: "governor",
+        quiet: true,
+        check: governor_filter,
+    },
+    EntryFilter {
+        name: "cjk",
+        quiet: true,
+        check: cjk_filter,
+    },
+    EntryFilter {
+        name: "name",
+        quiet: true,
+        check: name_filter,
+    },
+    EntryFilter {
+        name: "quote",
+        quiet: true,
+        check: quote_filter,
+    },
+    EntryFilter {
+        name: "dex",
+        quiet: true,
+        check: dex_filter,
+    },
+    EntryFilter {
+        name: "liquidity",
+        quiet: false,
+        check: liquidity_filter,
+    },
+    EntryFilter {
+        name: "freshness",
+        quiet: true,
+        check: freshness_filter,
+    },
+    EntryFilter {
+        name: "min_buys",
+        quiet: false,
+        check: min_buys_filter,
+    },
+];
+
+/// Entries copying a watched wallet skip the market filters (liquidity,
+/// freshness, buy count): the wallet's buy is the signal.
+const COPY_FILTERS: [EntryFilter; 5] = [
+    EntryFilter {
+        name: "enabled",
+        quiet: true,
+        check: enabled_filter,
+    },
+    EntryFilter {
+        name: "governor",
+        quiet: true,
+        check: governor_filter,
+    },
+    EntryFilter {
+        name: "name",
+        quiet: true,
+        check: name_filter,
+    },
+    EntryFilter {
+        name: "quote",
+        quiet: false,
+        check: quote_filter,
+    },
+    EntryFilter {
+        name: "dex",
+        quiet: true,
+        check: dex_filter,
+    },
+];
+
+/// Filters on the honeypot/risk reports attached to the context.
+const SAFETY_FILTERS: [EntryFilter; 2] = [
+    EntryFilter {
+        name: "honeypot",
+        quiet: false,
+        check: honeypot_filter,
+    },
+    EntryFilter {
+        name: "risk",
+        quiet: false,
+        check: risk_filter,
+    },
+];
+
+fn cfg_bool(store: &ConfigStore, key: &str, default: bool) -> bool {
+    store
+        .get(key)
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(default)
+}
+
+/// Whether `s` has CJK ideographs (the `avoid_chinese` filter).
+pub(crate) fn contains_cjk(s: &str) -> bool {
+    s.chars().any(|c| {
+        let u = c as u32;
+        (0x4E00..=0x9FFF).contains(&u)
+            || (0x3400..=0x4DBF).contains(&u)
+            || (0x20000..=0x2A6DF).contains(&u)
+            || (0x2A700..=0x2B73F).contains(&u)
+            || (0x2B740..=0x2B81F).contains(&u)
+            || (0x2B820..=0x2CEAF).contains(&u)
+    })
+}
+
+fn enabled_filter(ctx: &EntryCtx) -> Verdict {
+    if cfg_bool(ctx.config_store, "enabled", false) {
+        Verdict::Accept
+    } else {
+        Verdict::Reject("auto trade disabled".into())
+    }
+}
+
+fn governor_filter(_ctx: &EntryCtx) -> Verdict {
+    match governor::tripped() {
+        Some(trip) => Verdict::Reject(format!("circuit breaker: {}", trip.reason)),
+        None => Verdict::Accept,
+    }
+}
+
+fn cjk_filter(ctx: &EntryCtx) -> Verdict {
+    let p = ctx.pair_info;
+    if cfg_bool(ctx.config_store, "avoid_chinese", false)
+        && (contains_cjk(&p.symbol_base) || contains_cjk(&p.symbol_quote))
+    {
+        Verdict::Reject("CJK symbol".into())
+    } else {
+        Verdict::Accept
+    }
+}
+
+fn name_filter(ctx: &EntryCtx) -> Verdict {
+    let p = ctx.pair_info;
+    if should_avoid_name(&p.symbol_base) || should_avoid_name(&p.symbol_quote) {
+        Verdict::Reject("avoided name".into())
+    } else {
+        Verdict::Accept
+    }
+}
+
+fn quote_filter(ctx: &EntryCtx) -> Verdict {
+    let accepted = ctx
+        .config_store
+        .get("accepted_quotes")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "BNB,CAKE,USDT,USD1,ASTER,WBNB".to_string());
+    let quote = ctx.pair_info.symbol_quote.to_uppercase();
+    if accepted
+        .split(',')
+        .any(|s| s.trim().to_uppercase() == quote)
+    {
+        Verdict::Accept
+    } else {
+        Verdict::Reject(format!("quote {} not accepted", quote))
+    }
+}
+
+fn dex_filter(ctx: &EntryCtx) -> Verdict {
+    let tag = match ctx.src {
+        PairSource::V2 => "v2",
+        PairSource::V3 => "v3",
+        PairSource::FourMeme => "fm",
+        PairSource::Unknown => return Verdict::Reject("unknown source".into()),
+    };
+    let enabled = ctx
+        .config_store
+        .get("dexes")
+        .map(|v| v.as_str().contains(tag))
+        .unwrap_or(false);
+    if enabled {
+        Verdict::Accept
+    } else {
+        Verdict::Reject(format!("{} disabled", tag))
+    }
+}
+
+fn liquidity_filter(ctx: &EntryCtx) -> Verdict {
+    if ctx.src == PairSource::FourMeme {
+        return Verdict::Accept;
+    }
+    let min_liquidity = ctx
+        .config_store
+        .get("min_liquidity")
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(1000.0);
+    let liq_threshold = min_liquidity.max(5.0);
+    match ctx.pair_info.liquidity_usd {
+        Some(liq) if liq < liq_threshold => {
+            Verdict::Reject(format!("Liq ${:.0} < min ${:.0}", liq, liq_threshold))
+        }
+        Some(_) => Verdict::Accept,
+        None => Verdict::Reject("no liquidity data".into()),
+    }
+}
+
+fn freshness_filter(ctx: &EntryCtx) -> Verdict {
+    let freshness_secs = ctx
+        .config_store
+        .get("freshness_secs")
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(30);
+    let min_pnl_pct = ctx
+        .config_store
+        .get("min_pnl_pct")
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(100.0);
+    if let Some((pnl_pct, first_seen)) = pair_metrics(ctx.pair_key) {
+        if pnl_pct < min_pnl_pct && first_seen.elapsed().as_secs() > freshness_secs {
+            return Verdict::Reject(format!(
+                "older than {}s with PnL < {:.0}%",
+                freshness_secs, min_pnl_pct
+            ));
+        }
+    }
+    Verdict::Accept
+}
+
+fn min_buys_filter(ctx: &EntryCtx) -> Verdict {
+    let min_buys = ctx
+        .config_store
+        .get("min_buys")
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(3);
+    if ctx.buy_count < min_buys {
+        Verdict::Wait(format!("{} buys < min {}", ctx.buy_count, min_buys))
+    } else {
+        Verdict::Accept
+    }
+}
+
+fn honeypot_filter(ctx: &EntryCtx) -> Verdict {
+    let Some((max_tax, quote_only_ok)) = crate::app::auto_trade::honeypot_cfg(ctx.config_store)
+    else {
+        return Verdict::Accept;
+    };
+    match ctx.tax {
+        None => Verdict::Wait("tax check pending".into()),
+        Some(tax) => match tax.rejection(max_tax, quote_only_ok) {
+            Some(reason) => Verdict::Reject(reason),
+            None => Verdict::Accept,
+        },
+    }
+}
+
+fn risk_filter(ctx: &EntryCtx) -> Verdict {
+    let Some(max_score) = crate::app::auto_trade::risk_cfg(ctx.config_store) else {
+        return Verdict::Accept;
+    };
+    if crate::app::auto_trade::risk_scan_token(ctx.src, ctx.pair_info).is_none() {
+        return Verdict::Accept;
+    }
+    match ctx.risk.as_ref() {
+        None => Verdict::Wait("risk scan pending".into()),
+        Some(r) if r.score > max_score => {
+            Verdict::Reject(format!("risk {} > max {}", r.summary(), max_score))
+        }
+        Some(_) => Verdict::Accept,
+    }
+}
+
+fn run(ctx: &EntryCtx, filters: &[EntryFilter]) -> Verdict {
+    for f in filters {
+        let verdict = (f.check)(ctx);
+        if !verdict.is_accept() {
+            record(ctx, f.name, f.quiet, &verdict);
+            return verdict;
+        }
+    }
+    Verdict::Accept
+}
+
+/// Config and metadata filters (no RPC).
+pub fn basic(ctx: &EntryCtx) -> Verdict {
+    run(ctx, &BASIC_FILTERS)
+}
+
+/// Config filters for a copy-trading entry (no RPC).
+pub fn copy_basic(ctx: &EntryCtx) -> Verdict {
+    run(ctx, &COPY_FILTERS)
+}
+
+/// Honeypot/risk filters on `ctx.tax`/`ctx.risk`; a missing report waits.
+pub fn safety(ctx: &EntryCtx) -> Verdict {
+    run(ctx, &SAFETY_FILTERS)
+}
+
+/// Last step: the selected strategy decides whether to enter now.
+pub fn strategy(
+    ctx: &EntryCtx,
+    strategy: &dyn Strategy,
+    update: &PairUpdate,
+    exit: &ExitParams,
+) -> Verdict {
+    match strategy.on_update(ctx.pair_key, update, None, exit) {
+        Intent::Buy => Verdict::Accept,
+        _ => {
+            let verdict = Verdict::Wait(format!("{} strategy holding", strategy.name()));
+            record(ctx, "strategy", true, &verdict);
+            verdict
+        }
+    }
+}
+
+/// Reject outside the filter lists (e.g. a safety check that errored).
+pub fn reject(ctx: &EntryCtx, filter: &'static str, reason: String) -> Verdict {
+    let verdict = Verdict::Reject(reason);
+    record(ctx, filter, false, &verdict);
+    verdict
+}
+
+/// One entry of a pair's rejection trail.
+#[derive(Clone, Debug)]
+pub struct TrailEntry {
+    pub at: Instant,
+    pub mode: &'static str,
+    pub filter: &'static str,
+    pub verdict: Verdict,
+    /// Consecutive repeats of the same verdict
+    pub hits: u32,
+}
+
+impl TrailEntry {
+    pub fn line(&self) -> String {
+        let (kind, reason) = match &self.verdict {
+            Verdict::Accept => ("ACCEPT", ""),
+            Verdict::Wait(r) => ("WAIT", r.as_str()),
+            Verdict::Reject(r) => ("REJECT", r.as_str()),
+        };
+        format!(
+            "{:>4}s ago [{}] {} {}: {}{}",
+            self.at.elapsed().as_secs(),
+            self.mode,
+            kind,
+            self.filter,
+            reason,
+            if self.hits > 1 {
+                format!(" (x{})", self.hits)
+            } else {
+                String::new()
+            }
+        )
+    }
+}
+
+const TRAIL_LEN: usize = 12;
+static REJECTION_TRAIL: Lazy<DashMap<String, VecDeque<TrailEntry>>> = Lazy::new(DashMap::new);
+
+fn record(ctx: &EntryCtx, filter: &'static str, quiet: bool, verdict: &Verdict) {
+    let mut trail = REJECTION_TRAIL.entry(ctx.pair_key.to_string()).or_default();
+    if let Some(last) = trail.back_mut() {
+        if last.filter == filter && last.verdict == *verdict && last.mode == ctx.mode {
+            last.hits += 1;
+            last.at = Instant::now();
+            return;
+        }
+    }
+    if !quiet {
+        let (kind, reason) = match verdict {
+            Verdict::Wait(r) => ("WAITING", r),
+            Verdict::Reject(r) => ("REJECTED", r),
+            Verdict::Accept => return,
+        };
+        save_log_to_file(&format!(
+            "[{}] {} {}: {}",
+            ctx.mode, kind, ctx.pair_info.symbol_base, reason
+        ));
+    }
+    if trail.len() >= TRAIL_LEN {
+        trail.pop_front();
+    }
+    trail.push_back(TrailEntry {
+        at: Instant::now(),
+        mode: ctx.mode,
+        filter,
+        verdict: verdict.clone(),
+        hits: 1,
+    });
+}
+
+/// Rejection trail for a pair, newest first.
+pub fn rejection_trail(pair_key: &str) -> Vec<TrailEntry> {
+    REJECTION_TRAIL
+        .get(pair_key)
+        .map(|t| t.iter().rev().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Drop the trail of a pair that left the feed.
+pub fn forget(pair_key: &str) {
+    REJECTION_TRAIL.remove(pair_key);
+}
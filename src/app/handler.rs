@@ -1,10 +1,20 @@
 use {
     crate::app::auto_trade::pair_key_addr,
-    crate::app::auto_trade::{ensure_sell_allowance, manual_sell_all},
+    crate::app::auto_trade::{
+        dev_sell_exit_cfg, ensure_sell_allowance, flush_positions, honeypot_cfg, manual_sell_all,
+        mirror_real_positions, pair_update, real_position_pools, real_position_tokens,
+        restore_real_positions, risk_cfg, risk_scan_token, sell_slippage_cap_bps, strategy_cfg,
+        tp_ladder_cfg, trailing_stop_cfg, wbnb_price, wei_to_bnb,
+    },
     crate::app::cfg_bindings::cfg_bindings,
+    crate::app::copy_trade::spawn_copy_trader,
+    crate::app::entry_filters,
+    crate::app::liq_guard::spawn_liquidity_guard,
     crate::app::results::{results, results_interactions, ResultsAreas},
     crate::libs::bsc::{
         client::BscClient,
+        endpoints::{self, EndpointKind, EndpointStatus},
+        failover::FailoverTransport,
         spells::{format_bnb, get_balance},
     },
     crate::libs::cache::{
@@ -12,8 +22,16 @@ use {
         SettingsCache,
     },
     crate::libs::config::{load_env, Config},
+    crate::libs::copytrade,
+    crate::libs::gas,
+    crate::libs::governor,
+    crate::libs::honeypot,
     crate::libs::lookup::save_log_to_file,
+    crate::libs::nonce,
+    crate::libs::risk,
     crate::libs::sim::{DexType, SimEngine, SimPosition},
+    crate::libs::sizing,
+    crate::libs::strategy,
     crate::libs::tui::{
         centered_rect, draw_box, draw_config_main, draw_main_window, draw_modal, draw_modal_lines,
         draw_modal_pairs, draw_tab_strip, draw_title_bar, new_store_with_defaults, BoxProps,
@@ -21,9 +39,12 @@ use {
     },
     crate::libs::writing::cc,
     crate::libs::ws::pairs::{fourmeme_stream, pancakev2_stream, pancakev3_stream, PairInfo},
+    crate::libs::ws::source::LogSource,
+    crate::libs::ws::subscriptions::SubscriptionManager,
     crate::libs::ws::swap_aggregator::SwapAggregator,
-    crate::libs::ws::swaps::SwapEvent,
+    crate::libs::ws::swaps::{stop_swap_tracker, SwapEvent},
     crate::log,
+    crate::routy::route::{refresh_quote_rates, spawn_quote_rate_refresher},
     alloy::primitives::{utils::parse_units, Address, U256},
     alloy::providers::ProviderBuilder,
     alloy::providers::WalletProvider,
@@ -153,23 +174,42 @@ pub async fn init() -> Result<()> {
     load_env();
     let cfg = Config::new();
     let cli = BscClient::new(cfg.bsc_rpc.clone(), cfg.private_key.clone()).await?;
+    let rpc_urls = endpoints::parse_urls(&cfg.bsc_rpc, "BSC_RPC")?;
+    if rpc_urls.len() > 1 {
+        log!(cc::LIGHT_GREEN, "RPC endpoints: {}", rpc_urls.len());
+    }
     let bal = get_balance(&cli, cli.address).await?;
     let chain_id = cli.chain_id().await?;
     let address_display = format!("{}", cli.address);
     let balance_bnb = format_bnb(format!("0x{:x}", bal))?;
 
-    // start ws feeds for v2/v3/fm pairs
-    use crate::libs::bsc::client::BscWsClient;
-    let ws = BscWsClient::new(cfg.bsc_wss.clone(), cfg.private_key.clone()).await?;
+    // log feeds for v2/v3/fm pairs: WebSocket, or HTTP polling without BSC_WSS
+    let log_source = LogSource::from_config(&cfg).await?;
+    log!(cc::LIGHT_GREEN, "Log source: {}", log_source.label());
+    // every HTTP/WS endpoint is registered by now
+    endpoints::spawn_health_checks(cli.clone());
     let provider = {
-        let url = Url::parse(&cfg.bsc_rpc)?;
+        let transport = FailoverTransport::new(rpc_urls, cfg.rpc_broadcast);
         let signer = PrivateKeySigner::from_str(&cfg.private_key)?.with_chain_id(Some(56));
+        // Nonces come from the shared local manager instead of per-send lookups
         ProviderBuilder::new()
+            .disable_recommended_fillers()
+            .with_gas_estimation()
+            .with_nonce_management(nonce::manager())
             .with_chain_id(56)
             .wallet(signer)
-            .connect_http(url)
+            .connect_client(transport.into_client())
     };
     let config_store = new_store_with_defaults();
+    gas::apply_replace_policy(&config_store);
+    strategy::apply_strategy_cfg(&config_store);
+    let restored = restore_real_positions(provider.clone()).await;
+    if restored > 0 {
+        log!(cc::LIGHT_GREEN, "Restored {} real position(s).", restored);
+    }
+    // quote -> WBNB rates for routed (non-WBNB) pairs
+    refresh_quote_rates(provider.clone()).await;
+    spawn_quote_rate_refresher(provider.clone(), Duration::from_secs(30));
     log!(cc::LIGHT_GREEN, "Selling all FourMeme tokens...");
     startup_liquidate_fm_tokens(provider.clone(), &config_store).await;
     log!(cc::LIGHT_GREEN, "Finished.");
@@ -183,7 +223,7 @@ pub async fn init() -> Result<()> {
         &balance_bnb,
         pair_tx,
         pair_rx,
-        ws,
+        log_source,
         provider,
         cli.clone(),
         config_store,
@@ -198,7 +238,7 @@ pub struct JimmyTUI<P> {
     balance_bnb: String,
     pair_tx: tokio::sync::mpsc::Sender<(String, String, PairInfo)>,
     pairs_rx: Option<tokio::sync::mpsc::Receiver<(String, String, PairInfo)>>,
-    ws: crate::libs::bsc::client::BscWsClient,
+    log_source: LogSource,
     provider: P,
     cli: crate::libs::bsc::client::BscClient,
     config_store: ConfigStore,
@@ -215,7 +255,7 @@ where
         balance_bnb: &str,
         pair_tx: tokio::sync::mpsc::Sender<(String, String, PairInfo)>,
         pairs_rx: tokio::sync::mpsc::Receiver<(String, String, PairInfo)>,
-        ws: crate::libs::bsc::client::BscWsClient,
+        log_source: LogSource,
         provider: P,
         cli: crate::libs::bsc::client::BscClient,
         config_store: ConfigStore,
@@ -226,7 +266,7 @@ where
             balance_bnb: balance_bnb.to_string(),
             pair_tx,
             pairs_rx: Some(pairs_rx),
-            ws,
+            log_source,
             provider,
             cli,
             config_store,
@@ -272,12 +312,21 @@ where
         v3c: usize,
         fmc: usize,
     ) -> u16 {
-        let rt_lines = vec![
-            "WS: connected".to_string(),
+        let mut rt_lines = vec![format!("Logs: {}", self.log_source.label())];
+        let status = endpoints::snapshot();
+        for (kind, title) in [(EndpointKind::Http, "RPC:"), (EndpointKind::Ws, "WS:")] {
+            let of_kind: Vec<&EndpointStatus> = status.iter().filter(|e| e.kind == kind).collect();
+            if of_kind.is_empty() {
+                continue;
+            }
+            rt_lines.push(title.to_string());
+            rt_lines.extend(of_kind.into_iter().map(endpoint_line));
+        }
+        rt_lines.extend([
             "Hermes: streaming".to_string(),
             "Pairs cached:".to_string(),
             format!("v2:{} v3:{} fm:{}", v2c, v3c, fmc),
-        ];
+        ]);
         let rt_h = (rt_lines.len() as u16).saturating_add(2);
         let rt_props = BoxProps {
             offset: (0, 0),
@@ -328,6 +377,11 @@ where
         let mut pairs_scroll: usize = 0;
         let mut pairs_scroll_state = ratatui::widgets::ScrollbarState::default();
         let mut last_viewport_len: usize = 0;
+        // Home: click a pair to see why the entry filters skipped it
+        let mut home_area: Option<Rect> = None;
+        let mut home_keys: Vec<String> = Vec::new();
+        let mut trail_pair: Option<String> = None;
+        let mut trail_scroll_state = ratatui::widgets::ScrollbarState::default();
 
         // Results scroll
         let mut results_scroll: usize = 0;
@@ -347,6 +401,9 @@ where
 
         // Average fee in USD (refreshed periodically)
         let mut avg_fee_usd: Option<String> = None;
+
+        // Swap trackers of pruned pairs kept alive until their position closes
+        let mut parked_trackers: HashSet<Address> = HashSet::new();
         let mut config_areas: ConfigAreas = ConfigAreas::default();
 
         let tab_labels: [&str; 5] = ["Home", "Auto Trade", "Results", "Logs", "Settings"];
@@ -415,8 +472,57 @@ where
             let mut se = sim_engine.lock().await;
             se.set_max_hold_pnl_enabled(mh_pnl);
         }
+        // Initialize trailing stop
+        {
+            let (trail_pct, activate_pct) = trailing_stop_cfg(&self.config_store);
+            let mut se = sim_engine.lock().await;
+            se.set_trailing_stop(trail_pct, activate_pct);
+        }
+        // Initialize dev-dump exit
+        {
+            let mut se = sim_engine.lock().await;
+            se.set_dev_sell_exit(dev_sell_exit_cfg(&self.config_store));
+        }
+        // Initialize take-profit ladder
+        {
+            let mut se = sim_engine.lock().await;
+            se.set_tp_ladder(tp_ladder_cfg(&self.config_store));
+        }
+        // Initialize entry/exit strategy
+        {
+            let mut se = sim_engine.lock().await;
+            se.set_strategy(strategy_cfg(&self.config_store));
+        }
+        // Show real positions restored from the ledger
+        mirror_real_positions(&sim_engine, &self.config_store).await;
+        // Per-pair log filters (swaps, liquidity) share a few WebSocket connections
+        let subs = SubscriptionManager::new(
+            &self.log_source,
+            *crate::shared::WS_CONNECTIONS,
+            self.provider.clone().erased(),
+        );
+        // Exit held pools as soon as their liquidity is pulled
+        let _liq_guard = spawn_liquidity_guard(
+            subs.clone(),
+            self.provider.clone(),
+            sim_engine.clone(),
+            sold_pairs.clone(),
+            self.config_store.clone(),
+        );
         // Shared toggle for background sim usage
         let sim_mode_flag = Arc::new(AtomicBool::new(sim_mode));
+        // Mirror the buys and sells of the watched wallets
+        let watched = copytrade::load_watchlist();
+        if watched > 0 {
+            save_log_to_file(&format!("[copy] watching {} wallets", watched));
+        }
+        let _copy_trader = spawn_copy_trader(
+            self.provider.clone(),
+            sim_engine.clone(),
+            sim_mode_flag.clone(),
+            sold_pairs.clone(),
+            self.config_store.clone(),
+        );
 
         let pairs_rx_local = self.pairs_rx.take().unwrap_or_else(|| {
             let (_tx, rx) = tokio::sync::mpsc::channel::<(String, String, PairInfo)>(1);
@@ -440,16 +546,17 @@ where
                             want_fm: bool,
                             stream_handles: &mut StreamHandles,
                             pair_tx: tokio::sync::mpsc::Sender<(String, String, PairInfo)>,
-                            ws: crate::libs::bsc::client::BscWsClient,
+                            logs: LogSource,
                             provider: P| {
             if want_v2 && stream_handles.v2.is_none() {
                 let tx = pair_tx.clone();
-                let ws_c = ws.clone();
+                let logs_c = logs.clone();
                 let prov = provider.clone();
+                let subs_c = subs.clone();
                 let swap_tx_c = swap_tx.clone();
                 let swap_agg_c = swap_agg.clone();
                 stream_handles.v2 = Some(tokio::spawn(async move {
-                    pancakev2_stream(tx, ws_c, prov, swap_tx_c, swap_agg_c).await;
+                    pancakev2_stream(tx, logs_c, prov, subs_c, swap_tx_c, swap_agg_c).await;
                 }));
             } else if !want_v2 {
                 if let Some(h) = stream_handles.v2.take() {
@@ -459,12 +566,13 @@ where
 
             if want_v3 && stream_handles.v3.is_none() {
                 let tx = pair_tx.clone();
-                let ws_c = ws.clone();
+                let logs_c = logs.clone();
                 let prov = provider.clone();
+                let subs_c = subs.clone();
                 let swap_tx_c = swap_tx.clone();
                 let swap_agg_c = swap_agg.clone();
                 stream_handles.v3 = Some(tokio::spawn(async move {
-                    pancakev3_stream(tx, ws_c, prov, swap_tx_c, swap_agg_c).await;
+                    pancakev3_stream(tx, logs_c, prov, subs_c, swap_tx_c, swap_agg_c).await;
                 }));
             } else if !want_v3 {
                 if let Some(h) = stream_handles.v3.take() {
@@ -474,10 +582,10 @@ where
 
             if want_fm && stream_handles.fm.is_none() {
                 let tx = pair_tx.clone();
-                let ws_c = ws.clone();
+                let logs_c = logs.clone();
                 let prov = provider.clone();
                 stream_handles.fm = Some(tokio::spawn(async move {
-                    fourmeme_stream(tx, ws_c, prov).await;
+                    fourmeme_stream(tx, logs_c, prov).await;
                 }));
             } else if !want_fm {
                 if let Some(h) = stream_handles.fm.take() {
@@ -494,7 +602,7 @@ where
             want_fm,
             &mut stream_handles,
             self.pair_tx.clone(),
-            self.ws.clone(),
+            self.log_source.clone(),
             self.provider.clone(),
         );
         if stream_handles.hb.is_none() {
@@ -517,6 +625,7 @@ where
                         buy_count: 0,
                         sell_count: 0,
                         unique_buyers: 0,
+                        tax: None,
                     };
                     let _ = tx_hb.try_send((line1, link, pair_info));
                 }
@@ -534,7 +643,7 @@ where
             let config_store_c = self.config_store.clone();
             let provider_c = self.provider.clone();
             tokio::spawn(async move {
-                while let Some((l1, l2, pair_info)) = rx_bg.recv().await {
+                while let Some((l1, l2, mut pair_info)) = rx_bg.recv().await {
                     let pk = pair_key_addr(pair_info.pair);
                     let dexes_csv = config_store_c
                         .get("dexes")
@@ -558,6 +667,39 @@ where
                     if !allowed {
                         continue;
                     }
+                    // 0) attach the honeypot report, or start the checks in the background
+                    pair_info.tax = honeypot::cached_report(pair_info.pair);
+                    if pair_info.tax.is_none() && honeypot_cfg(&config_store_c).is_some() {
+                        let buy_amount_wei: U256 = config_store_c
+                            .get("buy_amount_wbnb")
+                            .and_then(|v| parse_units(v.as_str(), 18).ok())
+                            .map(Into::into)
+                            .unwrap_or(U256::ZERO);
+                        if !buy_amount_wei.is_zero() {
+                            let dex_type = match src {
+                                crate::app::pair_state::PairSource::V3 => DexType::V3,
+                                crate::app::pair_state::PairSource::FourMeme => DexType::FourMeme,
+                                _ => DexType::V2,
+                            };
+                            honeypot::spawn_check(
+                                provider_c.clone(),
+                                provider_c.default_signer_address(),
+                                dex_type,
+                                pair_info.clone(),
+                                buy_amount_wei,
+                            );
+                        }
+                    }
+                    if risk_cfg(&config_store_c).is_some() {
+                        if let Some(token) = risk_scan_token(src, &pair_info) {
+                            risk::spawn_scan(
+                                provider_c.clone(),
+                                pair_info.pair,
+                                token,
+                                pair_info.symbol_base.clone(),
+                            );
+                        }
+                    }
                     // 1) update Hermes state
                     {
                         let mut map = pairs_map_c.write().await;
@@ -574,15 +716,15 @@ where
                         );
                     }
                     // 2) Keep PnL state updated for both sim and real paths
-                    if let Some(current_price) = crate::app::pair_state::extract_price_f64(&l1) {
+                    // Prices are tracked in WBNB terms, routed through the pair's quote
+                    if let Some(current_price) = crate::app::pair_state::extract_price_f64(&l1)
+                        .and_then(|p| wbnb_price(src, &pair_info, p))
+                    {
                         let sim_on = sim_mode_flag_c.load(std::sync::atomic::Ordering::Relaxed);
                         let mut se = sim_engine_c.lock().await;
-                        let maybe_msg = se.update_or_execute(
-                            &pk,
-                            current_price,
-                            pair_info.liquidity_usd,
-                            sim_on,
-                        );
+                        let update = pair_update(current_price, pair_info.buy_count, &pair_info);
+                        let maybe_msg = se.update_or_execute(&pk, &update, sim_on);
+                        governor::observe_sim(&se, sim_on, &config_store_c);
                         drop(se);
                         if sim_on {
                             if let Some(msg) = maybe_msg {
@@ -642,7 +784,13 @@ where
                     // Handle input field editing
                     if let Some(field) = focused_field.as_ref() {
                         match key.code {
-                            KeyCode::Char(c) if c.is_ascii_digit() || c == '.' => {
+                            KeyCode::Char(c)
+                                if c.is_ascii_digit()
+                                    || c == '.'
+                                    || (field == "tp_ladder" && (c == ':' || c == ','))
+                                    || (field == "copy_wallet"
+                                        && (c.is_ascii_hexdigit() || c == 'x')) =>
+                            {
                                 input_buffer.push(c);
                             }
                             KeyCode::Backspace => {
@@ -667,6 +815,61 @@ where
                                             se.set_max_hold_secs(new_hold);
                                         }
                                     }
+                                    // Update sim engine if trailing stop changed
+                                    if field == "trail_pct" || field == "trail_activate_pct" {
+                                        let (trail_pct, activate_pct) =
+                                            trailing_stop_cfg(&self.config_store);
+                                        let mut se = sim_engine.lock().await;
+                                        se.set_trailing_stop(trail_pct, activate_pct);
+                                    }
+                                    // Update sim engine if the dev-dump threshold changed
+                                    if field == "dev_sell_pct" {
+                                        let mut se = sim_engine.lock().await;
+                                        se.set_dev_sell_exit(dev_sell_exit_cfg(&self.config_store));
+                                    }
+                                    // A typed wallet is added to or removed from the copy watchlist
+                                    if field == "copy_wallet" && !input_buffer.trim().is_empty() {
+                                        match input_buffer.trim().parse::<Address>() {
+                                            Ok(wallet) => match copytrade::toggle_wallet(wallet) {
+                                                Ok(true) => save_log_to_file(&format!(
+                                                    "[copy] watching {:#x}",
+                                                    wallet
+                                                )),
+                                                Ok(false) => save_log_to_file(&format!(
+                                                    "[copy] stopped watching {:#x}",
+                                                    wallet
+                                                )),
+                                                Err(e) => save_log_to_file(&format!(
+                                                    "[copy] failed to save watchlist: {}",
+                                                    e
+                                                )),
+                                            },
+                                            Err(_) => save_log_to_file(&format!(
+                                                "[copy] not a wallet address: {}",
+                                                input_buffer
+                                            )),
+                                        }
+                                        self.config_store.insert(field.clone(), String::new());
+                                        let _ = save_autotrade_cache(&self.config_store);
+                                    }
+                                    // Update sim engine if TP ladder changed
+                                    if field == "tp_ladder" {
+                                        let mut se = sim_engine.lock().await;
+                                        se.set_tp_ladder(tp_ladder_cfg(&self.config_store));
+                                    }
+                                    // Replacement policies follow the gas cap and bump settings
+                                    if matches!(
+                                        field.as_str(),
+                                        "max_gwei"
+                                            | "replace_after_blocks"
+                                            | "buy_gas_bump_pct"
+                                            | "sell_gas_bump_pct"
+                                    ) {
+                                        gas::apply_replace_policy(&self.config_store);
+                                    }
+                                    if field == "momentum_min_buyers" {
+                                        strategy::apply_strategy_cfg(&self.config_store);
+                                    }
                                 }
                                 focused_field = None;
                                 input_buffer.clear();
@@ -769,6 +972,18 @@ where
                                         active_tab = i;
                                     }
                                 }
+                                // Interactions INSIDE Home view (tab index 0): open/close a pair's filter trail
+                                if active_tab == 0 {
+                                    if trail_pair.is_some() {
+                                        trail_pair = None;
+                                    } else if let Some(r) = home_area {
+                                        // rows start below the top border; 3 rows per pair
+                                        if mx >= r.x && mx < r.x + r.width && my > r.y && my + 1 < r.y + r.height {
+                                            let idx = ((my - r.y - 1) as usize + pairs_scroll) / 3;
+                                            trail_pair = home_keys.get(idx).cloned();
+                                        }
+                                    }
+                                }
                                 // Interactions INSIDE Results view (tab index 2)
                                 if active_tab == 2 {
                                     if sim_mode {
@@ -848,7 +1063,7 @@ where
                         want_fm,
                         &mut stream_handles,
                         self.pair_tx.clone(),
-                        self.ws.clone(),
+                        self.log_source.clone(),
                         self.provider.clone(),
                     );
                     if !want_v2 || !want_v3 || !want_fm {
@@ -892,6 +1107,20 @@ where
                     if let Ok(b) = format_bnb(format!("0x{:x}", bal)) {
                         self.balance_bnb = b;
                     }
+                    // Funds and BNB/USD for the sizing modes
+                    let wbnb_bal = IERC20Lite::new(WBNB, self.provider.clone())
+                        .balanceOf(self.cli.address)
+                        .call()
+                        .await
+                        .unwrap_or(U256::ZERO);
+                    sizing::set_funds(sizing::Funds {
+                        bnb: wei_to_bnb(bal),
+                        wbnb: wei_to_bnb(wbnb_bal),
+                    });
+                    if let Ok(q) = pancakes::plug::price::get_price_v2(self.provider.clone(), WBNB, USDT).await {
+                        let units = q.amount_out_base_units.to_string().parse::<f64>().unwrap_or(0.0);
+                        sizing::set_bnb_usd(units / 10f64.powi(q.decimals_out as i32));
+                    }
                 }
             }
             _ = ticker.tick() => {
@@ -917,14 +1146,33 @@ where
                     let mut map = pairs_map.write().await;
                     let mut keys = pair_keys.write().await;
                     let mut sold = sold_pairs.write().await;
-                    for k in rm.iter() { let _ = map.remove(k); sold.insert(k.clone()); }
+                    for k in rm.iter() { let _ = map.remove(k); sold.insert(k.clone()); entry_filters::forget(k); strategy::forget(k); }
                     keys.retain(|k| !rm.contains(k));
+                    drop((map, keys, sold));
+                    // Drop cached tax and risk reports; park the swap trackers of pruned pairs until no position is held on them
+                    for k in rm.iter() {
+                        let Ok(pair) = k.parse::<Address>() else { continue };
+                        honeypot::forget(pair);
+                        risk::forget(pair);
+                        parked_trackers.insert(pair);
+                    }
+                }
+                if !parked_trackers.is_empty() {
+                    let held: HashSet<Address> = real_position_pools().await.into_iter().map(|(pool, _, _)| pool).collect();
+                    let se = sim_engine.lock().await;
+                    parked_trackers.retain(|pair| {
+                        let open = held.contains(pair) || se.open_position(&format!("{:#x}", pair)).is_some();
+                        if !open {
+                            stop_swap_tracker(*pair);
+                        }
+                        open
+                    });
                 }
 
                 // lock sim_engine for results view
                 let se_guard = sim_engine.lock().await;
                 // 2) Snapshot state for drawing
-                let (v2c, v3c, fmc, all_pairs): (usize, usize, usize, Vec<(String, String, String)>) = {
+                let (v2c, v3c, fmc, all_pairs, shown_keys) = {
                     let map = pairs_map.read().await;
                     let keys = pair_keys.read().await;
                     let avoid_cn = self
@@ -943,9 +1191,10 @@ where
                     let mut v3c = 0usize;
                     let mut fmc = 0usize;
                     let mut pairs_list: Vec<(String, String, String)> = Vec::with_capacity(keys.len());
+                    let mut shown_keys: Vec<String> = Vec::with_capacity(keys.len());
                     for k in keys.iter() {
                         if let Some(v) = map.get(k) {
-                            if avoid_cn && !open_set.contains(k) && contains_cjk(&v.upair_address) {
+                            if avoid_cn && !open_set.contains(k) && entry_filters::contains_cjk(&v.upair_address) {
                                 continue;
                             }
                             match v.source {
@@ -956,10 +1205,27 @@ where
                             }
                             let (l1, l2, l3) = v.to_three_lines();
                             pairs_list.push((l1, l2, l3));
+                            shown_keys.push(k.clone());
                         }
                     }
-                    (v2c, v3c, fmc, pairs_list)
+                    (v2c, v3c, fmc, pairs_list, shown_keys)
                 };
+                home_keys = shown_keys;
+                let trail_lines: Vec<String> = match trail_pair.as_deref() {
+                    Some(k) => {
+                        let trail = entry_filters::rejection_trail(k);
+                        if trail.is_empty() {
+                            vec!["No rejections recorded for this pair.".to_string()]
+                        } else {
+                            trail.iter().map(|e| e.line()).collect()
+                        }
+                    }
+                    None => Vec::new(),
+                };
+                let trail_title = trail_pair
+                    .as_deref()
+                    .map(|k| format!("Entry filters {} (newest first, click to close)", short_addr(k)))
+                    .unwrap_or_default();
 
                 // draw
                 terminal.draw(|f| {
@@ -987,7 +1253,7 @@ where
                                 v3c,
                                 fmc,
                             );
-                            let right = "q/esc: quit  ↑/↓: scroll  PgUp/PgDn: fast";
+                            let right = "q/esc: quit  ↑/↓: scroll  PgUp/PgDn: fast  click pair: filters";
                             draw_title_bar(f, size, "JimmyBSC", &mid, right);
 
                             // body below title
@@ -1044,7 +1310,12 @@ where
                                         let max_pos = content_len.saturating_sub(viewport_len);
                                         if pairs_scroll > max_pos { pairs_scroll = max_pos; }
                                         last_viewport_len = viewport_len;
-                                        draw_modal_pairs(f, content_area, "Hermes", &all_pairs, pairs_scroll, &mut pairs_scroll_state);
+                                        home_area = Some(content_area);
+                                        if trail_pair.is_some() {
+                                            draw_modal_lines(f, content_area, trail_title.as_str(), &trail_lines, 0, &mut trail_scroll_state);
+                                        } else {
+                                            draw_modal_pairs(f, content_area, "Hermes", &all_pairs, pairs_scroll, &mut pairs_scroll_state);
+                                        }
                                     }
                                     1 => {
                                         // Auto Trade → no modal; bordered panel for the config form
@@ -1167,8 +1438,9 @@ where
             }
         }
 
-        // Save Auto Trade config before exit
+        // Save Auto Trade config and the last positions ledger before exit
         let _ = save_autotrade_cache(&self.config_store);
+        flush_positions().await;
 
         let mut stdout = std::io::stdout();
         execute!(
@@ -1201,16 +1473,26 @@ fn balance_short(bal: &str) -> String {
     }
 }
 
-fn contains_cjk(s: &str) -> bool {
-    s.chars().any(|c| {
-        let u = c as u32;
-        (0x4E00..=0x9FFF).contains(&u)
-            || (0x3400..=0x4DBF).contains(&u)
-            || (0x20000..=0x2A6DF).contains(&u)
-            || (0x2A700..=0x2B73F).contains(&u)
-            || (0x2B740..=0x2B81F).contains(&u)
-            || (0x2B820..=0x2CEAF).contains(&u)
-    })
+/// One endpoint row of the Runtime block, e.g. ` up   bsc-dataseed.bin…   42ms +0`.
+fn endpoint_line(e: &EndpointStatus) -> String {
+    let state = if e.rate_limited() {
+        "429"
+    } else if e.usable() {
+        "up"
+    } else {
+        "down"
+    };
+    let host = e.host();
+    let host = if host.chars().count() > 17 {
+        format!("{}…", host.chars().take(16).collect::<String>())
+    } else {
+        host
+    };
+    let latency = e
+        .latency_ms
+        .map(|ms| format!("{}ms", ms))
+        .unwrap_or_else(|| "-".to_string());
+    format!(" {:<4} {:<17} {:>6} +{}", state, host, latency, e.lag)
 }
 
 fn load_logs_from_dir(dir: &PathBuf) -> Vec<String> {
@@ -1255,15 +1537,6 @@ fn load_logs_from_dir(dir: &PathBuf) -> Vec<String> {
     merged.into_iter().map(|(_, _, s)| s).collect()
 }
 
-fn gas_price_wei_from_cfg(config_store: &ConfigStore) -> u128 {
-    config_store
-        .get("max_gwei")
-        .and_then(|v| parse_units(v.as_str(), 9).ok())
-        .and_then(|wei| wei.try_into().ok())
-        .filter(|wei| *wei > 0)
-        .unwrap_or(1_000_000_000)
-}
-
 async fn startup_liquidate_fm_tokens<P>(provider: P, config_store: &ConfigStore)
 where
     P: alloy::providers::Provider + WalletProvider + Clone + Send + Sync + 'static,
@@ -1289,6 +1562,10 @@ where
             }
         }
     }
+    // Keep tokens that are still managed via the positions ledger
+    for token in real_position_tokens().await {
+        addrs.remove(&token);
+    }
     if addrs.is_empty() {
         return;
     }
@@ -1296,7 +1573,9 @@ where
     let from = provider.default_signer_address();
     let erc20 = |token: Address| IERC20Lite::new(token, provider.clone());
     let router = crate::router::FmRouter::new(provider.clone());
-    let gas_price = U256::from(gas_price_wei_from_cfg(config_store));
+    let gas_price = U256::from(gas::gas_price_wei(&provider, config_store).await);
+    // Startup cleanup is best-effort: sell at the configured slippage cap
+    let slippage_bps = sell_slippage_cap_bps(config_store);
 
     for token in addrs {
         let bal = erc20(token)
@@ -1326,7 +1605,7 @@ where
             continue;
         }
         match router
-            .sell_percent_pct(from, token, pct, Some(gas_price))
+            .sell_percent_pct(from, token, pct, slippage_bps, Some(gas_price))
             .await
         {
             Ok((_est, tx)) => save_log_to_file(&format!(
@@ -1505,8 +1784,21 @@ pub async fn results_interactions_real<P>(
                 return;
             }
 
-            let mut se = sim_engine.lock().await;
-            let _ = se.toggle_freeze(pair_addr);
+            match crate::app::auto_trade::toggle_real_freeze(pair_addr).await {
+                Some(frozen) => {
+                    let mut se = sim_engine.lock().await;
+                    se.set_freeze(pair_addr, frozen);
+                    save_log_to_file(&format!(
+                        "[trade] {} {}",
+                        if frozen { "◼ FROZEN" } else { "◻ UNFROZEN" },
+                        pair_addr
+                    ));
+                }
+                None => {
+                    let mut se = sim_engine.lock().await;
+                    let _ = se.toggle_freeze(pair_addr);
+                }
+            }
             return;
         }
     }
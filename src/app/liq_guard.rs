@@ -0,0 +1,145 @@
+//! Emergency exits on liquidity pulls. Keeps a `libs::ws::liquidity` watcher on
+//! the pool of every held V2/V3 real position and sells the position as soon as
+//! a removal reaches `liq_pull_pct` of its pool. four.meme tokens trade on the
+//! bonding curve until they migrate and have no LP to pull.
+
+use {
+    crate::app::auto_trade::{emergency_exit, real_position_pools},
+    crate::libs::lookup::save_log_to_file,
+    crate::libs::nonce,
+    crate::libs::sim::SimEngine,
+    crate::libs::tui::ConfigStore,
+    crate::libs::ws::liquidity::{watch_pool_liquidity, LiquidityPull, PullKind},
+    crate::libs::ws::subscriptions::SubscriptionManager,
+    alloy::primitives::Address,
+    alloy::providers::{Provider, WalletProvider},
+    std::collections::{HashMap, HashSet},
+    std::sync::Arc,
+    std::time::Duration,
+    tokio::sync::{mpsc, Mutex, RwLock},
+    tokio::task::JoinHandle,
+};
+
+/// How often the watched pools are synced with the held positions
+const SYNC_EVERY: Duration = Duration::from_secs(5);
+
+/// Removal (% of pool liquidity) that triggers an emergency exit; None when
+/// the guard is off.
+pub fn liq_guard_cfg(config_store: &ConfigStore) -> Option<f64> {
+    let enabled = config_store
+        .get("liq_guard")
+        .map(|v| v.as_str() == "true")
+        .unwrap_or(true);
+    if !enabled {
+        return None;
+    }
+    Some(
+        config_store
+            .get("liq_pull_pct")
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(30.0),
+    )
+}
+
+pub fn spawn_liquidity_guard<P>(
+    subs: SubscriptionManager,
+    provider: P,
+    sim_engine: Arc<Mutex<SimEngine>>,
+    sold_pairs: Arc<RwLock<HashSet<String>>>,
+    config_store: ConfigStore,
+) -> JoinHandle<()>
+where
+    P: Provider + Clone + WalletProvider + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let (tx, mut rx) = mpsc::channel::<LiquidityPull>(256);
+        let mut watchers: HashMap<Address, JoinHandle<()>> = HashMap::new();
+        let mut ticker = tokio::time::interval(SYNC_EVERY);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let held = if liq_guard_cfg(&config_store).is_some() {
+                        real_position_pools().await
+                    } else {
+                        Vec::new()
+                    };
+                    let keep: HashSet<Address> = held.iter().map(|(pool, _, _)| *pool).collect();
+                    // Finished watchers (subscription closed) are restarted below
+                    watchers.retain(|pool, handle| {
+                        if keep.contains(pool) && !handle.is_finished() {
+                            return true;
+                        }
+                        handle.abort();
+                        false
+                    });
+                    for (pool, v3, token) in held {
+                        if watchers.contains_key(&pool) {
+                            continue;
+                        }
+                        let subs = subs.clone();
+                        let provider = provider.clone();
+                        let tx = tx.clone();
+                        watchers.insert(
+                            pool,
+                            tokio::spawn(async move {
+                                watch_pool_liquidity(pool, v3, token, subs, provider, tx).await;
+                            }),
+                        );
+                    }
+                }
+                Some(pull) = rx.recv() => {
+                    on_pull(pull, &provider, &sim_engine, &sold_pairs, &config_store);
+                }
+            }
+        }
+    })
+}
+
+fn on_pull<P>(
+    pull: LiquidityPull,
+    provider: &P,
+    sim_engine: &Arc<Mutex<SimEngine>>,
+    sold_pairs: &Arc<RwLock<HashSet<String>>>,
+    config_store: &ConfigStore,
+) where
+    P: Provider + Clone + WalletProvider + Send + Sync + 'static,
+{
+    let Some(threshold) = liq_guard_cfg(config_store) else {
+        return;
+    };
+    if pull.removed_pct < threshold {
+        return;
+    }
+    if pull.kind == PullKind::LpTransfer {
+        // Moving LP is not a removal yet; the burn that may follow is
+        save_log_to_file(&format!(
+            "[liq] ⚠ {:.1}% of LP moved for {:#x} tx={:#x}",
+            pull.removed_pct, pull.pool, pull.tx_hash
+        ));
+        return;
+    }
+    let pair_key = format!("{:#x}", pull.pool);
+    let provider = provider.clone();
+    let sim_engine = sim_engine.clone();
+    let sold_pairs = sold_pairs.clone();
+    let config_store = config_store.clone();
+    // Exits run on their own so one slow sell does not hold up other pools
+    tokio::spawn(async move {
+        if let Err(e) = emergency_exit(
+            &pair_key,
+            pull.removed_pct,
+            provider.clone(),
+            &sim_engine,
+            Some(&sold_pairs),
+            &config_store,
+        )
+        .await
+        {
+            nonce::on_send_error(provider.default_signer_address(), &e.to_string());
+            save_log_to_file(&format!(
+                "[trade] emergency exit failed for {}: {}",
+                pair_key, e
+            ));
+        }
+    });
+}
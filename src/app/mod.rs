@@ -1,6 +1,9 @@
 pub mod auto_trade;
 pub mod cfg_bindings;
+pub mod copy_trade;
+pub mod entry_filters;
 pub mod handler;
+pub mod liq_guard;
 pub mod pair_state;
 pub mod pair_streams;
 pub mod results;
@@ -1,6 +1,8 @@
 #![deny(unused_imports)]
 use std::time::Instant;
 
+use crate::libs::honeypot::TaxReport;
+
 #[derive(Clone, Debug)]
 pub struct PairState {
     pub upair_address: String,
@@ -15,6 +17,9 @@ pub struct PairState {
     pub liquidity_usd: Option<f64>,
     pub buy_count: u32,  // Real buy transactions from swap events
     pub sell_count: u32, // Real sell transactions from swap events
+    pub tax: Option<TaxReport>,
+    /// Share of the creator wallets' holdings they sold, once seen (%)
+    pub dev_sold_pct: Option<f64>,
 }
 
 impl PairState {
@@ -59,6 +64,12 @@ impl PairState {
         }
         row2_parts.push(format!("Price: {}", price_text));
         row2_parts.push(format!("B:{} S:{}", self.buy_count, self.sell_count));
+        if let Some(tax) = self.tax {
+            row2_parts.push(tax.label());
+        }
+        if let Some(pct) = self.dev_sold_pct {
+            row2_parts.push(format!("Dev sold {:.0}%", pct));
+        }
 
         let row2 = format!("| {}", row2_parts.join(" | "));
 
@@ -2,6 +2,7 @@
 use {
     crate::app::auto_trade::pair_key_addr,
     crate::app::pair_state::{detect_source, extract_price_f64, PairState},
+    crate::libs::devwatch,
     crate::libs::ws::pairs::PairInfo,
     crate::shared::should_avoid_name,
     once_cell::sync::Lazy,
@@ -64,6 +65,10 @@ pub fn update_pairs_state(
         entry.buy_count = pair_info.buy_count;
         entry.sell_count = pair_info.sell_count;
         entry.liquidity_usd = pair_info.liquidity_usd;
+        if pair_info.tax.is_some() {
+            entry.tax = pair_info.tax;
+        }
+        entry.dev_sold_pct = devwatch::dev_sold_pct(pair_info.pair);
 
         if let Some(p) = price_opt {
             entry.last_price = Some(p);
@@ -119,6 +124,8 @@ pub fn update_pairs_state(
             liquidity_usd: pair_info.liquidity_usd,
             buy_count: pair_info.buy_count,
             sell_count: pair_info.sell_count,
+            tax: pair_info.tax,
+            dev_sold_pct: devwatch::dev_sold_pct(pair_info.pair),
         };
         if let Some(p) = price_opt {
             st.first_price = Some(p);
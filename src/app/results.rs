@@ -1,5 +1,6 @@
 #![warn(unused)]
 use {
+    crate::libs::copytrade::{self, short_wallet},
     crate::libs::lookup::save_log_to_file,
     crate::libs::sim::{DexType, SimEngine},
     crate::libs::tui::ConfigStore,
@@ -244,6 +245,12 @@ pub fn results<'a>(
                     Style::default().fg(Color::Red),
                 ));
             }
+            if let Some(wallet) = copytrade::copied_from(&pos.pair_address) {
+                spans.push(Span::styled(
+                    format!(" copy {}", short_wallet(wallet)),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
             stats_lines.push(Line::from(spans));
             line_idx += 1;
 
@@ -257,6 +264,54 @@ pub fn results<'a>(
             )]));
             line_idx += 1;
 
+            let ladder = sim_engine.tp_ladder();
+            if !ladder.is_empty() {
+                let hit = pos.tp_tiers_hit.min(ladder.len());
+                let mut spans = vec![Span::styled(
+                    format!("  TP ladder: {}/{} ", hit, ladder.len()),
+                    Style::default().fg(Color::DarkGray),
+                )];
+                for (i, (at, sell)) in ladder.iter().enumerate() {
+                    spans.push(Span::styled(
+                        format!("[+{:.0}%:{:.0}%] ", at, sell),
+                        Style::default().fg(if i < hit {
+                            Color::Green
+                        } else {
+                            Color::DarkGray
+                        }),
+                    ));
+                }
+                stats_lines.push(Line::from(spans));
+                line_idx += 1;
+            }
+
+            if let Some((trail, activate)) = sim_engine.trailing_stop() {
+                let peak_pnl = pos.peak_pnl_pct();
+                let armed = peak_pnl >= activate;
+                let stop_pnl =
+                    ((pos.peak_price * (1.0 - trail / 100.0)) / pos.entry_price - 1.0) * 100.0;
+                let text = if armed {
+                    format!(
+                        "  Trail: armed | Peak: {:+.2}% | Stop: {:+.2}%",
+                        peak_pnl, stop_pnl
+                    )
+                } else {
+                    format!(
+                        "  Trail: arms at {:+.2}% | Peak: {:+.2}%",
+                        activate, peak_pnl
+                    )
+                };
+                stats_lines.push(Line::from(Span::styled(
+                    text,
+                    Style::default().fg(if armed {
+                        Color::Yellow
+                    } else {
+                        Color::DarkGray
+                    }),
+                )));
+                line_idx += 1;
+            }
+
             take_button_lines.push((
                 this_first_line,
                 pos.pair_address.clone(),
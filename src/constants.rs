@@ -17,6 +17,9 @@ env_lazy! {
     pub MIN_TERMINAL_HEIGHT: u16 = ("MIN_TERMINAL_HEIGHT", 30);
     pub MAX_PAIRS: usize     = ("MAX_PAIRS", 120);
     pub BSC_CHAIN_ID: u64    = ("BSC_CHAIN_ID", 56);
+    pub WS_CONNECTIONS: usize = ("WS_CONNECTIONS", 4);
+    pub RPC_HEALTH_SECS: u64 = ("RPC_HEALTH_SECS", 15);
+    pub RPC_MAX_LAG: u64     = ("RPC_MAX_LAG", 3);
 }
 
 pub const ALL_QUOTES: [&str; 6] = ["BNB", "CAKE", "USDT", "USD1", "ASTER", "WBNB"];
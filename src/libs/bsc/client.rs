@@ -1,4 +1,8 @@
+use crate::libs::bsc::endpoints::{self, EndpointKind};
 use crate::libs::lookup::save_log_to_file;
+use crate::libs::ws::backfill::LogCursor;
+use alloy::providers::{DynProvider, Provider};
+use alloy::rpc::json_rpc::ErrorPayload;
 use alloy::rpc::types::eth::{Filter, Log as RpcLog};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::Value;
@@ -27,7 +31,8 @@ use reqwest::Client as HttpClient;
 /// living in [`crate::libs::bsc::spells`] can use it directly.
 #[derive(Clone, Debug)]
 pub struct BscClient {
-    rpc_url: Url,
+    /// Endpoints from `BSC_RPC`, tried healthiest first
+    rpc_urls: Vec<Url>,
     http: HttpClient,
     /// The Ethereum style address derived from the configured
     /// private key.  Exposed as a public field so callers may
@@ -37,7 +42,8 @@ pub struct BscClient {
 
 #[derive(Clone, Debug)]
 pub struct BscWsClient {
-    ws_url: Url,
+    /// Endpoints from `BSC_WSS`; every (re)connect picks the healthiest
+    ws_urls: Vec<Url>,
     pub address: alloy::primitives::Address,
 }
 
@@ -52,12 +58,12 @@ impl BscClient {
     /// behaviour of popular `.env` loaders and simplifies local
     /// development.
     pub async fn new(rpc_url: String, private_key: String) -> Result<Self> {
-        // Read the RPC endpoint URL from the environment or fall
-        // back to the public BSC node.  The provided URL must be
-        // parseable by the `url` crate; otherwise an error will
-        // propagate to the caller.
-        let url = Url::parse(&rpc_url)
-            .with_context(|| format!("Failed to parse BSC_RPC_URL: {rpc_url}"))?;
+        // `rpc_url` may list several comma-separated endpoints.  Each
+        // must be parseable by the `url` crate; otherwise an error
+        // will propagate to the caller.  Requests go to the
+        // healthiest endpoint and fail over to the others.
+        let urls = endpoints::parse_urls(&rpc_url, "BSC_RPC")?;
+        endpoints::register(EndpointKind::Http, &urls);
 
         // A private key is mandatory.  It is used only to derive
         // the associated address; no signing is performed by the
@@ -73,12 +79,22 @@ impl BscClient {
         let http = HttpClient::new();
 
         Ok(Self {
-            rpc_url: url,
+            rpc_urls: urls,
             http,
             address,
         })
     }
 
+    /// A copy of this client that only talks to `url`.  Used by the
+    /// endpoint health checks, which must not fail over.
+    pub fn pinned(&self, url: Url) -> Self {
+        Self {
+            rpc_urls: vec![url],
+            http: self.http.clone(),
+            address: self.address,
+        }
+    }
+
     /// Return the numeric chain identifier for the remote node.  On
     /// BSC this value is expected to be `56`.  The method
     /// internally performs an `eth_chainId` call and converts the
@@ -280,6 +296,24 @@ impl BscClient {
         Ok(U256::from_be_slice(&bytes))
     }
 
+    /// Return the number of the most recent block via `eth_blockNumber`.
+    pub async fn block_number(&self) -> Result<u64> {
+        let res = self.rpc("eth_blockNumber", serde_json::json!([])).await?;
+        let hex = res.as_str().context("blockNumber not string")?;
+        let val =
+            u64::from_str_radix(hex.trim_start_matches("0x"), 16).context("bad blockNumber hex")?;
+        Ok(val)
+    }
+
+    /// Fetch the logs matching `filter` via `eth_getLogs`.  The filter
+    /// should carry a block range; without one the node applies its
+    /// default (usually only the latest block).  Used by the HTTP
+    /// polling log source when no WebSocket endpoint is available.
+    pub async fn get_logs(&self, filter: &Filter) -> Result<Vec<RpcLog>> {
+        let res = self.rpc("eth_getLogs", serde_json::json!([filter])).await?;
+        serde_json::from_value(res).context("bad eth_getLogs response")
+    }
+
     async fn rpc(&self, method: &str, params: Value) -> Result<Value> {
         let body = serde_json::json!({
             "jsonrpc": "2.0",
@@ -287,14 +321,40 @@ impl BscClient {
             "method": method,
             "params": params,
         });
+        let mut last_err = None;
+        for url in endpoints::ranked(&self.rpc_urls) {
+            let start = std::time::Instant::now();
+            match self.rpc_at(&url, &body).await {
+                Ok(v) => {
+                    endpoints::report_success(&url, start.elapsed());
+                    if let Some(err) = v.get("error") {
+                        anyhow::bail!("rpc error: {}", err);
+                    }
+                    return Ok(v.get("result").cloned().unwrap_or(Value::Null));
+                }
+                Err((rate_limited, e)) => {
+                    endpoints::report_failure(&url, rate_limited);
+                    last_err = Some(e);
+                }
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("no RPC endpoint configured")))
+    }
+
+    /// One attempt against `url`.  Errors worth failing over on are
+    /// returned as `Err((rate_limited, error))`; a regular JSON‑RPC
+    /// error response is returned as `Ok` for the caller to surface.
+    async fn rpc_at(&self, url: &Url, body: &Value) -> Result<Value, (bool, anyhow::Error)> {
         let res = self
             .http
-            .post(self.rpc_url.as_str())
-            .json(&body)
+            .post(url.as_str())
+            .json(body)
             .send()
-            .await?;
+            .await
+            .map_err(|e| (false, e.into()))?;
         let status = res.status();
-        let bytes = res.bytes().await?;
+        let rate_limited = status.as_u16() == 429;
+        let bytes = res.bytes().await.map_err(|e| (rate_limited, e.into()))?;
         // Try to decode JSON; if it fails, surface useful diagnostics
         let v: Value = match serde_json::from_slice(&bytes) {
             Ok(json) => json,
@@ -311,37 +371,50 @@ impl BscClient {
                 } else {
                     "Non‑JSON response from RPC."
                 };
-                return Err(anyhow::anyhow!(
-                    "RPC decode error ({status}): {e}. {hint}\nEndpoint: {}\nSample: {}",
-                    self.rpc_url,
-                    sample
+                return Err((
+                    rate_limited,
+                    anyhow::anyhow!(
+                        "RPC decode error ({status}): {e}. {hint}\nEndpoint: {}\nSample: {}",
+                        url,
+                        sample
+                    ),
                 ));
             }
         };
+        // rate limits and overloaded nodes often answer with a JSON error
         if let Some(err) = v.get("error") {
-            anyhow::bail!("rpc error: {}", err);
+            let retry =
+                serde_json::from_value::<ErrorPayload>(err.clone()).is_ok_and(|p| p.is_retry_err());
+            if retry || rate_limited {
+                return Err((true, anyhow::anyhow!("rpc error: {}", err)));
+            }
         }
-        Ok(v.get("result").cloned().unwrap_or(Value::Null))
+        Ok(v)
     }
 }
 
 impl BscWsClient {
     pub async fn new(ws_url: String, private_key: String) -> anyhow::Result<Self> {
         use alloy::signers::local::PrivateKeySigner;
-        let url = Url::parse(&ws_url)
-            .map_err(|e| anyhow::anyhow!("Failed to parse BSC_WSS `{ws_url}`: {e}"))?;
+        let urls = endpoints::parse_urls(&ws_url, "BSC_WSS")?;
+        endpoints::register(EndpointKind::Ws, &urls);
         let signer: PrivateKeySigner = private_key.parse().map_err(|_| {
             anyhow::anyhow!("PRIVATE_KEY did not contain a valid hex encoded secret")
         })?;
         Ok(Self {
-            ws_url: url,
+            ws_urls: urls,
             address: signer.address(),
         })
     }
 
-    /// expose url so we can branch in ws/pairs.rs if needed
-    pub fn url(&self) -> &Url {
-        &self.ws_url
+    /// The healthiest endpoint right now
+    pub fn url(&self) -> Url {
+        endpoints::best(&self.ws_urls)
+    }
+
+    /// All configured endpoints, for connections that pick their own on reconnect
+    pub fn urls(&self) -> &[Url] {
+        &self.ws_urls
     }
 
     /// Old API – single subscription (kept for backward compatibility)
@@ -350,83 +423,20 @@ impl BscWsClient {
         filter: Filter,
     ) -> anyhow::Result<(mpsc::Receiver<RpcLog>, JoinHandle<()>)> {
         let (tx, rx) = mpsc::channel::<RpcLog>(1024);
-        let url = self.ws_url.clone();
-
-        let handle = tokio::spawn(async move {
-            loop {
-                save_log_to_file(&format!("[ws] connecting to {}", url));
-                let (mut ws, _) = match connect_async(url.as_str()).await {
-                    Ok(ok) => ok,
-                    Err(e) => {
-                        save_log_to_file(&format!("[ws] connect error: {e}, retrying in 3s …"));
-                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                        continue;
-                    }
-                };
-                save_log_to_file("[ws] connected");
-
-                let sub = serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "id": 1,
-                    "method": "eth_subscribe",
-                    "params": ["logs", filter],
-                });
-
-                save_log_to_file("[ws] sending eth_subscribe (logs)");
-                if let Err(e) = ws.send(Message::Text(sub.to_string().into())).await {
-                    save_log_to_file(&format!("[ws] send subscribe failed: {e}"));
-                    break;
-                }
-
-                while let Some(msg) = ws.next().await {
-                    match msg {
-                        Ok(Message::Text(txt)) => {
-                            if let Ok(v) = serde_json::from_str::<Value>(&txt) {
-                                if let Some(method) = v.get("method").and_then(|m| m.as_str()) {
-                                    if method == "eth_subscription" {
-                                        if let Some(result) =
-                                            v.get("params").and_then(|p| p.get("result"))
-                                        {
-                                            match serde_json::from_value::<RpcLog>(result.clone()) {
-                                                Ok(log) => {
-                                                    let _ = tx.send(log).await;
-                                                }
-                                                Err(e) => save_log_to_file(&format!(
-                                                    "[ws] failed to decode log: {e}"
-                                                )),
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Ok(Message::Close(frame)) => {
-                            if let Some(f) = frame {
-                                save_log_to_file(&format!(
-                                    "[ws] CLOSE code={} reason={}",
-                                    f.code, f.reason
-                                ));
-                            } else {
-                                save_log_to_file("[ws] CLOSE (no frame)");
-                            }
-                            break;
-                        }
-                        Ok(Message::Ping(p)) => {
-                            let _ = ws.send(Message::Pong(p)).await;
-                        }
-                        Ok(_) => {}
-                        Err(e) => {
-                            save_log_to_file(&format!("[ws] error: {e}"));
-                            break;
-                        }
-                    }
-                }
-
-                save_log_to_file("[ws] stream ended, retrying in 3s …");
-                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-            }
-        });
+        let handle = tokio::spawn(log_stream(self.ws_urls.clone(), filter, None, tx));
+        Ok((rx, handle))
+    }
 
+    /// Single subscription that survives reconnects without gaps: logs emitted
+    /// while the socket was down are fetched from `backfill` with `eth_getLogs`
+    /// before live logs resume.
+    pub async fn subscribe_logs_backfilled(
+        &self,
+        filter: Filter,
+        backfill: DynProvider,
+    ) -> anyhow::Result<(mpsc::Receiver<RpcLog>, JoinHandle<()>)> {
+        let (tx, rx) = mpsc::channel::<RpcLog>(1024);
+        let handle = tokio::spawn(log_stream(self.ws_urls.clone(), filter, Some(backfill), tx));
         Ok((rx, handle))
     }
 
@@ -436,15 +446,17 @@ impl BscWsClient {
         &self,
         tagged_filters: Vec<(String, Filter)>,
     ) -> anyhow::Result<(mpsc::Receiver<(String, RpcLog)>, JoinHandle<()>)> {
-        let url = self.ws_url.clone();
+        let urls = self.ws_urls.clone();
         let (tx, rx) = mpsc::channel::<(String, RpcLog)>(2048);
 
         let handle = tokio::spawn(async move {
             loop {
+                let url = endpoints::best(&urls);
                 save_log_to_file(&format!("[ws/multi] connecting to {}", url));
                 let (mut ws, _) = match connect_async(url.as_str()).await {
                     Ok(ok) => ok,
                     Err(e) => {
+                        endpoints::report_failure(&url, false);
                         save_log_to_file(&format!(
                             "[ws/multi] connect error: {e}, retrying in 3s …"
                         ));
@@ -603,3 +615,112 @@ impl BscWsClient {
         Ok((rx, handle))
     }
 }
+
+/// Reconnecting single-filter log stream behind [`BscWsClient::subscribe_logs`].
+/// Duplicates and logs removed by a reorg are dropped; with a `backfill`
+/// provider the blocks missed during a reconnect are fetched over HTTP.
+async fn log_stream(
+    urls: Vec<Url>,
+    filter: Filter,
+    backfill: Option<DynProvider>,
+    tx: mpsc::Sender<RpcLog>,
+) {
+    let mut cursor = LogCursor::default();
+    loop {
+        let url = endpoints::best(&urls);
+        save_log_to_file(&format!("[ws] connecting to {}", url));
+        let (mut ws, _) = match connect_async(url.as_str()).await {
+            Ok(ok) => ok,
+            Err(e) => {
+                endpoints::report_failure(&url, false);
+                save_log_to_file(&format!("[ws] connect error: {e}, retrying in 3s …"));
+                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                continue;
+            }
+        };
+        save_log_to_file("[ws] connected");
+
+        let sub = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_subscribe",
+            "params": ["logs", filter],
+        });
+
+        save_log_to_file("[ws] sending eth_subscribe (logs)");
+        if let Err(e) = ws.send(Message::Text(sub.to_string().into())).await {
+            save_log_to_file(&format!(
+                "[ws] send subscribe failed: {e}, retrying in 3s …"
+            ));
+            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+            continue;
+        }
+
+        // live logs queue up on the socket while the gap is fetched
+        if let Some(provider) = &backfill {
+            let missed = match provider.get_block_number().await {
+                Ok(head) => cursor.backfill(provider, &filter, head).await,
+                Err(e) => Err(e.into()),
+            };
+            match missed {
+                Ok(logs) => {
+                    if !logs.is_empty() {
+                        save_log_to_file(&format!("[ws] backfilled {} missed logs", logs.len()));
+                    }
+                    for log in logs {
+                        let _ = tx.send(log).await;
+                    }
+                }
+                Err(e) => save_log_to_file(&format!("[ws] backfill failed: {e}")),
+            }
+        }
+
+        while let Some(msg) = ws.next().await {
+            match msg {
+                Ok(Message::Text(txt)) => {
+                    if let Ok(v) = serde_json::from_str::<Value>(&txt) {
+                        if let Some(method) = v.get("method").and_then(|m| m.as_str()) {
+                            if method == "eth_subscription" {
+                                if let Some(result) = v.get("params").and_then(|p| p.get("result"))
+                                {
+                                    match serde_json::from_value::<RpcLog>(result.clone()) {
+                                        Ok(log) => {
+                                            if cursor.accept(&log) {
+                                                let _ = tx.send(log).await;
+                                            }
+                                        }
+                                        Err(e) => save_log_to_file(&format!(
+                                            "[ws] failed to decode log: {e}"
+                                        )),
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                Ok(Message::Close(frame)) => {
+                    if let Some(f) = frame {
+                        save_log_to_file(&format!(
+                            "[ws] CLOSE code={} reason={}",
+                            f.code, f.reason
+                        ));
+                    } else {
+                        save_log_to_file("[ws] CLOSE (no frame)");
+                    }
+                    break;
+                }
+                Ok(Message::Ping(p)) => {
+                    let _ = ws.send(Message::Pong(p)).await;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    save_log_to_file(&format!("[ws] error: {e}"));
+                    break;
+                }
+            }
+        }
+
+        save_log_to_file("[ws] stream ended, retrying in 3s …");
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+    }
+}
@@ -0,0 +1,298 @@
+//! RPC endpoint pool. `BSC_RPC` and `BSC_WSS` take comma-separated lists;
+//! every endpoint is registered here and probed periodically for health
+//! (`eth_health`), block height lag and latency. Callers ask for the endpoints
+//! ranked best first and report request outcomes back, so an endpoint that
+//! errors or rate limits is skipped until it recovers.
+
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use futures_util::future::join_all;
+use futures_util::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use url::Url;
+
+use crate::libs::bsc::client::BscClient;
+use crate::libs::lookup::save_log_to_file;
+use crate::shared::{RPC_HEALTH_SECS, RPC_MAX_LAG};
+
+/// Consecutive request failures before an endpoint is skipped
+const MAX_FAILURES: u32 = 3;
+/// How long a rate-limited endpoint is skipped
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(30);
+/// Upper bound for one health probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndpointKind {
+    Http,
+    Ws,
+}
+
+#[derive(Clone, Debug)]
+pub struct EndpointStatus {
+    pub url: Url,
+    pub kind: EndpointKind,
+    /// Result of the last health check (true until the first one ran)
+    pub healthy: bool,
+    /// Smoothed request latency
+    pub latency_ms: Option<u64>,
+    /// Head block at the last health check
+    pub block: Option<u64>,
+    /// Blocks behind the highest head seen across all endpoints
+    pub lag: u64,
+    /// Consecutive failed requests since the last success
+    pub failures: u32,
+    pub limited_until: Option<Instant>,
+}
+
+impl EndpointStatus {
+    fn new(url: Url, kind: EndpointKind) -> Self {
+        Self {
+            url,
+            kind,
+            healthy: true,
+            latency_ms: None,
+            block: None,
+            lag: 0,
+            failures: 0,
+            limited_until: None,
+        }
+    }
+
+    pub fn rate_limited(&self) -> bool {
+        self.limited_until.is_some_and(|t| t > Instant::now())
+    }
+
+    /// Whether requests should go to this endpoint.
+    pub fn usable(&self) -> bool {
+        self.healthy && self.failures < MAX_FAILURES && !self.rate_limited()
+    }
+
+    /// Host part of the URL for logs and the TUI.
+    pub fn host(&self) -> String {
+        self.url.host_str().unwrap_or("?").to_string()
+    }
+
+    fn record_latency(&mut self, latency: Duration) {
+        let ms = latency.as_millis() as u64;
+        self.latency_ms = Some(self.latency_ms.map_or(ms, |old| (old * 4 + ms) / 5));
+    }
+}
+
+static ENDPOINTS: Lazy<RwLock<Vec<EndpointStatus>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Parse a comma-separated endpoint list, e.g. the value of `BSC_RPC`.
+pub fn parse_urls(list: &str, var: &str) -> Result<Vec<Url>> {
+    let urls = list
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| Url::parse(s).with_context(|| format!("Failed to parse {var} entry `{s}`")))
+        .collect::<Result<Vec<_>>>()?;
+    anyhow::ensure!(!urls.is_empty(), "{var} does not contain any endpoint");
+    Ok(urls)
+}
+
+/// Add `urls` to the pool; endpoints already known are left as they are.
+pub fn register(kind: EndpointKind, urls: &[Url]) {
+    let mut all = ENDPOINTS.write().unwrap_or_else(|e| e.into_inner());
+    for url in urls {
+        if !all.iter().any(|e| &e.url == url) {
+            all.push(EndpointStatus::new(url.clone(), kind));
+        }
+    }
+}
+
+/// Current state of every endpoint, in configuration order.
+pub fn snapshot() -> Vec<EndpointStatus> {
+    ENDPOINTS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// `urls` ordered best first: usable endpoints before skipped ones, then by
+/// block lag and latency. Ties keep the configured order.
+pub fn ranked(urls: &[Url]) -> Vec<Url> {
+    let all = ENDPOINTS.read().unwrap_or_else(|e| e.into_inner());
+    let mut keyed: Vec<(bool, u64, u64, &Url)> = urls
+        .iter()
+        .map(|url| match all.iter().find(|e| &e.url == url) {
+            // 25ms buckets so near-equal endpoints do not flip on every sample
+            Some(e) => (
+                !e.usable(),
+                e.lag,
+                e.latency_ms.map_or(u64::MAX, |ms| ms / 25),
+                url,
+            ),
+            None => (false, 0, u64::MAX, url),
+        })
+        .collect();
+    keyed.sort_by_key(|(skipped, lag, latency, _)| (*skipped, *lag, *latency));
+    keyed.into_iter().map(|(.., url)| url.clone()).collect()
+}
+
+/// Best endpoint of `urls`; `urls` must not be empty.
+pub fn best(urls: &[Url]) -> Url {
+    ranked(urls).swap_remove(0)
+}
+
+/// Whether `url` is currently worth sending requests to.
+pub fn is_usable(url: &Url) -> bool {
+    with_endpoint(url, |e| e.usable()).unwrap_or(true)
+}
+
+pub fn report_success(url: &Url, latency: Duration) {
+    with_endpoint(url, |e| {
+        e.failures = 0;
+        e.record_latency(latency);
+    });
+}
+
+/// Count a failed request; a rate-limited endpoint is skipped for a while.
+pub fn report_failure(url: &Url, rate_limited: bool) {
+    let skipped = with_endpoint(url, |e| {
+        let was_usable = e.usable();
+        e.failures = e.failures.saturating_add(1);
+        if rate_limited {
+            e.limited_until = Some(Instant::now() + RATE_LIMIT_COOLDOWN);
+        }
+        (was_usable && !e.usable()).then(|| e.host())
+    });
+    if let Some(Some(host)) = skipped {
+        let why = if rate_limited {
+            "rate limited"
+        } else {
+            "failing"
+        };
+        save_log_to_file(&format!("[rpc] {} {}, failing over", host, why));
+    }
+}
+
+fn with_endpoint<T>(url: &Url, f: impl FnOnce(&mut EndpointStatus) -> T) -> Option<T> {
+    let mut all = ENDPOINTS.write().unwrap_or_else(|e| e.into_inner());
+    all.iter_mut().find(|e| &e.url == url).map(f)
+}
+
+/// Probe every registered endpoint every `RPC_HEALTH_SECS`. HTTP endpoints
+/// are checked through `client`, pinned to one endpoint at a time.
+pub fn spawn_health_checks(client: BscClient) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(Duration::from_secs((*RPC_HEALTH_SECS).max(1)));
+        loop {
+            tick.tick().await;
+            check_all(&client).await;
+        }
+    })
+}
+
+struct Probe {
+    block: u64,
+    latency: Duration,
+    healthy: bool,
+}
+
+async fn check_all(client: &BscClient) {
+    let targets: Vec<(Url, EndpointKind)> =
+        snapshot().into_iter().map(|e| (e.url, e.kind)).collect();
+    let probes = join_all(targets.iter().map(|(url, kind)| async move {
+        let probe = match kind {
+            EndpointKind::Http => probe_http(&client.pinned(url.clone())).await,
+            EndpointKind::Ws => probe_ws(url).await,
+        };
+        (url, probe)
+    }))
+    .await;
+
+    let top = probes
+        .iter()
+        .filter_map(|(_, p)| p.as_ref().ok().map(|p| p.block))
+        .max()
+        .unwrap_or(0);
+    for (url, probe) in probes {
+        let change = with_endpoint(url, |e| {
+            let was_healthy = e.healthy;
+            match &probe {
+                Ok(p) => {
+                    e.block = Some(p.block);
+                    e.lag = top.saturating_sub(p.block);
+                    e.healthy = p.healthy && e.lag <= *RPC_MAX_LAG;
+                    e.failures = 0;
+                    e.record_latency(p.latency);
+                }
+                Err(_) => e.healthy = false,
+            }
+            (was_healthy != e.healthy).then(|| (e.host(), e.healthy, e.lag))
+        });
+        if let Some(Some((host, healthy, lag))) = change {
+            let msg = match (&probe, healthy) {
+                (_, true) => format!("[rpc] {} healthy again", host),
+                (Err(err), _) => format!("[rpc] {} unhealthy: {}", host, err),
+                (Ok(p), _) if !p.healthy => format!("[rpc] {} unhealthy: eth_health false", host),
+                (Ok(_), _) => format!("[rpc] {} unhealthy: {} blocks behind", host, lag),
+            };
+            save_log_to_file(&msg);
+        }
+    }
+}
+
+async fn probe_http(client: &BscClient) -> Result<Probe> {
+    let start = Instant::now();
+    let block = timeout(PROBE_TIMEOUT, client.block_number())
+        .await
+        .context("timed out")??;
+    let latency = start.elapsed();
+    // many nodes do not serve eth_health; only an explicit false counts
+    let healthy = match timeout(PROBE_TIMEOUT, client.health()).await {
+        Ok(res) => res.unwrap_or(true),
+        Err(_) => false,
+    };
+    Ok(Probe {
+        block,
+        latency,
+        healthy,
+    })
+}
+
+async fn probe_ws(url: &Url) -> Result<Probe> {
+    let (mut ws, _) = timeout(PROBE_TIMEOUT, connect_async(url.as_str()))
+        .await
+        .context("connect timed out")??;
+    let req = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_blockNumber",
+        "params": [],
+    });
+    let start = Instant::now();
+    ws.send(Message::Text(req.to_string().into())).await?;
+    let reply = timeout(PROBE_TIMEOUT, async {
+        while let Some(msg) = ws.next().await {
+            if let Message::Text(txt) = msg? {
+                let v: Value = serde_json::from_str(&txt)?;
+                if v.get("id").and_then(|i| i.as_u64()) == Some(1) {
+                    return Ok(v);
+                }
+            }
+        }
+        anyhow::bail!("closed before eth_blockNumber reply")
+    })
+    .await
+    .context("eth_blockNumber timed out")??;
+    let latency = start.elapsed();
+    let _ = ws.close(None).await;
+    let hex = reply
+        .get("result")
+        .and_then(|r| r.as_str())
+        .context("blockNumber not string")?;
+    let block =
+        u64::from_str_radix(hex.trim_start_matches("0x"), 16).context("bad blockNumber hex")?;
+    Ok(Probe {
+        block,
+        latency,
+        healthy: true,
+    })
+}
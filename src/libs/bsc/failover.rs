@@ -0,0 +1,282 @@
+//! Alloy transport over several HTTP RPC endpoints. Each request goes to the
+//! best endpoint in [`endpoints`] and fails over to the next one on transport
+//! errors and rate limits. With broadcasting on, raw transactions are also
+//! sent to every other usable endpoint so they reach the mempool faster.
+//! Lookups of a just-sent tx go to the endpoint that accepted it first, since
+//! other nodes may not have seen it yet and would report it missing. A node
+//! that already has a raw tx (from a broadcast or an earlier attempt) counts
+//! as accepting it. Every raw tx outcome is reported to the nonce manager.
+//! Endpoints that answer "method not found" to an optional method such as
+//! `eth_simulateV1` are remembered and skipped for that method from then on.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use alloy::primitives::{keccak256, Bytes};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{
+    ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload,
+};
+use alloy::transports::http::{reqwest, Http};
+use alloy::transports::{TransportError, TransportErrorKind, TransportFut, TransportResult};
+use tower::Service;
+use url::Url;
+
+use crate::libs::bsc::endpoints::{self, EndpointKind};
+use crate::libs::lookup::save_log_to_file;
+use crate::libs::nonce;
+
+/// Methods routed to the endpoint that accepted the last raw tx
+const PINNED_METHODS: [&str; 3] = [
+    "eth_getTransactionByHash",
+    "eth_getTransactionReceipt",
+    "eth_getTransactionCount",
+];
+/// How long after a send those methods stay pinned
+const PIN_WINDOW: Duration = Duration::from_secs(60);
+/// Methods not every node serves; callers fall back when none does
+const OPTIONAL_METHODS: [&str; 1] = ["eth_simulateV1"];
+
+/// Whether an error reply says the node does not serve the method.
+fn method_missing(err: &ErrorPayload) -> bool {
+    let msg = err.message.to_lowercase();
+    err.code == -32601
+        || msg.contains("method not found")
+        || msg.contains("does not exist")
+        || msg.contains("not supported")
+        || msg.contains("unsupported method")
+}
+
+/// Whether a raw tx was rejected only because the node already has it.
+fn already_known(err: &ErrorPayload) -> bool {
+    let msg = err.message.to_lowercase();
+    msg.contains("already known") || msg.contains("known transaction")
+}
+
+/// Signed bytes of an `eth_sendRawTransaction` request.
+fn raw_tx(req: &RequestPacket) -> Option<Bytes> {
+    let RequestPacket::Single(req) = req else {
+        return None;
+    };
+    if req.method() != "eth_sendRawTransaction" {
+        return None;
+    }
+    let (raw,): (Bytes,) = serde_json::from_str(req.params()?.get()).ok()?;
+    Some(raw)
+}
+
+/// Reply for a raw tx the node already has: the hash of the signed bytes,
+/// which is what accepting it would have returned.
+fn known_tx_reply(req: &RequestPacket) -> Option<ResponsePacket> {
+    let raw = raw_tx(req)?;
+    let RequestPacket::Single(req) = req else {
+        return None;
+    };
+    let hash = serde_json::value::to_raw_value(&keccak256(&raw)).ok()?;
+    Some(ResponsePacket::Single(Response {
+        id: req.id().clone(),
+        payload: ResponsePayload::Success(hash),
+    }))
+}
+
+#[derive(Clone, Debug)]
+pub struct FailoverTransport {
+    urls: Arc<Vec<Url>>,
+    client: reqwest::Client,
+    broadcast: bool,
+    /// Endpoint that accepted the last `eth_sendRawTransaction`, and when
+    last_send: Arc<Mutex<Option<(Url, Instant)>>>,
+    /// (endpoint, method) pairs that answered "method not found"
+    unsupported: Arc<Mutex<HashSet<(Url, &'static str)>>>,
+}
+
+impl FailoverTransport {
+    pub fn new(urls: Vec<Url>, broadcast: bool) -> Self {
+        endpoints::register(EndpointKind::Http, &urls);
+        Self {
+            urls: Arc::new(urls),
+            client: reqwest::Client::new(),
+            broadcast,
+            last_send: Arc::new(Mutex::new(None)),
+            unsupported: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// RPC client for `ProviderBuilder::connect_client`.
+    pub fn into_client(self) -> RpcClient {
+        RpcClient::new(self, false)
+    }
+
+    fn http(&self, url: &Url) -> Http<reqwest::Client> {
+        Http::with_client(self.client.clone(), url.clone())
+    }
+
+    /// Endpoint that accepted a raw tx within the last `PIN_WINDOW`.
+    fn pinned(&self) -> Option<Url> {
+        let last = self.last_send.lock().unwrap_or_else(|e| e.into_inner());
+        last.as_ref()
+            .filter(|(_, at)| at.elapsed() < PIN_WINDOW)
+            .map(|(url, _)| url.clone())
+    }
+
+    /// Optional method in `req`, if any.
+    fn optional_method(req: &RequestPacket) -> Option<&'static str> {
+        OPTIONAL_METHODS
+            .into_iter()
+            .find(|m| req.method_names().any(|n| n == *m))
+    }
+
+    fn supports(&self, url: &Url, method: &'static str) -> bool {
+        let unsupported = self.unsupported.lock().unwrap_or_else(|e| e.into_inner());
+        !unsupported.contains(&(url.clone(), method))
+    }
+
+    async fn request(self, req: RequestPacket) -> TransportResult<ResponsePacket> {
+        let raw = raw_tx(&req);
+        let resp = self.dispatch(req).await;
+        if let Some(raw) = raw {
+            let accepted = resp
+                .as_ref()
+                .is_ok_and(|r| r.iter_errors().next().is_none());
+            nonce::on_broadcast(&raw, accepted).await;
+        }
+        resp
+    }
+
+    async fn dispatch(self, req: RequestPacket) -> TransportResult<ResponsePacket> {
+        let mut order = endpoints::ranked(&self.urls);
+        let optional = Self::optional_method(&req);
+        if let Some(method) = optional {
+            order.retain(|u| self.supports(u, method));
+            if order.is_empty() {
+                return Err(TransportErrorKind::custom_str(&format!(
+                    "{} not supported by any RPC endpoint",
+                    method
+                )));
+            }
+        }
+        let sending = req.method_names().any(|m| m == "eth_sendRawTransaction");
+        if req.method_names().any(|m| PINNED_METHODS.contains(&m)) {
+            if let Some(idx) = self
+                .pinned()
+                .and_then(|p| order.iter().position(|u| *u == p))
+            {
+                let url = order.remove(idx);
+                order.insert(0, url);
+            }
+        }
+        if self.broadcast && sending {
+            for url in order.iter().skip(1).filter(|u| endpoints::is_usable(u)) {
+                let mut http = self.http(url);
+                let req = req.clone();
+                let host = url.host_str().unwrap_or("?").to_string();
+                tokio::spawn(async move {
+                    if let Err(e) = http.call(req).await {
+                        save_log_to_file(&format!("[rpc] broadcast to {} failed: {}", host, e));
+                    }
+                });
+            }
+        }
+
+        let mut last = None;
+        for url in order {
+            let start = Instant::now();
+            match self.http(&url).call(req.clone()).await {
+                Ok(resp) if sending && resp.iter_errors().any(already_known) => {
+                    if let Some(reply) = known_tx_reply(&req) {
+                        endpoints::report_success(&url, start.elapsed());
+                        *self.last_send.lock().unwrap_or_else(|e| e.into_inner()) =
+                            Some((url, Instant::now()));
+                        return Ok(reply);
+                    }
+                    last = Some(Ok(resp));
+                }
+                Ok(resp) if resp.iter_errors().any(|e| e.is_retry_err()) => {
+                    endpoints::report_failure(&url, true);
+                    last = Some(Ok(resp));
+                }
+                Ok(resp) if optional.is_some() && resp.iter_errors().any(method_missing) => {
+                    endpoints::report_success(&url, start.elapsed());
+                    if let Some(method) = optional {
+                        save_log_to_file(&format!(
+                            "[rpc] {} does not serve {}, skipping it for that method",
+                            url.host_str().unwrap_or("?"),
+                            method
+                        ));
+                        self.unsupported
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .insert((url, method));
+                    }
+                    last = Some(Ok(resp));
+                }
+                Ok(resp) => {
+                    endpoints::report_success(&url, start.elapsed());
+                    if sending && resp.iter_errors().next().is_none() {
+                        *self.last_send.lock().unwrap_or_else(|e| e.into_inner()) =
+                            Some((url, Instant::now()));
+                    }
+                    return Ok(resp);
+                }
+                Err(e) => {
+                    let limited = e.as_transport_err().is_some_and(|k| k.is_retry_err());
+                    endpoints::report_failure(&url, limited);
+                    last = Some(Err(e));
+                }
+            }
+        }
+        last.unwrap_or_else(|| Err(TransportErrorKind::custom_str("no RPC endpoint configured")))
+    }
+}
+
+impl Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().request(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::json_rpc::{Id, Request};
+
+    #[test]
+    fn known_tx_reply_hashes_the_raw_tx() {
+        let raw = Bytes::from(vec![0x02, 0xf8, 0x6b, 0x38]);
+        let req = Request::new("eth_sendRawTransaction", Id::Number(7), (raw.clone(),))
+            .serialize()
+            .unwrap();
+        let reply = known_tx_reply(&RequestPacket::Single(req)).unwrap();
+        let ResponsePacket::Single(resp) = reply else {
+            panic!("expected a single response");
+        };
+        assert_eq!(resp.id, Id::Number(7));
+        let hash = resp
+            .try_success_as::<alloy::primitives::B256>()
+            .unwrap()
+            .unwrap();
+        assert_eq!(hash, keccak256(&raw));
+    }
+
+    #[test]
+    fn already_known_matches_geth_and_bsc_replies() {
+        let err = |message: &str| ErrorPayload {
+            code: -32000,
+            message: message.to_string().into(),
+            data: None,
+        };
+        assert!(already_known(&err("already known")));
+        assert!(already_known(&err("Known transaction: 0xabc")));
+        assert!(!already_known(&err("nonce too low")));
+    }
+}
@@ -5,4 +5,6 @@
 //! formatting BNB values.
 /// GPT-5 AGENT GENERATED FILE
 pub mod client;
+pub mod endpoints;
+pub mod failover;
 pub mod spells;
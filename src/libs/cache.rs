@@ -22,6 +22,55 @@ impl Default for SettingsCache {
     }
 }
 
+/// Real position ledger entry (one per open on-chain position)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPosition {
+    pub pair_address: String,
+    pub dex_type: crate::libs::sim::DexType,
+    pub token_out: String,
+    pub base_symbol: String,
+    pub quote_symbol: String,
+    pub entry_price: f64,
+    #[serde(default)]
+    pub peak_price: f64,
+    pub buy_amount_bnb: f64,
+    /// Tokens still held, raw units as a decimal string
+    #[serde(default)]
+    pub tokens_held: Option<String>,
+    /// BNB cost of the part still held (None: full `buy_amount_bnb`)
+    #[serde(default)]
+    pub cost_basis_bnb: Option<f64>,
+    /// Share of the original size still held (None: 1.0)
+    #[serde(default)]
+    pub remaining_fraction: Option<f64>,
+    /// PnL booked by partial sells
+    #[serde(default)]
+    pub realized_bnb: f64,
+    /// Unix seconds when the position was opened
+    pub opened_at: u64,
+    #[serde(default)]
+    pub tp_tiers_hit: usize,
+    /// V3 pool fee tier (None for V2/FourMeme or when unknown)
+    #[serde(default)]
+    pub fee: Option<u32>,
+    /// Intermediate quote token for WBNB -> quote -> token routes
+    #[serde(default)]
+    pub via: Option<String>,
+    /// Frozen from Results (no auto-sell)
+    #[serde(default)]
+    pub frozen: bool,
+    /// Watched wallet the position copies (copy trading)
+    #[serde(default)]
+    pub copied_from: Option<String>,
+}
+
+/// Real position ledger (survives restarts)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PositionsCache {
+    pub positions: Vec<CachedPosition>,
+    pub do_not_rebuy: Vec<String>,
+}
+
 /// Get cache directory path (creates if doesn't exist)
 fn cache_dir() -> Result<PathBuf> {
     let mut path = std::env::current_dir()?;
@@ -46,6 +95,20 @@ fn settings_cache_path() -> Result<PathBuf> {
     Ok(path)
 }
 
+/// Real positions ledger path
+fn positions_cache_path() -> Result<PathBuf> {
+    let mut path = cache_dir()?;
+    path.push("positions.json");
+    Ok(path)
+}
+
+/// Copy-trading watchlist path
+fn copy_wallets_cache_path() -> Result<PathBuf> {
+    let mut path = cache_dir()?;
+    path.push("copy_wallets.json");
+    Ok(path)
+}
+
 /// Load Auto Trade config from cache
 pub fn load_autotrade_cache() -> Result<std::collections::HashMap<String, String>> {
     let path = autotrade_cache_path()?;
@@ -91,3 +154,61 @@ pub fn save_settings_cache(settings: &SettingsCache) -> Result<()> {
     fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
     Ok(())
 }
+
+/// Load real positions ledger from cache
+pub fn load_positions_cache() -> Result<PositionsCache> {
+    let path = positions_cache_path()?;
+    if !path.exists() {
+        return Ok(PositionsCache::default());
+    }
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let ledger: PositionsCache =
+        serde_json::from_str(&contents).with_context(|| "Failed to parse positions cache")?;
+    Ok(ledger)
+}
+
+/// Save real positions ledger to cache. Written to a temp file and renamed
+/// over the ledger, so a crash mid-write never leaves a truncated file.
+pub fn save_positions_cache(ledger: &PositionsCache) -> Result<()> {
+    let path = positions_cache_path()?;
+    let tmp = path.with_extension("json.tmp");
+    let json = serde_json::to_string_pretty(ledger)?;
+    fs::write(&tmp, json).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Move an unreadable positions ledger aside so it is not overwritten by the
+/// next save. Returns where it was moved.
+pub fn quarantine_positions_cache() -> Result<PathBuf> {
+    let path = positions_cache_path()?;
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let aside = path.with_extension(format!("json.bad-{}", stamp));
+    fs::rename(&path, &aside).with_context(|| format!("Failed to move {}", path.display()))?;
+    Ok(aside)
+}
+
+/// Load the copy-trading watchlist from cache
+pub fn load_copy_wallets() -> Result<Vec<String>> {
+    let path = copy_wallets_cache_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let wallets: Vec<String> =
+        serde_json::from_str(&contents).with_context(|| "Failed to parse copy wallets cache")?;
+    Ok(wallets)
+}
+
+/// Save the copy-trading watchlist to cache
+pub fn save_copy_wallets(wallets: &[String]) -> Result<()> {
+    let path = copy_wallets_cache_path()?;
+    let json = serde_json::to_string_pretty(wallets)?;
+    fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
@@ -10,18 +10,37 @@ pub fn load_env() {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
-    pub bsc_wss: String,
+    /// Optional; without it logs are polled over `bsc_rpc`. Comma-separated
+    /// list of endpoints; each reconnect picks the healthiest one.
+    pub bsc_wss: Option<String>,
     pub private_key: String,
+    /// Comma-separated list of HTTP endpoints; reads go to the healthiest
+    /// one and fail over to the others
     pub bsc_rpc: String,
+    /// `RPC_BROADCAST`: also send raw transactions to every other healthy endpoint
+    pub rpc_broadcast: bool,
+    /// `LOG_SOURCE`: "ws" or "poll" (default: ws when BSC_WSS is set)
+    pub log_source: String,
+    /// `POLL_INTERVAL_MS`: how often the poll source asks for new logs
+    pub poll_interval_ms: u64,
 }
 
 impl Config {
     pub fn new() -> Self {
+        let bsc_wss = std::env::var("BSC_WSS")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let log_source = std::env::var("LOG_SOURCE")
+            .map(|v| v.trim().to_ascii_lowercase())
+            .unwrap_or_else(|_| if bsc_wss.is_some() { "ws" } else { "poll" }.to_string());
         Self {
-            bsc_wss: std::env::var("BSC_WSS").expect("config.rs:BSC_WSS is not set"),
+            bsc_wss,
             private_key: std::env::var("PRIVATE_KEY").expect("config.rs: PRIVATE_KEY is not set"),
             bsc_rpc: std::env::var("BSC_RPC")
                 .unwrap_or_else(|_| "https://bsc-dataseed.binance.org".to_string()),
+            rpc_broadcast: Self::get_var_t("RPC_BROADCAST", false),
+            log_source,
+            poll_interval_ms: Self::get_var_t("POLL_INTERVAL_MS", 1000),
         }
     }
 
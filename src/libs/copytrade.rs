@@ -0,0 +1,173 @@
+//! Copy trading: a watchlist of wallets whose buys and sells are mirrored.
+//!
+//! The swap trackers and the four.meme stream report every swap sent by a
+//! watched wallet as a [`CopySignal`]. The app queues an entry when a watched
+//! wallet buys and takes it on a later pair update once the configured delay
+//! passed; a position opened that way remembers the wallet so the wallet's sell
+//! can exit it. The watchlist is kept in `.cache/copy_wallets.json`.
+
+use std::collections::HashSet;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use alloy::primitives::{Address, B256};
+use anyhow::Result;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use tokio::sync::broadcast;
+
+use crate::libs::cache::{load_copy_wallets, save_copy_wallets};
+use crate::libs::lookup::save_log_to_file;
+
+/// How long a queued entry stays valid after its delay passed
+const ENTRY_TTL: Duration = Duration::from_secs(60);
+
+/// A swap sent by a watched wallet.
+#[derive(Clone, Copy, Debug)]
+pub struct CopySignal {
+    /// Pair, pool or four.meme token traded
+    pub pair: Address,
+    pub wallet: Address,
+    pub is_buy: bool,
+    pub tx_hash: B256,
+}
+
+static WATCHLIST: Lazy<RwLock<HashSet<Address>>> = Lazy::new(|| RwLock::new(HashSet::new()));
+static SIGNALS: Lazy<broadcast::Sender<CopySignal>> = Lazy::new(|| broadcast::channel(256).0);
+/// pair key -> (wallet, entry allowed from)
+static PENDING: Lazy<DashMap<String, (Address, Instant)>> = Lazy::new(DashMap::new);
+/// pair key -> wallet the open position copies
+static COPIED: Lazy<DashMap<String, Address>> = Lazy::new(DashMap::new);
+
+fn pair_key(pair: Address) -> String {
+    format!("{:#x}", pair)
+}
+
+/// Shortened wallet for logs and the TUI, e.g. `0x1234…abcd`.
+pub fn short_wallet(wallet: Address) -> String {
+    let s = format!("{:#x}", wallet);
+    format!("{}…{}", &s[..6], &s[s.len() - 4..])
+}
+
+/// Load the watchlist from `.cache`. Returns the number of wallets.
+pub fn load_watchlist() -> usize {
+    let wallets = match load_copy_wallets() {
+        Ok(w) => w,
+        Err(e) => {
+            save_log_to_file(&format!("[copy] failed to load watchlist: {}", e));
+            return 0;
+        }
+    };
+    let mut list = WATCHLIST.write().unwrap_or_else(|e| e.into_inner());
+    list.extend(
+        wallets
+            .iter()
+            .filter_map(|w| w.trim().parse::<Address>().ok()),
+    );
+    list.len()
+}
+
+/// Add `wallet` to the watchlist, or remove it when already watched, and save
+/// the list. Returns true when the wallet is now watched.
+pub fn toggle_wallet(wallet: Address) -> Result<bool> {
+    let (watched, saved) = {
+        let mut list = WATCHLIST.write().unwrap_or_else(|e| e.into_inner());
+        let watched = if list.remove(&wallet) {
+            false
+        } else {
+            list.insert(wallet);
+            true
+        };
+        let mut saved: Vec<String> = list.iter().map(|w| format!("{:#x}", w)).collect();
+        saved.sort();
+        (watched, saved)
+    };
+    save_copy_wallets(&saved)?;
+    Ok(watched)
+}
+
+/// Watched wallets, sorted.
+pub fn wallets() -> Vec<Address> {
+    let mut list: Vec<Address> = WATCHLIST
+        .read()
+        .map(|l| l.iter().copied().collect())
+        .unwrap_or_default();
+    list.sort();
+    list
+}
+
+/// Whether any wallet is watched (senders only need resolving then).
+pub fn watching() -> bool {
+    WATCHLIST.read().map(|l| !l.is_empty()).unwrap_or(false)
+}
+
+pub fn is_watched(wallet: Address) -> bool {
+    WATCHLIST
+        .read()
+        .map(|l| l.contains(&wallet))
+        .unwrap_or(false)
+}
+
+/// Report a swap on `pair` sent by `wallet`; published when the wallet is watched.
+pub fn report_swap(pair: Address, wallet: Address, is_buy: bool, tx_hash: B256) {
+    if !is_watched(wallet) {
+        return;
+    }
+    save_log_to_file(&format!(
+        "[copy] {} {} {:#x} tx={:#x}",
+        short_wallet(wallet),
+        if is_buy { "bought" } else { "sold" },
+        pair,
+        tx_hash
+    ));
+    // No receiver just means copy trading is not running
+    let _ = SIGNALS.send(CopySignal {
+        pair,
+        wallet,
+        is_buy,
+        tx_hash,
+    });
+}
+
+pub fn subscribe() -> broadcast::Receiver<CopySignal> {
+    SIGNALS.subscribe()
+}
+
+/// Queue an entry on `pair` copying `wallet`, allowed once `delay` passed.
+pub fn queue_entry(pair: Address, wallet: Address, delay: Duration) {
+    PENDING.insert(pair_key(pair), (wallet, Instant::now() + delay));
+}
+
+/// Wallet whose queued entry on `pair` is due now. Expired entries are dropped.
+pub fn ready_entry(pair: Address) -> Option<Address> {
+    let key = pair_key(pair);
+    let (wallet, at) = *PENDING.get(&key)?;
+    let now = Instant::now();
+    if now < at {
+        return None;
+    }
+    if now.duration_since(at) > ENTRY_TTL {
+        PENDING.remove(&key);
+        return None;
+    }
+    Some(wallet)
+}
+
+/// Drop the queued entry on `pair` (taken, or its wallet already sold).
+pub fn cancel_entry(pair: Address) {
+    PENDING.remove(&pair_key(pair));
+}
+
+/// Remember that the position on `pair_key` copies `wallet`.
+pub fn mark_copied(pair_key: &str, wallet: Address) {
+    COPIED.insert(pair_key.to_string(), wallet);
+}
+
+/// Wallet the position on `pair_key` copies, if it was opened by copy trading.
+pub fn copied_from(pair_key: &str) -> Option<Address> {
+    COPIED.get(pair_key).map(|w| *w)
+}
+
+pub fn forget_copied(pair_key: &str) {
+    COPIED.remove(pair_key);
+}
@@ -0,0 +1,213 @@
+//! Creator ("dev") wallets per token and how much of their holdings they sold.
+//!
+//! A token's creators are the sender of the tx that created its pair, pool or
+//! four.meme listing, plus its `owner()` when set: plain RPC cannot look up a
+//! contract's deployer, and launch templates leave the deployer as owner. A
+//! wallet a creator sent the token to counts as creator-funded and is treated
+//! as a dev wallet too; transfers are searched from `LOOKBACK_BLOCKS` before
+//! the listing, since launches fund side wallets before adding liquidity.
+//! Dev sells are sized as sold / (sold + still held). Routers and four.meme
+//! contracts never count as creators, and neither does the sender of a
+//! four.meme migration.
+
+use std::collections::HashSet;
+
+use alloy::consensus::Transaction as _;
+use alloy::network::TransactionResponse;
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::eth::Filter;
+use alloy::sol_types::SolEvent;
+use dashmap::DashMap;
+use fourmeme::{TOKEN_MANAGER_2, TOKEN_MANAGER_HELPER_3, TOKEN_MANAGER_V1};
+use once_cell::sync::Lazy;
+use pancakes::pancake::pancake_swap::addresses::{PANCAKE_SMART_ROUTER, PANCAKE_V3_SWAP_ROUTER};
+use pancakes::pancake::pancake_swap_v2::addresses::PANCAKE_V2_ROUTER;
+
+use crate::libs::lookup::{pct_of, save_log_to_file};
+
+alloy::sol! {
+    #[sol(rpc)]
+    interface IOwnable {
+        function owner() view returns (address);
+    }
+
+    #[sol(rpc)]
+    interface IERC20Holdings {
+        function balanceOf(address owner) view returns (uint256);
+    }
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+}
+
+/// Blocks searched for creator transfers before the listing, or before the
+/// current block when the listing block is unknown
+const LOOKBACK_BLOCKS: u64 = 5_000;
+
+/// four.meme contracts; a pool created through them is a migration
+const FOURMEME_MANAGERS: [Address; 3] = [TOKEN_MANAGER_V1, TOKEN_MANAGER_2, TOKEN_MANAGER_HELPER_3];
+
+/// Contracts that send or own tokens on someone else's behalf
+const NOT_CREATORS: [Address; 6] = [
+    PANCAKE_V2_ROUTER,
+    PANCAKE_V3_SWAP_ROUTER,
+    PANCAKE_SMART_ROUTER,
+    TOKEN_MANAGER_V1,
+    TOKEN_MANAGER_2,
+    TOKEN_MANAGER_HELPER_3,
+];
+
+#[derive(Default)]
+struct DevWallets {
+    wallets: HashSet<Address>,
+    /// Traders already checked and not funded by a dev wallet
+    cleared: HashSet<Address>,
+    /// Tokens sold by dev wallets, raw units
+    sold: U256,
+    /// Block the token was listed in
+    from_block: Option<u64>,
+    /// Tracked pairs of the token; it is forgotten with the last one
+    pairs: HashSet<Address>,
+}
+
+/// token -> its dev wallets
+static DEVS: Lazy<DashMap<Address, DevWallets>> = Lazy::new(DashMap::new);
+/// pair -> share of dev holdings sold (%)
+static DEV_SOLD: Lazy<DashMap<Address, f64>> = Lazy::new(DashMap::new);
+
+/// Record `wallet` as a creator of `token` traded on `pair`.
+pub fn add_creator(pair: Address, token: Address, wallet: Address, block: Option<u64>) {
+    if wallet == Address::ZERO || NOT_CREATORS.contains(&wallet) {
+        return;
+    }
+    let mut devs = DEVS.entry(token).or_default();
+    devs.pairs.insert(pair);
+    devs.wallets.insert(wallet);
+    devs.cleared.remove(&wallet);
+    if devs.from_block.is_none() {
+        devs.from_block = block;
+    }
+}
+
+/// Learn the creators of `token` from the tx that listed it on `pair` and its
+/// `owner()`. For four.meme listings `pair` is the token itself.
+pub async fn learn_creators<P: Provider>(
+    provider: &P,
+    pair: Address,
+    token: Address,
+    creation_tx: Option<B256>,
+    block: Option<u64>,
+) {
+    if let Some(hash) = creation_tx {
+        if let Ok(Some(tx)) = provider.get_transaction_by_hash(hash).await {
+            let migration =
+                pair != token && tx.to().is_some_and(|to| FOURMEME_MANAGERS.contains(&to));
+            if !migration {
+                add_creator(pair, token, tx.from(), block);
+            }
+        }
+    }
+    if let Ok(owner) = IOwnable::new(token, provider).owner().call().await {
+        add_creator(pair, token, owner, block);
+    }
+}
+
+/// Drop what is known about `pair`, and about its token once no other
+/// tracked pair trades it.
+pub fn forget_pair(pair: Address) {
+    DEV_SOLD.remove(&pair);
+    DEVS.retain(|_, d| !(d.pairs.remove(&pair) && d.pairs.is_empty()));
+}
+
+/// Whether any creator of `token` is known.
+pub fn has_creators(token: Address) -> bool {
+    DEVS.contains_key(&token)
+}
+
+/// Whether `trader` is a creator of `token` or was sent the token by one.
+async fn is_dev_wallet<P: Provider>(provider: &P, token: Address, trader: Address) -> bool {
+    let (devs, from_block) = {
+        let Some(d) = DEVS.get(&token) else {
+            return false;
+        };
+        if d.wallets.contains(&trader) {
+            return true;
+        }
+        if d.cleared.contains(&trader) {
+            return false;
+        }
+        (
+            d.wallets
+                .iter()
+                .map(|w| w.into_word())
+                .collect::<Vec<B256>>(),
+            d.from_block,
+        )
+    };
+    let from_block = match from_block {
+        Some(b) => b.saturating_sub(LOOKBACK_BLOCKS),
+        None => provider
+            .get_block_number()
+            .await
+            .map(|n| n.saturating_sub(LOOKBACK_BLOCKS))
+            .unwrap_or(0),
+    };
+    let filter = Filter::new()
+        .address(token)
+        .event_signature(Transfer::SIGNATURE_HASH)
+        .topic1(devs)
+        .topic2(trader.into_word())
+        .from_block(from_block);
+    let Ok(logs) = provider.get_logs(&filter).await else {
+        return false;
+    };
+    let funded = !logs.is_empty();
+    if let Some(mut d) = DEVS.get_mut(&token) {
+        if funded {
+            d.wallets.insert(trader);
+        } else {
+            d.cleared.insert(trader);
+        }
+    }
+    funded
+}
+
+/// Check a sell of `tokens` of `token` on `pair` by `trader`. When the trader
+/// is a dev wallet the sell is added to the dev total; returns the share of dev
+/// holdings sold so far (%).
+pub async fn check_sell<P: Provider>(
+    provider: &P,
+    pair: Address,
+    token: Address,
+    trader: Address,
+    tx_hash: B256,
+    tokens: U256,
+) -> Option<f64> {
+    if !is_dev_wallet(provider, token, trader).await {
+        return None;
+    }
+    let wallets: Vec<Address> = DEVS.get(&token)?.wallets.iter().copied().collect();
+    let erc20 = IERC20Holdings::new(token, provider);
+    let mut held = U256::ZERO;
+    for wallet in wallets {
+        held += erc20.balanceOf(wallet).call().await.unwrap_or_default();
+    }
+    let sold = {
+        let mut d = DEVS.get_mut(&token)?;
+        d.sold += tokens;
+        d.sold
+    };
+    // nothing sold or held reads as nothing sold
+    let pct = pct_of(sold, sold + held).unwrap_or(0.0);
+    DEV_SOLD.insert(pair, pct);
+    save_log_to_file(&format!(
+        "[dev] {:#x} sold {} of {:#x} on {:#x} (dev sold {:.1}%) tx={:#x}",
+        trader, tokens, token, pair, pct, tx_hash
+    ));
+    Some(pct)
+}
+
+/// Share of dev holdings sold on `pair` (%), once a dev sell was seen.
+pub fn dev_sold_pct(pair: Address) -> Option<f64> {
+    DEV_SOLD.get(&pair).map(|v| *v)
+}
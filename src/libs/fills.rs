@@ -0,0 +1,266 @@
+//! Trade fills read from receipts. Pancake swaps move WBNB and the token
+//! through ERC20 `Transfer`s to and from the wallet (net of transfer taxes);
+//! four.meme trades settle in native BNB and report cost and fee in
+//! `TokenPurchase` / `TokenSale`. Positions are valued at these fills instead
+//! of the stream price and intended size.
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionReceipt;
+use alloy::sol_types::SolEvent;
+use pancakes::pancake::pancake_swap::addresses::WBNB;
+use pancakes::tx::TxSide;
+
+alloy::sol! {
+    #[sol(rpc)]
+    interface IERC20Decimals {
+        function decimals() view returns (uint8);
+    }
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+
+    // four.meme TokenManager2 (fields are not indexed)
+    event TokenPurchase(address token, address account, uint256 price, uint256 amount, uint256 cost, uint256 fee, uint256 offers, uint256 funds);
+    event TokenSale(address token, address account, uint256 price, uint256 amount, uint256 cost, uint256 fee, uint256 offers, uint256 funds);
+}
+
+fn wei_to_bnb(wei: U256) -> f64 {
+    u128::try_from(wei).map(|v| v as f64 / 1e18).unwrap_or(0.0)
+}
+
+/// What one trade actually moved for the wallet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fill {
+    /// Tokens received (buy) or sent (sell), raw units
+    pub tokens: U256,
+    /// BNB/WBNB spent (buy) or received (sell); None when the receipt does not show it
+    pub bnb: Option<f64>,
+    /// Venue fee in BNB (four.meme), already included in `bnb`
+    pub fee_bnb: f64,
+    /// Gas paid in BNB
+    pub gas_bnb: f64,
+}
+
+impl Fill {
+    /// BNB per whole token.
+    pub fn price(&self, decimals: u8) -> Option<f64> {
+        let tokens = wei_to_bnb(self.tokens) * 10f64.powi(18 - decimals as i32);
+        match self.bnb {
+            Some(bnb) if tokens > 0.0 && bnb > 0.0 => Some(bnb / tokens),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse_fill(
+    receipt: &TransactionReceipt,
+    wallet: Address,
+    token: Address,
+    side: TxSide,
+) -> Fill {
+    let mut tokens = U256::ZERO;
+    let mut wbnb = U256::ZERO;
+    let mut fm: Option<(U256, U256, U256)> = None;
+    for log in receipt.logs() {
+        let addr = log.address();
+        let topic0 = log.topic0().copied();
+        if topic0 == Some(Transfer::SIGNATURE_HASH) {
+            let Ok(t) = Transfer::decode_log_data(log.data()) else {
+                continue;
+            };
+            let ours = match side {
+                TxSide::Buy => t.to == wallet,
+                TxSide::Sell => t.from == wallet,
+            };
+            if addr == token && ours {
+                tokens += t.value;
+            }
+            // WBNB leaves the wallet on buys and comes back on sells
+            let wbnb_ours = match side {
+                TxSide::Buy => t.from == wallet,
+                TxSide::Sell => t.to == wallet,
+            };
+            if addr == WBNB && wbnb_ours {
+                wbnb += t.value;
+            }
+        } else if topic0 == Some(TokenPurchase::SIGNATURE_HASH) && side == TxSide::Buy {
+            if let Ok(e) = TokenPurchase::decode_log_data(log.data()) {
+                if e.token == token && e.account == wallet {
+                    fm = Some((e.amount, e.cost + e.fee, e.fee));
+                }
+            }
+        } else if topic0 == Some(TokenSale::SIGNATURE_HASH) && side == TxSide::Sell {
+            if let Ok(e) = TokenSale::decode_log_data(log.data()) {
+                if e.token == token && e.account == wallet {
+                    fm = Some((e.amount, e.cost.saturating_sub(e.fee), e.fee));
+                }
+            }
+        }
+    }
+
+    let (bnb, fee_bnb) = match fm {
+        Some((amount, bnb, fee)) => {
+            if tokens.is_zero() {
+                tokens = amount;
+            }
+            (Some(wei_to_bnb(bnb)), wei_to_bnb(fee))
+        }
+        None if !wbnb.is_zero() => (Some(wei_to_bnb(wbnb)), 0.0),
+        None => (None, 0.0),
+    };
+    let gas_wei = U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price);
+    Fill {
+        tokens,
+        bnb,
+        fee_bnb,
+        gas_bnb: wei_to_bnb(gas_wei),
+    }
+}
+
+/// Fetch the receipt of `tx` and parse the wallet's fill for `token`.
+pub async fn fetch_fill<P: Provider>(
+    provider: &P,
+    tx: B256,
+    wallet: Address,
+    token: Address,
+    side: TxSide,
+) -> Option<Fill> {
+    let receipt = provider.get_transaction_receipt(tx).await.ok()??;
+    receipt
+        .status()
+        .then(|| parse_fill(&receipt, wallet, token, side))
+}
+
+pub async fn token_decimals<P: Provider>(provider: &P, token: Address) -> Option<u8> {
+    IERC20Decimals::new(token, provider)
+        .decimals()
+        .call()
+        .await
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::consensus::{Receipt, ReceiptEnvelope, ReceiptWithBloom};
+    use alloy::primitives::{Bloom, LogData};
+    use alloy::rpc::types::Log;
+
+    const WALLET: Address = Address::repeat_byte(0xaa);
+    const TOKEN: Address = Address::repeat_byte(0x70);
+    const POOL: Address = Address::repeat_byte(0x99);
+
+    fn log(address: Address, data: LogData) -> Log {
+        Log {
+            inner: alloy::primitives::Log { address, data },
+            ..Default::default()
+        }
+    }
+
+    fn transfer(address: Address, from: Address, to: Address, value: u128) -> Log {
+        let event = Transfer {
+            from,
+            to,
+            value: U256::from(value),
+        };
+        log(address, event.encode_log_data())
+    }
+
+    fn receipt(logs: Vec<Log>) -> TransactionReceipt {
+        TransactionReceipt {
+            inner: ReceiptEnvelope::Legacy(ReceiptWithBloom {
+                receipt: Receipt {
+                    status: true.into(),
+                    cumulative_gas_used: 100_000,
+                    logs,
+                },
+                logs_bloom: Bloom::default(),
+            }),
+            transaction_hash: B256::ZERO,
+            transaction_index: None,
+            block_hash: None,
+            block_number: None,
+            gas_used: 100_000,
+            effective_gas_price: 1_000_000_000,
+            blob_gas_used: None,
+            blob_gas_price: None,
+            from: WALLET,
+            to: None,
+            contract_address: None,
+        }
+    }
+
+    #[test]
+    fn pancake_buy_counts_wallet_transfers() {
+        let r = receipt(vec![
+            transfer(WBNB, WALLET, POOL, 500_000_000_000_000_000),
+            transfer(TOKEN, POOL, WALLET, 900),
+            // tax skimmed to another address is not ours
+            transfer(TOKEN, POOL, Address::repeat_byte(0x01), 100),
+        ]);
+        let fill = parse_fill(&r, WALLET, TOKEN, TxSide::Buy);
+        assert_eq!(fill.tokens, U256::from(900));
+        assert_eq!(fill.bnb, Some(0.5));
+        assert_eq!(fill.fee_bnb, 0.0);
+        assert!((fill.gas_bnb - 0.0001).abs() < 1e-12);
+    }
+
+    #[test]
+    fn pancake_sell_counts_wbnb_received() {
+        let r = receipt(vec![
+            transfer(TOKEN, WALLET, POOL, 1_000),
+            transfer(WBNB, POOL, WALLET, 250_000_000_000_000_000),
+        ]);
+        let fill = parse_fill(&r, WALLET, TOKEN, TxSide::Sell);
+        assert_eq!(fill.tokens, U256::from(1_000));
+        assert_eq!(fill.bnb, Some(0.25));
+    }
+
+    #[test]
+    fn fourmeme_buy_includes_fee() {
+        let event = TokenPurchase {
+            token: TOKEN,
+            account: WALLET,
+            price: U256::ZERO,
+            amount: U256::from(5_000),
+            cost: U256::from(990_000_000_000_000_000u128),
+            fee: U256::from(10_000_000_000_000_000u128),
+            offers: U256::ZERO,
+            funds: U256::ZERO,
+        };
+        let r = receipt(vec![log(TOKEN, event.encode_log_data())]);
+        let fill = parse_fill(&r, WALLET, TOKEN, TxSide::Buy);
+        assert_eq!(fill.tokens, U256::from(5_000));
+        assert_eq!(fill.bnb, Some(1.0));
+        assert_eq!(fill.fee_bnb, 0.01);
+    }
+
+    #[test]
+    fn fourmeme_sell_nets_fee() {
+        let event = TokenSale {
+            token: TOKEN,
+            account: WALLET,
+            price: U256::ZERO,
+            amount: U256::from(5_000),
+            cost: U256::from(1_000_000_000_000_000_000u128),
+            fee: U256::from(10_000_000_000_000_000u128),
+            offers: U256::ZERO,
+            funds: U256::ZERO,
+        };
+        let r = receipt(vec![
+            transfer(TOKEN, WALLET, TOKEN, 5_000),
+            log(TOKEN, event.encode_log_data()),
+        ]);
+        let fill = parse_fill(&r, WALLET, TOKEN, TxSide::Sell);
+        assert_eq!(fill.tokens, U256::from(5_000));
+        assert_eq!(fill.bnb, Some(0.99));
+    }
+
+    #[test]
+    fn unknown_bnb_without_wbnb_or_event() {
+        let r = receipt(vec![transfer(TOKEN, POOL, WALLET, 42)]);
+        let fill = parse_fill(&r, WALLET, TOKEN, TxSide::Buy);
+        assert_eq!(fill.tokens, U256::from(42));
+        assert_eq!(fill.bnb, None);
+    }
+}
@@ -0,0 +1,200 @@
+//! Gas pricing. `gas_mode` picks the price sent with every transaction:
+//! - `fixed`: `max_gwei`
+//! - `oracle`: base fee plus the median priority fee of the last blocks
+//! - `oracle_plus`: the oracle price plus `gas_oracle_pct`
+//!
+//! Every mode is capped at `max_gwei`. Emergency exits bid above the regular
+//! price up to their own `emergency_max_gwei` ceiling. Stuck transactions are rebroadcast by
+//! `pancakes::tx` with the replacement policies set from config here.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::utils::parse_units;
+use alloy::providers::Provider;
+use once_cell::sync::Lazy;
+use pancakes::tx::{set_replace_policy, ReplacePolicy, TxSide};
+
+use crate::libs::lookup::save_log_to_file;
+use crate::libs::tui::ConfigStore;
+
+/// Selectable modes, in Auto Trade cycling order.
+pub const GAS_MODES: [&str; 3] = ["fixed", "oracle", "oracle_plus"];
+
+/// Mode that follows `mode` in `GAS_MODES`.
+pub fn next_gas_mode(mode: &str) -> &'static str {
+    let idx = GAS_MODES.iter().position(|m| *m == mode).unwrap_or(0);
+    GAS_MODES[(idx + 1) % GAS_MODES.len()]
+}
+
+const DEFAULT_GAS_WEI: u128 = 1_000_000_000; // 1 gwei
+const DEFAULT_EMERGENCY_GAS_WEI: u128 = 3_000_000_000; // 3 gwei
+const GWEI_DECIMALS: u8 = 9;
+/// Blocks sampled by the oracle
+const ORACLE_BLOCKS: u64 = 10;
+const ORACLE_TTL: Duration = Duration::from_secs(3);
+const MAX_BUMPS: u32 = 3;
+
+/// Last oracle price and when it was fetched
+static ORACLE: Lazy<Mutex<Option<(Instant, u128)>>> = Lazy::new(|| Mutex::new(None));
+
+fn cfg_u64(config_store: &ConfigStore, key: &str, default: u64) -> u64 {
+    config_store
+        .get(key)
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(default)
+}
+
+fn cfg_gwei(config_store: &ConfigStore, key: &str, default: u128) -> u128 {
+    config_store
+        .get(key)
+        .and_then(|v| parse_units(v.as_str(), GWEI_DECIMALS).ok())
+        .and_then(|wei| wei.try_into().ok())
+        .filter(|wei| *wei > 0)
+        .unwrap_or(default)
+}
+
+/// `max_gwei` in wei; the fixed price and the cap for the oracle modes.
+pub fn max_gas_price_wei(config_store: &ConfigStore) -> u128 {
+    cfg_gwei(config_store, "max_gwei", DEFAULT_GAS_WEI)
+}
+
+/// `emergency_max_gwei` in wei, never below `max_gwei`; the cap for emergency exits.
+pub fn emergency_max_gas_price_wei(config_store: &ConfigStore) -> u128 {
+    cfg_gwei(
+        config_store,
+        "emergency_max_gwei",
+        DEFAULT_EMERGENCY_GAS_WEI,
+    )
+    .max(max_gas_price_wei(config_store))
+}
+
+/// Base fee of the next block plus the median priority fee paid over the
+/// last `ORACLE_BLOCKS`, falling back to `eth_gasPrice`.
+async fn oracle_price_wei<P: Provider>(provider: &P) -> Option<u128> {
+    if let Some((at, price)) = *ORACLE.lock().unwrap() {
+        if at.elapsed() < ORACLE_TTL {
+            return Some(price);
+        }
+    }
+    let price = match provider
+        .get_fee_history(ORACLE_BLOCKS, BlockNumberOrTag::Latest, &[50.0])
+        .await
+    {
+        Ok(history) => {
+            let base = history.latest_block_base_fee().unwrap_or(0);
+            let mut tips: Vec<u128> = history
+                .reward
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|r| r.first().copied())
+                .collect();
+            tips.sort_unstable();
+            let tip = tips.get(tips.len() / 2).copied().unwrap_or(0);
+            Some(base + tip).filter(|p| *p > 0)
+        }
+        Err(_) => None,
+    };
+    let price = match price {
+        Some(p) => p,
+        None => provider.get_gas_price().await.ok()?,
+    };
+    *ORACLE.lock().unwrap() = Some((Instant::now(), price));
+    Some(price)
+}
+
+/// Gas price for the next transaction per `gas_mode`, capped at `max_gwei`.
+pub async fn gas_price_wei<P: Provider>(provider: &P, config_store: &ConfigStore) -> u128 {
+    let cap = max_gas_price_wei(config_store);
+    let mode = config_store
+        .get("gas_mode")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "fixed".to_string());
+    let bump_pct = match mode.as_str() {
+        "oracle" => 0,
+        "oracle_plus" => cfg_u64(config_store, "gas_oracle_pct", 10),
+        _ => return cap,
+    };
+    match oracle_price_wei(provider).await {
+        Some(price) => (price * (100 + bump_pct as u128) / 100).min(cap),
+        None => {
+            save_log_to_file(&format!(
+                "[gas] oracle unavailable; using max_gwei ({} wei)",
+                cap
+            ));
+            cap
+        }
+    }
+}
+
+/// Gas price for emergency exits: the regular price bumped by
+/// `sell_gas_bump_pct`, capped at `emergency_max_gwei`.
+pub async fn emergency_gas_price_wei<P: Provider>(
+    provider: &P,
+    config_store: &ConfigStore,
+) -> u128 {
+    let price = gas_price_wei(provider, config_store).await;
+    let bump_pct = cfg_u64(config_store, "sell_gas_bump_pct", 30) as u128;
+    let cap = emergency_max_gas_price_wei(config_store);
+    let bumped = (price * (100 + bump_pct) / 100).min(cap);
+    if bumped <= price {
+        save_log_to_file(&format!(
+            "[gas] emergency exit cannot outbid {} wei: emergency_max_gwei is {} wei",
+            price, cap
+        ));
+    }
+    bumped
+}
+
+/// Push the stuck-transaction replacement settings to `pancakes::tx`. Sells
+/// bump harder so exits are not left pending; both stay capped at `max_gwei`,
+/// so a tx already sent at the cap (fixed gas mode) is not replaced.
+pub fn apply_replace_policy(config_store: &ConfigStore) {
+    let after_blocks = cfg_u64(config_store, "replace_after_blocks", 5);
+    let cap = max_gas_price_wei(config_store);
+    set_replace_policy(
+        TxSide::Buy,
+        ReplacePolicy {
+            after_blocks,
+            bump_pct: cfg_u64(config_store, "buy_gas_bump_pct", 15),
+            max_bumps: MAX_BUMPS,
+            max_gas_price: cap,
+        },
+    );
+    set_replace_policy(
+        TxSide::Sell,
+        ReplacePolicy {
+            after_blocks,
+            bump_pct: cfg_u64(config_store, "sell_gas_bump_pct", 30),
+            max_bumps: MAX_BUMPS,
+            max_gas_price: cap,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_gas_mode_cycles() {
+        assert_eq!(next_gas_mode("fixed"), "oracle");
+        assert_eq!(next_gas_mode("oracle"), "oracle_plus");
+        assert_eq!(next_gas_mode("oracle_plus"), "fixed");
+    }
+
+    #[test]
+    fn next_gas_mode_unknown_restarts() {
+        assert_eq!(next_gas_mode("bogus"), "oracle");
+    }
+
+    #[test]
+    fn emergency_cap_is_never_below_max_gwei() {
+        let store: ConfigStore = Default::default();
+        store.insert("max_gwei".into(), "1.0".into());
+        assert_eq!(emergency_max_gas_price_wei(&store), 3_000_000_000);
+        store.insert("emergency_max_gwei".into(), "0.5".into());
+        assert_eq!(emergency_max_gas_price_wei(&store), 1_000_000_000);
+    }
+}
@@ -0,0 +1,331 @@
+//! Risk governor: tracks realized PnL and BNB deployed over rolling windows for
+//! sim and real trading, and trips a circuit breaker that turns auto trade off
+//! once a loss or exposure limit is hit. The breaker stays tripped until reset.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+use crate::libs::cache::save_autotrade_cache;
+use crate::libs::lookup::save_log_to_file;
+use crate::libs::sim::SimEngine;
+use crate::libs::tui::ConfigStore;
+
+const LOSS_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+const DEPLOY_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Limits from config; 0 disables a limit.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    pub max_daily_loss_bnb: f64,
+    pub max_losing_streak: u32,
+    pub max_bnb_per_hour: f64,
+}
+
+pub fn limits_cfg(config_store: &ConfigStore) -> Limits {
+    let f = |key: &str, default: f64| {
+        config_store
+            .get(key)
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(default)
+    };
+    Limits {
+        max_daily_loss_bnb: f("max_daily_loss_bnb", 0.05),
+        max_losing_streak: config_store
+            .get("max_losing_streak")
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(5),
+        max_bnb_per_hour: f("max_bnb_per_hour", 0.5),
+    }
+}
+
+/// Why and when the breaker tripped.
+#[derive(Clone, Debug)]
+pub struct Trip {
+    /// "sim" or "trade"
+    pub mode: &'static str,
+    pub reason: String,
+    pub at: Instant,
+}
+
+/// Rolling figures for one mode.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BookStats {
+    /// Realized PnL over the last 24h
+    pub daily_pnl_bnb: f64,
+    pub losing_streak: u32,
+    /// BNB spent on buys over the last hour
+    pub hourly_deployed_bnb: f64,
+}
+
+#[derive(Default)]
+struct Book {
+    /// (at, realized PnL in BNB)
+    pnl: VecDeque<(Instant, f64)>,
+    /// (at, BNB spent on a buy)
+    deployed: VecDeque<(Instant, f64)>,
+    losing_streak: u32,
+}
+
+fn drop_older(q: &mut VecDeque<(Instant, f64)>, window: Duration, now: Instant) {
+    while q
+        .front()
+        .is_some_and(|(at, _)| now.duration_since(*at) > window)
+    {
+        q.pop_front();
+    }
+}
+
+impl Book {
+    fn prune(&mut self, now: Instant) {
+        drop_older(&mut self.pnl, LOSS_WINDOW, now);
+        drop_older(&mut self.deployed, DEPLOY_WINDOW, now);
+    }
+
+    fn stats(&mut self, now: Instant) -> BookStats {
+        self.prune(now);
+        BookStats {
+            daily_pnl_bnb: self.pnl.iter().map(|(_, v)| v).sum(),
+            losing_streak: self.losing_streak,
+            hourly_deployed_bnb: self.deployed.iter().map(|(_, v)| v).sum(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Governor {
+    sim: Book,
+    real: Book,
+    /// Closed sim positions already accounted for
+    sim_seen: usize,
+    trip: Option<Trip>,
+}
+
+impl Governor {
+    fn book(&mut self, mode: &'static str) -> &mut Book {
+        if mode == "sim" {
+            &mut self.sim
+        } else {
+            &mut self.real
+        }
+    }
+
+    /// The first limit `mode` is over at `now`, if any.
+    fn breach(&mut self, mode: &'static str, limits: &Limits, now: Instant) -> Option<String> {
+        let s = self.book(mode).stats(now);
+        let reason = if limits.max_daily_loss_bnb > 0.0
+            && -s.daily_pnl_bnb >= limits.max_daily_loss_bnb
+        {
+            format!(
+                "24h loss {:.4} BNB >= limit {:.4}",
+                -s.daily_pnl_bnb, limits.max_daily_loss_bnb
+            )
+        } else if limits.max_losing_streak > 0 && s.losing_streak >= limits.max_losing_streak {
+            format!("{} losses in a row", s.losing_streak)
+        } else if limits.max_bnb_per_hour > 0.0 && s.hourly_deployed_bnb >= limits.max_bnb_per_hour
+        {
+            format!(
+                "{:.4} BNB deployed in 1h >= limit {:.4}",
+                s.hourly_deployed_bnb, limits.max_bnb_per_hour
+            )
+        } else {
+            return None;
+        };
+        Some(reason)
+    }
+
+    fn check(&mut self, mode: &'static str, config_store: &ConfigStore) {
+        if self.trip.is_some() {
+            return;
+        }
+        let Some(reason) = self.breach(mode, &limits_cfg(config_store), Instant::now()) else {
+            return;
+        };
+
+        config_store.insert("enabled".into(), "false".into());
+        let _ = save_autotrade_cache(config_store);
+        save_log_to_file(&format!(
+            "[risk] CIRCUIT BREAKER ({}) tripped: {}; auto trade disabled",
+            mode, reason
+        ));
+        self.trip = Some(Trip {
+            mode,
+            reason,
+            at: Instant::now(),
+        });
+    }
+
+    fn pnl(&mut self, mode: &'static str, pnl_bnb: f64, position_pnl: Option<f64>, now: Instant) {
+        let book = self.book(mode);
+        book.pnl.push_back((now, pnl_bnb));
+        if let Some(total) = position_pnl {
+            if total < 0.0 {
+                book.losing_streak += 1;
+            } else {
+                book.losing_streak = 0;
+            }
+        }
+    }
+
+    /// Book the PnL of sim closes past `sim_seen` when `sim_on`, else only
+    /// skip them. Returns whether any were booked.
+    fn observe_sim<I>(&mut self, closed_pnls: I, sim_on: bool, now: Instant) -> bool
+    where
+        I: ExactSizeIterator<Item = f64>,
+    {
+        let closed = closed_pnls.len();
+        if closed < self.sim_seen {
+            // Sim was reset
+            self.sim_seen = 0;
+        }
+        if closed == self.sim_seen {
+            return false;
+        }
+        if sim_on {
+            for pnl in closed_pnls.skip(self.sim_seen) {
+                self.pnl("sim", pnl, Some(pnl), now);
+            }
+        }
+        self.sim_seen = closed;
+        sim_on
+    }
+}
+
+static GOVERNOR: Lazy<Mutex<Governor>> = Lazy::new(|| Mutex::new(Governor::default()));
+
+/// Count `bnb` spent on a buy against the hourly deployment limit.
+pub fn record_buy(mode: &'static str, bnb: f64, config_store: &ConfigStore) {
+    let mut g = GOVERNOR.lock().unwrap();
+    g.book(mode).deployed.push_back((Instant::now(), bnb));
+    g.check(mode, config_store);
+}
+
+/// Record realized PnL of one sell. `position_pnl` is the position's total
+/// PnL when this sell closed it; only closed positions move the losing streak.
+pub fn record_pnl(
+    mode: &'static str,
+    pnl_bnb: f64,
+    position_pnl: Option<f64>,
+    config_store: &ConfigStore,
+) {
+    let mut g = GOVERNOR.lock().unwrap();
+    g.pnl(mode, pnl_bnb, position_pnl, Instant::now());
+    g.check(mode, config_store);
+}
+
+/// Account for sim positions closed since the last call. With `sim_on` false
+/// the closes are mirrors of real trades (already recorded) and only skipped.
+pub fn observe_sim(sim_engine: &SimEngine, sim_on: bool, config_store: &ConfigStore) {
+    let closed = sim_engine.closed_positions().iter().map(|p| p.pnl_wbnb);
+    let mut g = GOVERNOR.lock().unwrap();
+    if g.observe_sim(closed, sim_on, Instant::now()) {
+        g.check("sim", config_store);
+    }
+}
+
+pub fn tripped() -> Option<Trip> {
+    GOVERNOR.lock().unwrap().trip.clone()
+}
+
+pub fn stats(mode: &'static str) -> BookStats {
+    GOVERNOR.lock().unwrap().book(mode).stats(Instant::now())
+}
+
+/// Clear the breaker and the rolling windows, and turn auto trade back on.
+pub fn reset(config_store: &ConfigStore) {
+    let mut g = GOVERNOR.lock().unwrap();
+    let sim_seen = g.sim_seen;
+    *g = Governor {
+        sim_seen,
+        ..Governor::default()
+    };
+    config_store.insert("enabled".into(), "true".into());
+    let _ = save_autotrade_cache(config_store);
+    save_log_to_file("[risk] circuit breaker reset; auto trade enabled");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    fn limits(max_daily_loss_bnb: f64, max_losing_streak: u32, max_bnb_per_hour: f64) -> Limits {
+        Limits {
+            max_daily_loss_bnb,
+            max_losing_streak,
+            max_bnb_per_hour,
+        }
+    }
+
+    #[test]
+    fn windows_drop_entries_past_their_age() {
+        let t0 = Instant::now();
+        let mut book = Book::default();
+        book.pnl.push_back((t0, -0.01));
+        book.deployed.push_back((t0, 0.1));
+        book.deployed.push_back((t0 + HOUR, 0.2));
+
+        let s = book.stats(t0 + HOUR + Duration::from_secs(1));
+        assert_eq!(s.hourly_deployed_bnb, 0.2);
+        assert_eq!(s.daily_pnl_bnb, -0.01);
+
+        let s = book.stats(t0 + 24 * HOUR + Duration::from_secs(1));
+        assert_eq!(s.daily_pnl_bnb, 0.0);
+        assert!(book.pnl.is_empty());
+    }
+
+    #[test]
+    fn winning_close_resets_the_losing_streak() {
+        let now = Instant::now();
+        let mut g = Governor::default();
+        g.pnl("trade", -0.01, Some(-0.01), now);
+        g.pnl("trade", -0.02, Some(-0.02), now);
+        // a partial sell does not close the position
+        g.pnl("trade", 0.05, None, now);
+        assert_eq!(g.real.losing_streak, 2);
+        assert!(g.breach("trade", &limits(0.0, 2, 0.0), now).is_some());
+
+        g.pnl("trade", 0.01, Some(0.03), now);
+        assert_eq!(g.real.losing_streak, 0);
+        assert!(g.breach("trade", &limits(0.0, 2, 0.0), now).is_none());
+    }
+
+    #[test]
+    fn daily_loss_trips_at_the_limit() {
+        let t0 = Instant::now();
+        let mut g = Governor::default();
+        let l = limits(0.05, 0, 0.0);
+        g.pnl("trade", -0.025, None, t0);
+        assert!(g.breach("trade", &l, t0).is_none());
+        g.pnl("trade", -0.025, None, t0);
+        assert!(g.breach("trade", &l, t0).is_some());
+        // the other mode's book is separate
+        assert!(g.breach("sim", &l, t0).is_none());
+        assert!(g
+            .breach("trade", &l, t0 + 24 * HOUR + Duration::from_secs(1))
+            .is_none());
+    }
+
+    #[test]
+    fn observe_sim_resyncs_after_a_sim_reset() {
+        let now = Instant::now();
+        let mut g = Governor::default();
+        assert!(g.observe_sim([-0.01, -0.01, -0.01].into_iter(), true, now));
+        assert_eq!(g.sim_seen, 3);
+        assert_eq!(g.sim.losing_streak, 3);
+        assert!(!g.observe_sim([-0.01, -0.01, -0.01].into_iter(), true, now));
+
+        // fewer closes than seen: the sim was reset and the new one is booked
+        assert!(g.observe_sim([-0.02].into_iter(), true, now));
+        assert_eq!(g.sim_seen, 1);
+        assert_eq!(g.sim.losing_streak, 4);
+
+        // mirrors of real trades are skipped, not booked
+        assert!(!g.observe_sim([-0.02, -0.01].into_iter(), false, now));
+        assert_eq!(g.sim_seen, 2);
+        assert_eq!(g.sim.losing_streak, 4);
+    }
+}
@@ -0,0 +1,558 @@
+//! Pre-buy honeypot check: simulate a buy followed by an immediate sell
+//! (`eth_simulateV1` for Pancake, Helper3 `tryBuy`/`trySell` for four.meme)
+//! and measure the buy/sell tax from the simulated balance deltas.
+//!
+//! The failover transport remembers which endpoints lack `eth_simulateV1` and
+//! routes simulations to the ones that serve it. When none do, the check falls
+//! back to a quote-only round trip: the buy and the sell are priced with
+//! `eth_call` on `getAmountsOut` / the V3 quoter. That catches dead pools but
+//! cannot see transfer taxes, so such reports only pass when the user opts in.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::simulate::{SimBlock, SimCallResult, SimulatePayload};
+use alloy::rpc::types::state::StateOverridesBuilder;
+use alloy::rpc::types::TransactionRequest;
+use alloy::sol_types::SolCall;
+use alloy::transports::{RpcError, TransportErrorKind};
+use anyhow::{anyhow, Result};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+use pancakes::pancake::pancake_swap::addresses::{
+    PANCAKE_V3_QUOTER_V2, PANCAKE_V3_SWAP_ROUTER, WBNB,
+};
+use pancakes::pancake::pancake_swap::{encode_v3_path, PancakeV3};
+use pancakes::pancake::pancake_swap_v2::addresses::PANCAKE_V2_ROUTER;
+
+use crate::libs::lookup::save_log_to_file;
+use crate::libs::sim::DexType;
+use crate::libs::ws::pairs::PairInfo;
+use crate::routy::route::Route;
+use crate::routy::v3 as routy_v3;
+
+alloy::sol! {
+    interface ISimErc20 {
+        function deposit() payable;
+        function approve(address spender, uint256 value) returns (bool);
+        function balanceOf(address owner) view returns (uint256);
+    }
+
+    interface ISimV2Router {
+        function getAmountsOut(uint amountIn, address[] calldata path) view returns (uint[] memory amounts);
+        function swapExactTokensForTokensSupportingFeeOnTransferTokens(
+            uint amountIn,
+            uint amountOutMin,
+            address[] calldata path,
+            address to,
+            uint deadline
+        );
+    }
+
+    interface ISimV3Router {
+        struct ExactInputParams {
+            bytes   path;
+            address recipient;
+            uint256 deadline;
+            uint256 amountIn;
+            uint256 amountOutMinimum;
+        }
+        function exactInput(ExactInputParams calldata params) payable returns (uint256 amountOut);
+    }
+
+    interface ISimV3Quoter {
+        function quoteExactInput(bytes path, uint256 amountIn)
+            returns (uint256 amountOut, uint160[] sqrtPriceX96AfterList, uint32[] initializedTicksCrossedList, uint256 gasEstimate);
+    }
+}
+
+/// Outcome of a simulated buy + immediate sell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TaxReport {
+    pub buy_tax_pct: f64,
+    pub sell_tax_pct: f64,
+    /// BNB lost over the round trip (taxes + LP fees + price impact)
+    pub round_trip_loss_pct: f64,
+    pub sellable: bool,
+    /// Priced with quotes only (no `eth_simulateV1`); taxes were not measured
+    pub quote_only: bool,
+}
+
+impl TaxReport {
+    fn unsellable(buy_tax_pct: f64) -> Self {
+        Self {
+            buy_tax_pct,
+            sell_tax_pct: 100.0,
+            round_trip_loss_pct: 100.0,
+            sellable: false,
+            quote_only: false,
+        }
+    }
+
+    /// Reason to skip the token under `max_tax_pct`, if any. Quote-only
+    /// reports are rejected unless `quote_only_ok`, since they cannot see taxes.
+    pub fn rejection(&self, max_tax_pct: f64, quote_only_ok: bool) -> Option<String> {
+        if !self.sellable {
+            return Some("honeypot: simulated sell failed".to_string());
+        }
+        if self.quote_only && !quote_only_ok {
+            return Some("taxes unverified: no RPC serves eth_simulateV1".to_string());
+        }
+        if self.round_trip_loss_pct > max_tax_pct {
+            return Some(format!(
+                "round-trip loss {:.1}% > max {:.1}% (buy tax {:.1}% / sell tax {:.1}%)",
+                self.round_trip_loss_pct, max_tax_pct, self.buy_tax_pct, self.sell_tax_pct
+            ));
+        }
+        None
+    }
+
+    /// Short Home feed label
+    pub fn label(&self) -> String {
+        if self.sellable && self.quote_only {
+            "Tax: ?".to_string()
+        } else if self.sellable {
+            format!("Tax: {:.0}/{:.0}%", self.buy_tax_pct, self.sell_tax_pct)
+        } else {
+            "HONEYPOT".to_string()
+        }
+    }
+}
+
+/// Finished checks keyed by pair address, with the buy size they simulated
+static TAX_REPORTS: Lazy<DashMap<Address, (TaxReport, U256)>> = Lazy::new(DashMap::new);
+/// Last attempt per pair; failed checks are retried after `RETRY_AFTER`
+static CHECK_ATTEMPTS: Lazy<DashMap<Address, Instant>> = Lazy::new(DashMap::new);
+const RETRY_AFTER: Duration = Duration::from_secs(60);
+
+pub fn cached_report(pair: Address) -> Option<TaxReport> {
+    TAX_REPORTS.get(&pair).map(|r| r.0)
+}
+
+/// Whether a round trip of `measured` stands for a buy of `amount_in`. Price
+/// impact grows with size, so a buy over 25% larger needs its own check.
+fn covers(measured: U256, amount_in: U256) -> bool {
+    amount_in <= measured.saturating_add(measured / U256::from(4u8))
+}
+
+/// The cached report, if it was measured at a size that covers `amount_in`.
+pub fn cached_report_for(pair: Address, amount_in: U256) -> Option<TaxReport> {
+    TAX_REPORTS
+        .get(&pair)
+        .filter(|r| covers(r.1, amount_in))
+        .map(|r| r.0)
+}
+
+/// Whether no check of `pair` was attempted within the retry window.
+pub fn check_due(pair: Address) -> bool {
+    CHECK_ATTEMPTS
+        .get(&pair)
+        .is_none_or(|at| at.elapsed() >= RETRY_AFTER)
+}
+
+/// Drop the report and retry state of a pruned pair.
+pub fn forget(pair: Address) {
+    TAX_REPORTS.remove(&pair);
+    CHECK_ATTEMPTS.remove(&pair);
+}
+
+fn u256_f64(v: U256) -> f64 {
+    v.to_string().parse::<f64>().unwrap_or(0.0)
+}
+
+/// How far `actual` fell short of `expected`, in % (0 when nothing was expected).
+fn shortfall_pct(actual: U256, expected: U256) -> f64 {
+    if expected.is_zero() {
+        return 0.0;
+    }
+    ((1.0 - u256_f64(actual) / u256_f64(expected)) * 100.0).clamp(0.0, 100.0)
+}
+
+fn deadline() -> U256 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    U256::from(now + 300)
+}
+
+fn sim_call(from: Address, to: Address, data: Vec<u8>) -> TransactionRequest {
+    TransactionRequest::default()
+        .from(from)
+        .to(to)
+        .input(Bytes::from(data).into())
+}
+
+/// Where the round trip is executed
+enum Venue {
+    V2 {
+        buy: Vec<Address>,
+        sell: Vec<Address>,
+    },
+    V3 {
+        buy: Bytes,
+        sell: Bytes,
+    },
+}
+
+impl Venue {
+    fn router(&self) -> Address {
+        match self {
+            Venue::V2 { .. } => PANCAKE_V2_ROUTER,
+            Venue::V3 { .. } => PANCAKE_V3_SWAP_ROUTER,
+        }
+    }
+
+    /// Reserve/quoter expectation ignoring token transfer taxes
+    fn quote(&self, from: Address, buy: bool, amount_in: U256) -> TransactionRequest {
+        match self {
+            Venue::V2 { buy: b, sell: s } => {
+                let path = if buy { b.clone() } else { s.clone() };
+                let data = ISimV2Router::getAmountsOutCall {
+                    amountIn: amount_in,
+                    path,
+                }
+                .abi_encode();
+                sim_call(from, PANCAKE_V2_ROUTER, data)
+            }
+            Venue::V3 { buy: b, sell: s } => {
+                let path = if buy { b.clone() } else { s.clone() };
+                let data = ISimV3Quoter::quoteExactInputCall {
+                    path,
+                    amountIn: amount_in,
+                }
+                .abi_encode();
+                sim_call(from, PANCAKE_V3_QUOTER_V2, data)
+            }
+        }
+    }
+
+    fn decode_quote(&self, res: &SimCallResult) -> U256 {
+        if !res.status {
+            return U256::ZERO;
+        }
+        self.decode_quote_data(&res.return_data)
+    }
+
+    fn decode_quote_data(&self, data: &[u8]) -> U256 {
+        match self {
+            Venue::V2 { .. } => ISimV2Router::getAmountsOutCall::abi_decode_returns(data)
+                .ok()
+                .and_then(|amounts| amounts.last().copied())
+                .unwrap_or(U256::ZERO),
+            Venue::V3 { .. } => ISimV3Quoter::quoteExactInputCall::abi_decode_returns(data)
+                .map(|r| r.amountOut)
+                .unwrap_or(U256::ZERO),
+        }
+    }
+
+    fn swap(&self, from: Address, buy: bool, amount_in: U256) -> TransactionRequest {
+        match self {
+            Venue::V2 { buy: b, sell: s } => {
+                let path = if buy { b.clone() } else { s.clone() };
+                let data =
+                    ISimV2Router::swapExactTokensForTokensSupportingFeeOnTransferTokensCall {
+                        amountIn: amount_in,
+                        amountOutMin: U256::ZERO,
+                        path,
+                        to: from,
+                        deadline: deadline(),
+                    }
+                    .abi_encode();
+                sim_call(from, PANCAKE_V2_ROUTER, data)
+            }
+            Venue::V3 { buy: b, sell: s } => {
+                let path = if buy { b.clone() } else { s.clone() };
+                let data = ISimV3Router::exactInputCall {
+                    params: ISimV3Router::ExactInputParams {
+                        path,
+                        recipient: from,
+                        deadline: deadline(),
+                        amountIn: amount_in,
+                        amountOutMinimum: U256::ZERO,
+                    },
+                }
+                .abi_encode();
+                sim_call(from, PANCAKE_V3_SWAP_ROUTER, data)
+            }
+        }
+    }
+}
+
+fn balance_of(from: Address, token: Address) -> TransactionRequest {
+    sim_call(
+        from,
+        token,
+        ISimErc20::balanceOfCall { owner: from }.abi_encode(),
+    )
+}
+
+fn approve(from: Address, token: Address, spender: Address) -> TransactionRequest {
+    sim_call(
+        from,
+        token,
+        ISimErc20::approveCall {
+            spender,
+            value: U256::MAX,
+        }
+        .abi_encode(),
+    )
+}
+
+fn decode_balance(res: &SimCallResult) -> U256 {
+    ISimErc20::balanceOfCall::abi_decode_returns(&res.return_data).unwrap_or(U256::ZERO)
+}
+
+async fn simulate<P: Provider + Clone>(
+    provider: &P,
+    from: Address,
+    calls: Vec<TransactionRequest>,
+) -> Result<Vec<SimCallResult>> {
+    // Fund the wallet inside the simulation so the check works before any wrap
+    let overrides = StateOverridesBuilder::default()
+        .with_balance(from, U256::from(10u64).pow(U256::from(21u64)))
+        .build();
+    let payload = SimulatePayload::default().extend(
+        SimBlock::default()
+            .with_state_overrides(overrides)
+            .extend_calls(calls),
+    );
+    let blocks = provider.simulate(&payload).await?;
+    blocks
+        .into_iter()
+        .next()
+        .map(|b| b.calls)
+        .ok_or_else(|| anyhow!("eth_simulateV1 returned no blocks"))
+}
+
+async fn pancake_round_trip<P: Provider + Clone>(
+    provider: &P,
+    from: Address,
+    token: Address,
+    venue: &Venue,
+    amount_in: U256,
+) -> Result<TaxReport> {
+    let router = venue.router();
+    let setup = vec![
+        sim_call(from, WBNB, ISimErc20::depositCall {}.abi_encode()).value(amount_in),
+        approve(from, WBNB, router),
+        balance_of(from, token),
+        venue.quote(from, true, amount_in),
+        venue.swap(from, true, amount_in),
+        balance_of(from, token),
+    ];
+    let res = simulate(provider, from, setup.clone()).await?;
+    if res.len() < 6 {
+        return Err(anyhow!("simulation returned {} of 6 calls", res.len()));
+    }
+    if !res[4].status {
+        // The buy itself reverts: nothing we could ever hold or sell
+        return Ok(TaxReport::unsellable(100.0));
+    }
+    let received = decode_balance(&res[5]).saturating_sub(decode_balance(&res[2]));
+    let buy_tax_pct = shortfall_pct(received, venue.decode_quote(&res[3]));
+    if received.is_zero() {
+        return Ok(TaxReport::unsellable(buy_tax_pct));
+    }
+
+    // Replay the buy, then sell exactly what it delivered
+    let mut calls = setup;
+    calls.extend([
+        approve(from, token, router),
+        venue.quote(from, false, received),
+        balance_of(from, WBNB),
+        venue.swap(from, false, received),
+        balance_of(from, WBNB),
+    ]);
+    let res = simulate(provider, from, calls).await?;
+    if res.len() < 11 || !res[9].status {
+        return Ok(TaxReport::unsellable(buy_tax_pct));
+    }
+    let back = decode_balance(&res[10]).saturating_sub(decode_balance(&res[8]));
+    if back.is_zero() {
+        return Ok(TaxReport::unsellable(buy_tax_pct));
+    }
+    Ok(TaxReport {
+        buy_tax_pct,
+        sell_tax_pct: shortfall_pct(back, venue.decode_quote(&res[7])),
+        round_trip_loss_pct: shortfall_pct(back, amount_in),
+        sellable: true,
+        quote_only: false,
+    })
+}
+
+/// Whether `err` says the node does not serve `eth_simulateV1`.
+fn is_unsupported(err: &anyhow::Error) -> bool {
+    if let Some(payload) = err
+        .downcast_ref::<RpcError<TransportErrorKind>>()
+        .and_then(|e| e.as_error_resp())
+    {
+        if payload.code == -32601 {
+            return true;
+        }
+    }
+    let msg = err.to_string().to_lowercase();
+    msg.contains("method not found")
+        || msg.contains("does not exist")
+        || msg.contains("not supported")
+        || msg.contains("unsupported method")
+        || msg.contains("not available")
+}
+
+/// Quote-only round trip for RPCs without `eth_simulateV1`: price the buy,
+/// then the sell of the quoted amount, with plain `eth_call`s.
+async fn quote_round_trip<P: Provider + Clone>(
+    provider: &P,
+    from: Address,
+    venue: &Venue,
+    amount_in: U256,
+) -> Result<TaxReport> {
+    let bought = venue.decode_quote_data(&provider.call(venue.quote(from, true, amount_in)).await?);
+    if bought.is_zero() {
+        return Ok(TaxReport::unsellable(100.0));
+    }
+    let back = venue.decode_quote_data(&provider.call(venue.quote(from, false, bought)).await?);
+    if back.is_zero() {
+        return Ok(TaxReport::unsellable(0.0));
+    }
+    Ok(TaxReport {
+        buy_tax_pct: 0.0,
+        sell_tax_pct: 0.0,
+        round_trip_loss_pct: shortfall_pct(back, amount_in),
+        sellable: true,
+        quote_only: true,
+    })
+}
+
+/// Simulated round trip, or the quote-only one when no endpoint simulates.
+async fn venue_round_trip<P: Provider + Clone>(
+    provider: &P,
+    from: Address,
+    token: Address,
+    venue: &Venue,
+    amount_in: U256,
+) -> Result<TaxReport> {
+    match pancake_round_trip(provider, from, token, venue, amount_in).await {
+        Err(e) if is_unsupported(&e) => quote_round_trip(provider, from, venue, amount_in).await,
+        res => res,
+    }
+}
+
+async fn fourmeme_round_trip<P: Provider + Clone>(
+    provider: P,
+    token: Address,
+    amount_in: U256,
+) -> Result<TaxReport> {
+    let buy = fourmeme::trade::try_buy(provider.clone(), token, U256::ZERO, amount_in).await?;
+    let buy_tax_pct = if amount_in.is_zero() {
+        0.0
+    } else {
+        (u256_f64(buy.estimated_fee) / u256_f64(amount_in) * 100.0).clamp(0.0, 100.0)
+    };
+    let sell = match fourmeme::trade::try_sell(provider, token, buy.estimated_amount).await {
+        Ok(s) if !s.funds.is_zero() => s,
+        _ => return Ok(TaxReport::unsellable(buy_tax_pct)),
+    };
+    let gross = sell.funds.saturating_add(sell.fee);
+    Ok(TaxReport {
+        buy_tax_pct,
+        sell_tax_pct: shortfall_pct(sell.funds, gross),
+        round_trip_loss_pct: shortfall_pct(sell.funds, amount_in),
+        sellable: true,
+        quote_only: false,
+    })
+}
+
+/// Simulate buying `amount_in` BNB worth of the pair's token and selling it
+/// straight back. The result is cached per pair and logged.
+pub async fn check_pair<P: Provider + Clone>(
+    provider: P,
+    from: Address,
+    dex_type: DexType,
+    pair_info: &PairInfo,
+    amount_in: U256,
+) -> Result<TaxReport> {
+    CHECK_ATTEMPTS.insert(pair_info.pair, Instant::now());
+    let report = match dex_type {
+        DexType::FourMeme => fourmeme_round_trip(provider, pair_info.addr1, amount_in).await?,
+        DexType::V2 | DexType::V3 => {
+            let route = Route::for_pair(pair_info.addr1, pair_info.addr2, pair_info.fee)
+                .ok_or_else(|| anyhow!("no WBNB route for {}", pair_info.symbol_base))?;
+            let venue = if dex_type == DexType::V2 {
+                Venue::V2 {
+                    buy: route.v2_buy_path(),
+                    sell: route.v2_sell_path(),
+                }
+            } else {
+                let pancake = PancakeV3::new(provider.clone());
+                let (token_fee, quote_fee) = routy_v3::route_fees(&pancake, &route).await?;
+                match route.via {
+                    Some(q) => Venue::V3 {
+                        buy: encode_v3_path(&[WBNB, q, route.token], &[quote_fee, token_fee]),
+                        sell: encode_v3_path(&[route.token, q, WBNB], &[token_fee, quote_fee]),
+                    },
+                    None => Venue::V3 {
+                        buy: encode_v3_path(&[WBNB, route.token], &[token_fee]),
+                        sell: encode_v3_path(&[route.token, WBNB], &[token_fee]),
+                    },
+                }
+            };
+            venue_round_trip(&provider, from, route.token, &venue, amount_in).await?
+        }
+    };
+    save_log_to_file(&format!(
+        "[tax] {} buy {:.1}% sell {:.1}% round-trip {:.1}%{}{}",
+        pair_info.symbol_base,
+        report.buy_tax_pct,
+        report.sell_tax_pct,
+        report.round_trip_loss_pct,
+        if report.sellable { "" } else { " HONEYPOT" },
+        if report.quote_only {
+            " (quote only)"
+        } else {
+            ""
+        }
+    ));
+    TAX_REPORTS.insert(pair_info.pair, (report, amount_in));
+    Ok(report)
+}
+
+/// Run `check_pair` in the background unless the pair was checked already
+/// or attempted within the retry window.
+pub fn spawn_check<P>(
+    provider: P,
+    from: Address,
+    dex_type: DexType,
+    pair_info: PairInfo,
+    amount_in: U256,
+) where
+    P: Provider + Clone + Send + Sync + 'static,
+{
+    if TAX_REPORTS.contains_key(&pair_info.pair) || !check_due(pair_info.pair) {
+        return;
+    }
+    CHECK_ATTEMPTS.insert(pair_info.pair, Instant::now());
+    tokio::spawn(async move {
+        if let Err(e) = check_pair(provider, from, dex_type, &pair_info, amount_in).await {
+            save_log_to_file(&format!(
+                "[tax] check failed for {}: {}",
+                pair_info.symbol_base, e
+            ));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_covers_buys_up_to_a_quarter_larger() {
+        let measured = U256::from(100u64);
+        assert!(covers(measured, U256::from(40u64)));
+        assert!(covers(measured, U256::from(125u64)));
+        assert!(!covers(measured, U256::from(126u64)));
+    }
+}
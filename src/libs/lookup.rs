@@ -2,7 +2,7 @@ use std::io::{self, Write};
 
 use anyhow::Result;
 
-use alloy::primitives::{Address, B256};
+use alloy::primitives::{Address, B256, U256};
 use alloy::providers::Provider;
 use alloy::rpc::types::eth::TransactionReceipt;
 
@@ -119,3 +119,34 @@ pub fn trim_chars(s: &str, max: usize) -> String {
     }
     out
 }
+
+/// Share `part / whole` in %, capped at 100; None when `whole` is zero.
+pub fn pct_of(part: U256, whole: U256) -> Option<f64> {
+    if whole.is_zero() {
+        return None;
+    }
+    let scaled = part.saturating_mul(U256::from(10_000u64)) / whole;
+    Some((u64::try_from(scaled).unwrap_or(u64::MAX) as f64 / 100.0).min(100.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pct_of_share() {
+        assert_eq!(pct_of(U256::from(25u64), U256::from(100u64)), Some(25.0));
+        assert_eq!(pct_of(U256::from(1u64), U256::from(3u64)), Some(33.33));
+    }
+
+    #[test]
+    fn pct_of_caps_at_100() {
+        assert_eq!(pct_of(U256::from(150u64), U256::from(100u64)), Some(100.0));
+        assert_eq!(pct_of(U256::MAX, U256::from(1u64)), Some(100.0));
+    }
+
+    #[test]
+    fn pct_of_empty_whole() {
+        assert_eq!(pct_of(U256::from(1u64), U256::ZERO), None);
+    }
+}
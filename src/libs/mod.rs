@@ -1,9 +1,19 @@
 pub mod bsc;
 pub mod cache;
 pub mod config;
+pub mod copytrade;
+pub mod devwatch;
+pub mod fills;
+pub mod gas;
+pub mod governor;
+pub mod honeypot;
 pub mod lookup;
+pub mod nonce;
 pub mod price;
+pub mod risk;
 pub mod sim;
+pub mod sizing;
+pub mod strategy;
 pub mod tui;
 pub mod writing;
 pub mod ws;
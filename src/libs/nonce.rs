@@ -0,0 +1,200 @@
+//! Local nonce manager for the signer. Every provider built with `manager()`
+//! shares one counter per address, so concurrent approvals, wraps, buys and
+//! sells get distinct nonces instead of racing on `eth_getTransactionCount`.
+//!
+//! A nonce is in flight from allocation until its raw tx is broadcast, which
+//! the RPC transport reports through `on_broadcast`. The counter is lowered to
+//! the pending count only while nothing is in flight: when the account was
+//! idle, after a nonce error, after a rejected broadcast, or once an allocation
+//! expires unbroadcast. alloy fills the nonce concurrently with the gas
+//! estimate, so a send whose estimate reverts has taken a nonce that never
+//! reaches the mempool; without the re-sync later sends would queue behind it.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use alloy::consensus::transaction::SignerRecoverable;
+use alloy::consensus::{Transaction as _, TxEnvelope};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::network::Network;
+use alloy::primitives::Address;
+use alloy::providers::fillers::NonceManager;
+use alloy::providers::Provider;
+use alloy::transports::TransportResult;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use tokio::sync::Mutex;
+
+use crate::libs::lookup::save_log_to_file;
+
+/// Allocations closer together than this are a burst and skip the chain check
+const GAP_CHECK_AFTER: Duration = Duration::from_secs(15);
+/// An allocation not broadcast within this long was abandoned by its send
+const IN_FLIGHT_FOR: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Default)]
+struct NonceState {
+    /// Next nonce to hand out; None until fetched
+    next: Option<u64>,
+    last_alloc: Option<Instant>,
+    /// Nonces handed out but not broadcast yet, and when
+    in_flight: BTreeMap<u64, Instant>,
+}
+
+#[derive(Debug, Default)]
+struct NonceSlot {
+    state: Mutex<NonceState>,
+    /// A handed-out nonce may never reach the mempool; re-sync when idle
+    stale: AtomicBool,
+}
+
+static NONCES: Lazy<DashMap<Address, Arc<NonceSlot>>> = Lazy::new(DashMap::new);
+
+fn slot(address: Address) -> Arc<NonceSlot> {
+    NONCES.entry(address).or_default().clone()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LocalNonceManager;
+
+pub fn manager() -> LocalNonceManager {
+    LocalNonceManager
+}
+
+#[async_trait]
+impl NonceManager for LocalNonceManager {
+    async fn get_next_nonce<P, N>(&self, provider: &P, address: Address) -> TransportResult<u64>
+    where
+        P: Provider<N>,
+        N: Network,
+    {
+        let slot = slot(address);
+        let mut state = slot.state.lock().await;
+        let before = state.in_flight.len();
+        state.in_flight.retain(|_, at| at.elapsed() < IN_FLIGHT_FOR);
+        if state.in_flight.len() < before {
+            save_log_to_file(&format!(
+                "[nonce] {} allocation(s) never broadcast",
+                before - state.in_flight.len()
+            ));
+            slot.stale.store(true, Ordering::Relaxed);
+        }
+        let idle = state
+            .last_alloc
+            .is_none_or(|at| at.elapsed() >= GAP_CHECK_AFTER);
+        // Lowering `next` under a nonce another task still holds would reuse it
+        let check = state.in_flight.is_empty() && (idle || slot.stale.load(Ordering::Relaxed));
+        let nonce = match state.next {
+            Some(next) if !check => next,
+            local => {
+                let pending = provider.get_transaction_count(address).pending().await?;
+                slot.stale.store(false, Ordering::Relaxed);
+                match local {
+                    Some(next) if next > pending => save_log_to_file(&format!(
+                        "[nonce] gap: local {} > pending {}, re-synced",
+                        next, pending
+                    )),
+                    Some(next) if next < pending => save_log_to_file(&format!(
+                        "[nonce] {} tx(s) sent outside the manager, skipping to {}",
+                        pending - next,
+                        pending
+                    )),
+                    _ => {}
+                }
+                pending
+            }
+        };
+        state.next = Some(nonce + 1);
+        state.last_alloc = Some(Instant::now());
+        state.in_flight.insert(nonce, Instant::now());
+        Ok(nonce)
+    }
+}
+
+/// Mark the counter for a re-sync from the pending count. It happens on the
+/// next allocation that finds no nonce in flight.
+pub fn resync(address: Address) {
+    slot(address).stale.store(true, Ordering::Relaxed);
+}
+
+/// Sender and nonce of a signed raw tx.
+fn raw_sender_nonce(raw: &[u8]) -> Option<(Address, u64)> {
+    let tx = TxEnvelope::decode_2718(&mut &raw[..]).ok()?;
+    Some((tx.recover_signer().ok()?, tx.nonce()))
+}
+
+/// The transport broadcast a raw tx; its nonce is no longer in flight. A
+/// rejected first send leaves its nonce unused, so the counter re-syncs.
+/// Replacements reuse a nonce that already left flight and change nothing.
+pub async fn on_broadcast(raw: &[u8], accepted: bool) {
+    let Some((address, nonce)) = raw_sender_nonce(raw) else {
+        return;
+    };
+    let Some(slot) = NONCES.get(&address).map(|s| s.clone()) else {
+        return;
+    };
+    let first = slot.state.lock().await.in_flight.remove(&nonce).is_some();
+    if first && !accepted {
+        save_log_to_file(&format!("[nonce] broadcast of nonce {} rejected", nonce));
+        slot.stale.store(true, Ordering::Relaxed);
+    }
+}
+
+fn is_nonce_error(msg: &str) -> bool {
+    let msg = msg.to_lowercase();
+    msg.contains("nonce too low")
+        || msg.contains("nonce too high")
+        || msg.contains("invalid nonce")
+        || msg.contains("replacement transaction underpriced")
+        || msg.contains("already known")
+        // send_replaceable gave up on a tx the node no longer knows
+        || msg.ends_with(" dropped")
+}
+
+/// Re-sync after a failed send that points at a wrong counter. Other failures
+/// are covered by `on_broadcast` and by expiring unbroadcast allocations.
+pub fn on_send_error(address: Address, err: &str) {
+    if is_nonce_error(err) {
+        resync(address);
+        save_log_to_file(&format!("[nonce] re-sync {:#x} after: {}", address, err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::consensus::{SignableTransaction, TxLegacy};
+    use alloy::eips::eip2718::Encodable2718;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::signers::SignerSync;
+
+    #[test]
+    fn raw_sender_nonce_recovers_the_signer() {
+        let signer = PrivateKeySigner::random();
+        let tx = TxLegacy {
+            chain_id: Some(56),
+            nonce: 42,
+            gas_limit: 21_000,
+            ..Default::default()
+        };
+        let sig = signer.sign_hash_sync(&tx.signature_hash()).unwrap();
+        let raw = TxEnvelope::from(tx.into_signed(sig)).encoded_2718();
+        assert_eq!(raw_sender_nonce(&raw), Some((signer.address(), 42)));
+        assert_eq!(raw_sender_nonce(&[0x02, 0x01]), None);
+    }
+
+    #[test]
+    fn only_counter_errors_are_nonce_errors() {
+        assert!(is_nonce_error(
+            "server returned an error response: nonce too low"
+        ));
+        assert!(is_nonce_error("tx 0xabc dropped"));
+        assert!(!is_nonce_error(
+            "execution reverted: PancakeRouter: EXPIRED"
+        ));
+        assert!(!is_nonce_error("error sending request for url"));
+    }
+}
@@ -13,12 +13,12 @@ use pancakes::plug::price::IPancakeV3FactoryView;
 use pancakes::plug::price::{get_price_v2, get_price_v3, PriceQuote};
 use pancakes::plug::v2::IPancakeV2FactoryView;
 
-use crate::libs::bsc::client::BscWsClient;
+use crate::libs::ws::source::LogSource;
 
 /// Subscribe to v2 pair Sync events and emit PriceQuote updates for 1 unit of `token_in` to `token_out`.
 pub async fn subscribe_v2_price<'a, P: Provider + Clone + 'static>(
     provider: P,
-    ws: &'a BscWsClient,
+    logs: &'a LogSource,
     token_in: Address,
     token_out: Address,
 ) -> Result<(mpsc::Receiver<PriceQuote>, JoinHandle<()>)> {
@@ -31,7 +31,7 @@ pub async fn subscribe_v2_price<'a, P: Provider + Clone + 'static>(
 
     let topic_sync: B256 = keccak256("Sync(uint112,uint112)".as_bytes());
     let filter = Filter::new().address(pair).event_signature(topic_sync);
-    let (mut rx_logs, ws_handle) = ws.subscribe_logs(filter).await?;
+    let (mut rx_logs, ws_handle) = logs.subscribe_logs(filter).await?;
 
     let (tx, rx) = mpsc::channel::<PriceQuote>(1024);
     let prov_clone = provider.clone();
@@ -54,7 +54,7 @@ pub async fn subscribe_v2_price<'a, P: Provider + Clone + 'static>(
 /// If `fee` is None, the pool fee tier is auto-detected once at startup.
 pub async fn subscribe_v3_price<'a, P: Provider + Clone + 'static>(
     provider: P,
-    ws: &'a BscWsClient,
+    logs: &'a LogSource,
     token_in: Address,
     token_out: Address,
     fee: Option<u32>,
@@ -107,7 +107,7 @@ pub async fn subscribe_v3_price<'a, P: Provider + Clone + 'static>(
     let topic_swap: B256 =
         keccak256("Swap(address,address,int256,int256,uint160,uint128,int24)".as_bytes());
     let filter = Filter::new().address(pool_addr).event_signature(topic_swap);
-    let (mut rx_logs, ws_handle) = ws.subscribe_logs(filter).await?;
+    let (mut rx_logs, ws_handle) = logs.subscribe_logs(filter).await?;
 
     let (tx, rx) = mpsc::channel::<PriceQuote>(1024);
     let prov_clone = provider.clone();
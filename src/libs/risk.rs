@@ -0,0 +1,332 @@
+//! Bytecode risk scanner: pull the token's runtime code with `eth_getCode`,
+//! walk it for PUSH1..PUSH4 selectors of dangerous admin functions and known proxy
+//! patterns, and check whether ownership was renounced.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use alloy::primitives::{address, hex, keccak256, Address};
+use alloy::providers::Provider;
+use anyhow::Result;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+use crate::libs::lookup::save_log_to_file;
+
+alloy::sol! {
+    #[sol(rpc)]
+    interface IOwnable {
+        function owner() view returns (address);
+    }
+}
+
+const DEAD: Address = address!("0x000000000000000000000000000000000000dEaD");
+
+/// EIP-1967 implementation slot, keccak256("eip1967.proxy.implementation") - 1
+const EIP1967_IMPL_SLOT: [u8; 32] =
+    hex!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+/// EIP-1167 minimal proxy prefix (up to the PUSH20 of the implementation)
+const EIP1167_PREFIX: [u8; 10] = hex!("363d3d373d3d3d363d73");
+
+/// (reason, score, signatures). A category scores once however many match.
+const RISKY_SELECTORS: [(&str, u32, &[&str]); 5] = [
+    (
+        "blacklist",
+        30,
+        &[
+            "blacklist(address)",
+            "addToBlacklist(address)",
+            "setBlacklist(address,bool)",
+            "blacklistAddress(address,bool)",
+            "addBots(address[])",
+            "setBots(address[])",
+            "setBot(address,bool)",
+        ],
+    ),
+    (
+        "mutable fee/tax",
+        25,
+        &[
+            "setFee(uint256)",
+            "setFees(uint256,uint256)",
+            "setTax(uint256)",
+            "setTaxes(uint256,uint256)",
+            "setBuyFee(uint256)",
+            "setSellFee(uint256)",
+            "updateFees(uint256,uint256)",
+            "setTaxFeePercent(uint256)",
+        ],
+    ),
+    ("pausable", 25, &["pause()", "setPaused(bool)"]),
+    ("mintable", 30, &["mint(address,uint256)", "mint(uint256)"]),
+    (
+        "owner-gated transfers",
+        15,
+        &[
+            "enableTrading()",
+            "openTrading()",
+            "setTradingEnabled(bool)",
+            "setMaxTxAmount(uint256)",
+            "setMaxWalletSize(uint256)",
+        ],
+    ),
+];
+const PROXY_SCORE: u32 = 30;
+const OWNER_SCORE: u32 = 20;
+
+/// (reason, score, 4-byte selectors)
+type SelectorGroup = (&'static str, u32, Vec<[u8; 4]>);
+
+static SELECTOR_TABLE: Lazy<Vec<SelectorGroup>> = Lazy::new(|| {
+    RISKY_SELECTORS
+        .iter()
+        .map(|(reason, score, sigs)| {
+            let sels = sigs
+                .iter()
+                .map(|s| {
+                    let h = keccak256(s.as_bytes());
+                    [h[0], h[1], h[2], h[3]]
+                })
+                .collect();
+            (*reason, *score, sels)
+        })
+        .collect()
+});
+
+/// Risk score (0..=100, higher is worse) with the reasons that produced it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RiskReport {
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
+
+impl RiskReport {
+    fn flag(&mut self, score: u32, reason: impl Into<String>) {
+        self.score = (self.score + score).min(100);
+        self.reasons.push(reason.into());
+    }
+
+    pub fn summary(&self) -> String {
+        if self.reasons.is_empty() {
+            format!("score {}", self.score)
+        } else {
+            format!("score {} ({})", self.score, self.reasons.join(", "))
+        }
+    }
+}
+
+/// Every selector pushed with PUSH1..PUSH4, skipping over other PUSH
+/// immediates. The compiler pushes selectors with leading zero bytes with a
+/// shorter PUSH, so those immediates are left-padded to 4 bytes.
+fn push_selectors(code: &[u8]) -> HashSet<[u8; 4]> {
+    let mut out = HashSet::new();
+    let mut i = 0;
+    while i < code.len() {
+        let op = code[i];
+        if (0x60..=0x7f).contains(&op) {
+            let n = (op - 0x5f) as usize;
+            if n <= 4 && i + n < code.len() {
+                let mut sel = [0u8; 4];
+                sel[4 - n..].copy_from_slice(&code[i + 1..=i + n]);
+                out.insert(sel);
+            }
+            i += n;
+        }
+        i += 1;
+    }
+    out
+}
+
+fn contains_bytes(code: &[u8], needle: &[u8]) -> bool {
+    code.windows(needle.len()).any(|w| w == needle)
+}
+
+/// Score runtime bytecode on its own (no RPC).
+pub fn scan_bytecode(code: &[u8]) -> RiskReport {
+    let mut report = RiskReport::default();
+    if code.is_empty() {
+        report.flag(100, "no contract code");
+        return report;
+    }
+    if code.starts_with(&EIP1167_PREFIX) || contains_bytes(code, &EIP1967_IMPL_SLOT) {
+        report.flag(PROXY_SCORE, "upgradeable/proxy");
+    }
+    let selectors = push_selectors(code);
+    for (reason, score, sels) in SELECTOR_TABLE.iter() {
+        if sels.iter().any(|s| selectors.contains(s)) {
+            report.flag(*score, *reason);
+        }
+    }
+    report
+}
+
+/// Fetch the token code and owner and score them.
+pub async fn scan_token<P: Provider + Clone>(provider: P, token: Address) -> Result<RiskReport> {
+    let code = provider.get_code_at(token).await?;
+    let mut report = scan_bytecode(&code);
+    if !code.is_empty() {
+        // No owner() at all is fine; a live owner keeps the admin functions usable
+        if let Ok(owner) = IOwnable::new(token, provider).owner().call().await {
+            if owner != Address::ZERO && owner != DEAD {
+                report.flag(OWNER_SCORE, format!("owner not renounced ({:#x})", owner));
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Finished scans keyed by token address
+static RISK_REPORTS: Lazy<DashMap<Address, RiskReport>> = Lazy::new(DashMap::new);
+/// Last attempt per token; failed scans are retried after `RETRY_AFTER`
+static SCAN_ATTEMPTS: Lazy<DashMap<Address, Instant>> = Lazy::new(DashMap::new);
+const RETRY_AFTER: Duration = Duration::from_secs(60);
+/// Token scanned for each pair, so pruned pairs can release their scans
+static PAIR_TOKENS: Lazy<DashMap<Address, Address>> = Lazy::new(DashMap::new);
+
+pub fn cached_report(token: Address) -> Option<RiskReport> {
+    RISK_REPORTS.get(&token).map(|r| r.clone())
+}
+
+/// Whether no scan of `token` was attempted within the retry window.
+pub fn scan_due(token: Address) -> bool {
+    SCAN_ATTEMPTS
+        .get(&token)
+        .is_none_or(|at| at.elapsed() >= RETRY_AFTER)
+}
+
+/// Drop the scan of a pruned pair's token unless another pair still trades it.
+pub fn forget(pair: Address) {
+    let Some((_, token)) = PAIR_TOKENS.remove(&pair) else {
+        return;
+    };
+    if !PAIR_TOKENS.iter().any(|e| *e.value() == token) {
+        RISK_REPORTS.remove(&token);
+        SCAN_ATTEMPTS.remove(&token);
+    }
+}
+
+/// Scan `pair`'s `token`, log the result under `symbol` and cache it.
+pub async fn check_token<P: Provider + Clone>(
+    provider: P,
+    pair: Address,
+    token: Address,
+    symbol: &str,
+) -> Result<RiskReport> {
+    PAIR_TOKENS.insert(pair, token);
+    SCAN_ATTEMPTS.insert(token, Instant::now());
+    let report = scan_token(provider, token).await?;
+    save_log_to_file(&format!("[risk] {} {}", symbol, report.summary()));
+    RISK_REPORTS.insert(token, report.clone());
+    Ok(report)
+}
+
+/// Run `check_token` in the background unless the token was scanned already
+/// or attempted within the retry window.
+pub fn spawn_scan<P>(provider: P, pair: Address, token: Address, symbol: String)
+where
+    P: Provider + Clone + Send + Sync + 'static,
+{
+    PAIR_TOKENS.insert(pair, token);
+    if RISK_REPORTS.contains_key(&token) || !scan_due(token) {
+        return;
+    }
+    SCAN_ATTEMPTS.insert(token, Instant::now());
+    tokio::spawn(async move {
+        if let Err(e) = check_token(provider, pair, token, &symbol).await {
+            save_log_to_file(&format!("[risk] scan failed for {}: {}", symbol, e));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PUSH4 of the selector of `sig`
+    fn push4(sig: &str) -> Vec<u8> {
+        let h = keccak256(sig.as_bytes());
+        vec![0x63, h[0], h[1], h[2], h[3]]
+    }
+
+    #[test]
+    fn push_immediates_are_skipped() {
+        // A PUSH32 whose immediate looks like PUSH4 0x11223344, then a real PUSH4
+        let mut code = vec![0x7f, 0x63, 0x11, 0x22, 0x33, 0x44];
+        code.resize(33, 0xff);
+        code.extend([0x63, 0xaa, 0xbb, 0xcc, 0xdd]);
+        let sels = push_selectors(&code);
+        assert!(sels.contains(&[0xaa, 0xbb, 0xcc, 0xdd]));
+        assert!(!sels.contains(&[0x11, 0x22, 0x33, 0x44]));
+    }
+
+    #[test]
+    fn wide_and_truncated_pushes_are_not_selectors() {
+        // PUSH5 is too wide; the trailing PUSH4 runs past the end of the code
+        let code = [0x64, 1, 2, 3, 4, 5, 0x63, 0xaa, 0xbb];
+        assert!(push_selectors(&code).is_empty());
+    }
+
+    #[test]
+    fn short_push_selectors_are_left_padded() {
+        let code = [0x62, 0xaa, 0xbb, 0xcc, 0x60, 0x05];
+        let sels = push_selectors(&code);
+        assert!(sels.contains(&[0x00, 0xaa, 0xbb, 0xcc]));
+        assert!(sels.contains(&[0x00, 0x00, 0x00, 0x05]));
+        assert_eq!(sels.len(), 2);
+    }
+
+    #[test]
+    fn risky_selector_is_flagged_once_per_category() {
+        let mut code = push4("mint(address,uint256)");
+        code.extend(push4("mint(uint256)"));
+        let report = scan_bytecode(&code);
+        assert_eq!(report.score, 30);
+        assert_eq!(report.reasons, vec!["mintable".to_string()]);
+    }
+
+    #[test]
+    fn selector_inside_push_data_is_ignored() {
+        let mut code = vec![0x7f];
+        code.extend(push4("pause()"));
+        code.resize(33, 0);
+        assert_eq!(scan_bytecode(&code), RiskReport::default());
+    }
+
+    #[test]
+    fn eip1167_minimal_proxy_is_flagged() {
+        let mut code = EIP1167_PREFIX.to_vec();
+        code.extend([0x11; 20]);
+        code.extend(hex!("5af43d82803e903d91602b57fd5bf3"));
+        let report = scan_bytecode(&code);
+        assert_eq!(report.score, PROXY_SCORE);
+        assert_eq!(report.reasons, vec!["upgradeable/proxy".to_string()]);
+    }
+
+    #[test]
+    fn eip1967_slot_is_flagged() {
+        let mut code = vec![0x60, 0x80, 0x7f];
+        code.extend(EIP1967_IMPL_SLOT);
+        code.push(0x54);
+        let report = scan_bytecode(&code);
+        assert_eq!(report.score, PROXY_SCORE);
+        assert_eq!(report.reasons, vec!["upgradeable/proxy".to_string()]);
+    }
+
+    #[test]
+    fn empty_code_scores_max() {
+        assert_eq!(scan_bytecode(&[]).score, 100);
+    }
+
+    #[test]
+    fn forget_keeps_tokens_other_pairs_trade() {
+        let token = Address::repeat_byte(0x71);
+        let (a, b) = (Address::repeat_byte(0x0a), Address::repeat_byte(0x0b));
+        PAIR_TOKENS.insert(a, token);
+        PAIR_TOKENS.insert(b, token);
+        RISK_REPORTS.insert(token, RiskReport::default());
+        forget(a);
+        assert!(cached_report(token).is_some());
+        forget(b);
+        assert!(cached_report(token).is_none());
+    }
+}
@@ -1,3 +1,6 @@
+use crate::libs::strategy::{
+    DefaultStrategy, ExitParams, ExitReason, Intent, PairUpdate, PositionView, Strategy,
+};
 use alloy::primitives::Address;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -17,9 +20,72 @@ pub enum PositionStatus {
     Open,
     ClosedTP,
     ClosedSL,
+    ClosedTrailing,
     ClosedManual,
 }
 
+/// Trailing stop check shared by sim and real positions: armed once the peak PnL
+/// reaches `activate_pct`, fires when price drops `trail_pct` below the peak.
+pub fn trailing_stop_hit(
+    entry_price: f64,
+    peak_price: f64,
+    current_price: f64,
+    trail_pct: f64,
+    activate_pct: f64,
+) -> bool {
+    if entry_price <= 0.0 || peak_price <= 0.0 || trail_pct <= 0.0 {
+        return false;
+    }
+    let peak_pnl_pct = ((peak_price / entry_price) - 1.0) * 100.0;
+    if peak_pnl_pct < activate_pct {
+        return false;
+    }
+    let drop_pct = (1.0 - current_price / peak_price) * 100.0;
+    drop_pct >= trail_pct
+}
+
+/// Parse a take-profit ladder like "50:30,100:30" into (pnl %, sell % of the
+/// original size) tiers, sorted by trigger. Invalid entries are skipped.
+pub fn parse_tp_ladder(spec: &str) -> Vec<(f64, f64)> {
+    let mut tiers: Vec<(f64, f64)> = spec
+        .split(',')
+        .filter_map(|part| {
+            let (at, sell) = part.split_once(':')?;
+            let at = at.trim().parse::<f64>().ok()?;
+            let sell = sell.trim().parse::<f64>().ok()?;
+            (at > 0.0 && sell > 0.0).then_some((at, sell.min(100.0)))
+        })
+        .collect();
+    tiers.sort_by(|a, b| a.0.total_cmp(&b.0));
+    tiers
+}
+
+/// Fraction of the *remaining* size to sell for ladder tier `idx`, given that
+/// all earlier tiers already fired. Returns 1.0 when the tier empties the position.
+pub fn tp_tier_fraction(ladder: &[(f64, f64)], idx: usize) -> f64 {
+    let Some((_, sell_pct)) = ladder.get(idx) else {
+        return 0.0;
+    };
+    let sold_before: f64 = ladder[..idx].iter().map(|(_, s)| s).sum();
+    let remaining_pct = 100.0 - sold_before;
+    if remaining_pct <= 0.0 || *sell_pct >= remaining_pct {
+        return 1.0;
+    }
+    sell_pct / remaining_pct
+}
+
+/// Log header for an exit, as used by the close messages.
+fn exit_label(reason: &ExitReason) -> &'static str {
+    match reason {
+        ExitReason::TakeProfit(_) => "🎯 TAKE PROFIT",
+        ExitReason::TakeProfitTier(..) => "🪜 TP LADDER",
+        ExitReason::StopLoss(_) => "🛑 STOP LOSS",
+        ExitReason::Trailing(_) => "📉 TRAILING STOP",
+        ExitReason::MaxHold(_) => "⏰ MAX HOLD",
+        ExitReason::DevSold(_) => "🚨 DEV SOLD",
+    }
+}
+
 /// A simulated trading position
 #[derive(Debug, Clone)]
 pub struct SimPosition {
@@ -29,6 +95,8 @@ pub struct SimPosition {
     pub quote_token: String,
     pub entry_price: f64,
     pub current_price: f64,
+    /// Highest price seen since entry (drives the trailing stop)
+    pub peak_price: f64,
     pub buy_amount_wbnb: f64,
     pub remaining_amount_wbnb: f64,
     pub opened_at: Instant,
@@ -43,6 +111,8 @@ pub struct SimPosition {
     pub pnl_wbnb: f64,
     pub realized_pnl_wbnb: f64,
     pub frozen: bool,
+    /// Number of take-profit ladder tiers already sold
+    pub tp_tiers_hit: usize,
 }
 
 impl SimPosition {
@@ -63,6 +133,7 @@ impl SimPosition {
             quote_token,
             entry_price,
             current_price: entry_price,
+            peak_price: entry_price,
             buy_amount_wbnb,
             remaining_amount_wbnb: buy_amount_wbnb,
             opened_at: Instant::now(),
@@ -77,6 +148,7 @@ impl SimPosition {
             pnl_wbnb: 0.0,
             realized_pnl_wbnb: 0.0,
             frozen: false,
+            tp_tiers_hit: 0,
         }
     }
 
@@ -106,43 +178,29 @@ impl SimPosition {
         }
     }
 
-    /// Update position with new price and check TP/SL conditions
-    pub fn update_price(&mut self, new_price: f64) -> bool {
+    /// Update position with new price (exits are decided by the strategy)
+    pub fn update_price(&mut self, new_price: f64) {
         self.current_price = new_price;
+        if new_price > self.peak_price {
+            self.peak_price = new_price;
+        }
 
         if self.entry_price <= 0.0 {
-            return false;
+            return;
         }
 
         self.pnl_pct = ((new_price / self.entry_price) - 1.0) * 100.0;
         // PnL on the still-open portion only
         self.pnl_wbnb = self.remaining_amount_wbnb * (self.pnl_pct / 100.0);
+    }
 
-        // Frozen positions never auto-close on TP/SL
-        if self.frozen {
-            return false;
-        }
-
-        // Only auto-close on TP/SL if there is remaining amount
-        if self.remaining_amount_wbnb > 0.0 {
-            // Check TP condition
-            if let Some(tp) = self.tp_pct {
-                if self.pnl_pct >= tp {
-                    self.close(PositionStatus::ClosedTP);
-                    return true;
-                }
-            }
-
-            // Check SL condition
-            if let Some(sl) = self.sl_pct {
-                if self.pnl_pct <= -sl {
-                    self.close(PositionStatus::ClosedSL);
-                    return true;
-                }
-            }
+    pub fn view(&self) -> PositionView {
+        PositionView {
+            entry_price: self.entry_price,
+            peak_price: self.peak_price,
+            held_secs: self.duration_secs(),
+            tp_tiers_hit: self.tp_tiers_hit,
         }
-
-        false
     }
 
     /// Execute a partial sell by fraction of remaining amount (0.0 < fraction <= 1.0).
@@ -177,6 +235,14 @@ impl SimPosition {
         end.duration_since(self.opened_at).as_secs()
     }
 
+    /// PnL at the highest price seen since entry
+    pub fn peak_pnl_pct(&self) -> f64 {
+        if self.entry_price <= 0.0 {
+            return 0.0;
+        }
+        ((self.peak_price / self.entry_price) - 1.0) * 100.0
+    }
+
     /// Total PnL including realized from partial sells plus current open PnL
     pub fn total_pnl_wbnb(&self) -> f64 {
         self.realized_pnl_wbnb + self.pnl_wbnb
@@ -196,6 +262,16 @@ pub struct SimEngine {
     max_hold_secs: u64,
     // Whether to enforce PnL threshold (50%) on Max Hold auto-close
     max_hold_pnl_enabled: bool,
+    // Trailing stop distance from peak in % (None = disabled)
+    trail_pct: Option<f64>,
+    // Dev-sold share of holdings (%) that closes the position (None = disabled)
+    dev_sell_pct: Option<f64>,
+    // Peak PnL % required before the trailing stop arms
+    trail_activate_pct: f64,
+    // Take-profit ladder tiers as (pnl %, sell % of original size); empty = disabled
+    tp_ladder: Vec<(f64, f64)>,
+    // Entry/exit strategy selected in the Auto Trade config
+    strategy: &'static dyn Strategy,
 }
 
 impl SimEngine {
@@ -208,9 +284,22 @@ impl SimEngine {
             do_not_rebuy: HashSet::new(),
             max_hold_secs: 0,
             max_hold_pnl_enabled: true,
+            trail_pct: None,
+            dev_sell_pct: None,
+            trail_activate_pct: 0.0,
+            tp_ladder: Vec::new(),
+            strategy: &DefaultStrategy,
         }
     }
 
+    pub fn set_strategy(&mut self, strategy: &'static dyn Strategy) {
+        self.strategy = strategy;
+    }
+
+    pub fn strategy(&self) -> &'static dyn Strategy {
+        self.strategy
+    }
+
     /// Update max hold duration in seconds (0 disables)
     pub fn set_max_hold_secs(&mut self, secs: u64) {
         self.max_hold_secs = secs;
@@ -221,6 +310,56 @@ impl SimEngine {
         self.max_hold_pnl_enabled = enabled;
     }
 
+    /// Configure the trailing stop (None disables it)
+    pub fn set_trailing_stop(&mut self, trail_pct: Option<f64>, activate_pct: f64) {
+        self.trail_pct = trail_pct;
+        self.trail_activate_pct = activate_pct;
+    }
+
+    /// Current trailing stop settings as (trail %, activation %)
+    pub fn trailing_stop(&self) -> Option<(f64, f64)> {
+        self.trail_pct.map(|t| (t, self.trail_activate_pct))
+    }
+
+    /// Configure the dev-dump exit (None disables it)
+    pub fn set_dev_sell_exit(&mut self, sold_pct: Option<f64>) {
+        self.dev_sell_pct = sold_pct;
+    }
+
+    /// Configure the take-profit ladder (empty disables it)
+    pub fn set_tp_ladder(&mut self, ladder: Vec<(f64, f64)>) {
+        self.tp_ladder = ladder;
+    }
+
+    pub fn tp_ladder(&self) -> &[(f64, f64)] {
+        &self.tp_ladder
+    }
+
+    /// Apply a ladder tier sold on-chain to the mirrored position.
+    /// Returns Some((realized_pnl, closed_now)) like `partial_take`.
+    pub fn record_tp_tier(&mut self, pair_address: &str, fraction: f64) -> Option<(f64, bool)> {
+        let pos = self.positions.get_mut(pair_address)?;
+        let realized = pos.partial_sell_fraction(fraction);
+        pos.tp_tiers_hit += 1;
+        Some((realized, pos.remaining_amount_wbnb == 0.0))
+    }
+
+    /// Restore the sold share, realized PnL and ladder progress of a mirrored
+    /// real position from the persisted ledger.
+    pub fn restore_real_progress(
+        &mut self,
+        pair_address: &str,
+        remaining_fraction: f64,
+        realized_pnl_wbnb: f64,
+        tp_tiers_hit: usize,
+    ) {
+        if let Some(pos) = self.positions.get_mut(pair_address) {
+            pos.remaining_amount_wbnb = pos.buy_amount_wbnb * remaining_fraction.clamp(0.0, 1.0);
+            pos.realized_pnl_wbnb = realized_pnl_wbnb;
+            pos.tp_tiers_hit = tp_tiers_hit;
+        }
+    }
+
     /// Manually close a single open position by pair address. Returns the closed position.
     pub fn take_position(&mut self, pair_address: &str) -> Option<SimPosition> {
         if let Some(mut pos) = self.positions.remove(pair_address) {
@@ -236,6 +375,26 @@ impl SimEngine {
         }
     }
 
+    /// Replace the PnL booked for a mirrored real sell (`marked`, at the stream
+    /// price) with the PnL read from its receipt. Applies to the open position
+    /// after a partial sell, else to the pair's last closed entry.
+    pub fn book_real_pnl(&mut self, pair_address: &str, marked: f64, actual: f64) {
+        let delta = actual - marked;
+        if let Some(pos) = self.positions.get_mut(pair_address) {
+            pos.realized_pnl_wbnb += delta;
+        } else if let Some(pos) = self
+            .closed_positions
+            .iter_mut()
+            .rev()
+            .find(|p| p.pair_address == pair_address)
+        {
+            pos.pnl_wbnb += delta;
+            if pos.buy_amount_wbnb > 0.0 {
+                pos.pnl_pct = pos.pnl_wbnb / pos.buy_amount_wbnb * 100.0;
+            }
+        }
+    }
+
     /// Partially sell an open position by a fraction (e.g., 0.1, 0.25, 0.5).
     /// Returns Some((realized_pnl, closed_now)) if position exists and not frozen.
     pub fn partial_take(&mut self, pair_address: &str, fraction: f64) -> Option<(f64, bool)> {
@@ -332,14 +491,14 @@ impl SimEngine {
     }
 
     /// Update position or execute pending buy with new price
-    /// `allow_close` controls whether TP/SL/MaxHold auto-closes are applied (true for sim, false when mirroring real trades for display).
+    /// `allow_close` controls whether strategy exits are applied (true for sim, false when mirroring real trades for display).
     pub fn update_or_execute(
         &mut self,
         pair_address: &str,
-        new_price: f64,
-        liquidity: Option<f64>,
+        update: &PairUpdate,
         allow_close: bool,
     ) -> Option<String> {
+        let new_price = update.price;
         // Check if this is a pending buy - execute it at this price (simulating block delay)
         if let Some((dex_type, base_token, quote_token, buy_amount, tp_pct, sl_pct)) =
             self.pending_buys.remove(pair_address)
@@ -354,7 +513,7 @@ impl SimEngine {
                 tp_pct,
                 sl_pct,
             );
-            position.update_liquidity(liquidity);
+            position.update_liquidity(update.liquidity_usd);
             self.positions.insert(pair_address.to_string(), position);
             return Some(format!(
                 "EXECUTED buy for {} at {:.8} (simulated 1-block delay)",
@@ -363,51 +522,131 @@ impl SimEngine {
         }
 
         // Otherwise, update existing position
-        if let Some(pos) = self.positions.get_mut(pair_address) {
-            pos.update_liquidity(liquidity);
-            let closed = pos.update_price(new_price);
-            if closed && allow_close {
-                // Move to closed positions
-                if let Some(closed_pos) = self.positions.remove(pair_address) {
-                    self.do_not_rebuy.insert(closed_pos.pair_address.clone());
-                    self.closed_positions.push(closed_pos);
-                }
-            }
+        let pos = self.positions.get_mut(pair_address)?;
+        pos.update_liquidity(update.liquidity_usd);
+        pos.update_price(new_price);
+        if !allow_close {
+            return None;
         }
 
-        // Time-based auto-take: if max_hold reached and (PnL <= 50% when enabled), close (when not frozen)
-        if self.max_hold_secs > 0 && allow_close {
-            let should_close = if let Some(pos) = self.positions.get(pair_address) {
-                if !pos.frozen
-                    && pos.duration_secs() >= self.max_hold_secs
-                    && pos.remaining_amount_wbnb > 0.0
-                {
-                    if self.max_hold_pnl_enabled {
-                        pos.pnl_pct <= 50.0
-                    } else {
-                        true
-                    }
-                } else {
-                    false
-                }
-            } else {
-                false
+        // Ask the strategy; ladder tiers may fire several times in one update
+        let exit = ExitParams {
+            tp_pct: pos.tp_pct,
+            sl_pct: pos.sl_pct,
+            tp_ladder: &self.tp_ladder,
+            trail_pct: self.trail_pct,
+            trail_activate_pct: self.trail_activate_pct,
+            max_hold_secs: self.max_hold_secs,
+            max_hold_pnl: self.max_hold_pnl_enabled,
+            dev_sell_pct: self.dev_sell_pct,
+        };
+        let mut fired: Vec<String> = Vec::new();
+        let mut partial: Option<String> = None;
+        let mut close: Option<ExitReason> = None;
+        while !pos.frozen && pos.remaining_amount_wbnb > 0.0 {
+            let Intent::Sell { fraction, reason } =
+                self.strategy
+                    .on_update(pair_address, update, Some(&pos.view()), &exit)
+            else {
+                break;
             };
-            if should_close {
-                if let Some(mut pos) = self.positions.remove(pair_address) {
-                    pos.close(PositionStatus::ClosedManual);
-                    let msg = format!(
-                        "⏰ MAX HOLD TAKE closed {} ({}) PnL: {:+.6} WBNB",
-                        pos.base_token, pos.pair_address, pos.pnl_wbnb
-                    );
-                    self.do_not_rebuy.insert(pos.pair_address.clone());
-                    self.closed_positions.push(pos);
-                    return Some(msg);
+            if let ExitReason::TakeProfitTier(idx, at) = reason {
+                let realized = pos.partial_sell_fraction(fraction.min(1.0));
+                pos.tp_tiers_hit += 1;
+                fired.push(format!(
+                    "tier {}/{} +{:.0}% realized {:+.6} WBNB",
+                    idx + 1,
+                    exit.tp_ladder.len(),
+                    at,
+                    realized
+                ));
+                if pos.remaining_amount_wbnb == 0.0 {
+                    close = Some(reason);
                 }
+                continue;
+            }
+            if fraction < 1.0 {
+                let realized = pos.partial_sell_fraction(fraction);
+                partial = Some(format!(
+                    "{} {} ({}) sold {:.0}% realized {:+.6} WBNB",
+                    exit_label(&reason),
+                    pos.base_token,
+                    pos.pair_address,
+                    fraction * 100.0,
+                    realized
+                ));
+                break;
             }
+            close = Some(reason);
+            break;
         }
 
-        None
+        let ladder = (!fired.is_empty()).then(|| {
+            format!(
+                "🪜 TP LADDER {} ({}) {}",
+                pos.base_token,
+                pos.pair_address,
+                fired.join(", ")
+            )
+        });
+        let mut msg = match (ladder, partial) {
+            (Some(l), Some(p)) => Some(format!("{}; {}", l, p)),
+            (l, p) => l.or(p),
+        };
+        let Some(reason) = close else {
+            return msg;
+        };
+        let mut pos = self.positions.remove(pair_address)?;
+        // The closed position's PnL includes what earlier partial exits realized
+        pos.pnl_wbnb += pos.realized_pnl_wbnb;
+        let text = match reason {
+            ExitReason::TakeProfitTier(..) => {
+                pos.close(PositionStatus::ClosedTP);
+                msg.take().map(|m| format!("{} (closed)", m))
+            }
+            ExitReason::TakeProfit(_) => {
+                pos.close(PositionStatus::ClosedTP);
+                Some(format!(
+                    "🎯 TAKE PROFIT closed {} ({}) {:+.2}% PnL: {:+.6} WBNB",
+                    pos.base_token, pos.pair_address, pos.pnl_pct, pos.pnl_wbnb
+                ))
+            }
+            ExitReason::StopLoss(_) => {
+                pos.close(PositionStatus::ClosedSL);
+                Some(format!(
+                    "🛑 STOP LOSS closed {} ({}) {:+.2}% PnL: {:+.6} WBNB",
+                    pos.base_token, pos.pair_address, pos.pnl_pct, pos.pnl_wbnb
+                ))
+            }
+            ExitReason::Trailing(_) => {
+                pos.close(PositionStatus::ClosedTrailing);
+                Some(format!(
+                    "📉 TRAILING STOP closed {} ({}) peak {:+.2}% now {:+.2}% PnL: {:+.6} WBNB",
+                    pos.base_token,
+                    pos.pair_address,
+                    pos.peak_pnl_pct(),
+                    pos.pnl_pct,
+                    pos.pnl_wbnb
+                ))
+            }
+            ExitReason::MaxHold(_) => {
+                pos.close(PositionStatus::ClosedManual);
+                Some(format!(
+                    "⏰ MAX HOLD TAKE closed {} ({}) PnL: {:+.6} WBNB",
+                    pos.base_token, pos.pair_address, pos.pnl_wbnb
+                ))
+            }
+            ExitReason::DevSold(sold) => {
+                pos.close(PositionStatus::ClosedManual);
+                Some(format!(
+                    "🚨 DEV SOLD {:.0}% closed {} ({}) {:+.2}% PnL: {:+.6} WBNB",
+                    sold, pos.base_token, pos.pair_address, pos.pnl_pct, pos.pnl_wbnb
+                ))
+            }
+        };
+        self.do_not_rebuy.insert(pos.pair_address.clone());
+        self.closed_positions.push(pos);
+        text.or(msg)
     }
 
     /// Get all open positions sorted by entry time (oldest first)
@@ -542,6 +781,8 @@ impl SimEngine {
     }
 
     /// Mirror a real buy directly into the sim engine (without pending delay).
+    /// A queued sim buy for the pair is dropped so the next update does not
+    /// replace the fill price with the stream price.
     pub fn add_real_position(
         &mut self,
         pair_address: String,
@@ -554,6 +795,7 @@ impl SimEngine {
         sl_pct: Option<f64>,
         liquidity_usd: Option<f64>,
     ) {
+        self.pending_buys.remove(&pair_address);
         if self.positions.contains_key(&pair_address) {
             return;
         }
@@ -587,3 +829,68 @@ pub struct SimStats {
     /// Closed PnL + realized partials (excludes current unrealized open PnL)
     pub total_pnl_realized: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tp_ladder_sorts_tiers() {
+        assert_eq!(
+            parse_tp_ladder("100:30, 50:30,200:40"),
+            vec![(50.0, 30.0), (100.0, 30.0), (200.0, 40.0)]
+        );
+    }
+
+    #[test]
+    fn parse_tp_ladder_skips_invalid_entries() {
+        assert_eq!(
+            parse_tp_ladder("50:30,abc,75,0:10,80:-5,90:x,120:20"),
+            vec![(50.0, 30.0), (120.0, 20.0)]
+        );
+        assert!(parse_tp_ladder("").is_empty());
+    }
+
+    #[test]
+    fn parse_tp_ladder_caps_sell_share() {
+        assert_eq!(parse_tp_ladder("50:150"), vec![(50.0, 100.0)]);
+    }
+
+    #[test]
+    fn real_position_keeps_fill_price_after_update() {
+        let mut se = SimEngine::new(3);
+        let pair = "0xpair".to_string();
+        assert!(se.submit_buy(
+            pair.clone(),
+            DexType::V2,
+            "TKN".into(),
+            "WBNB".into(),
+            0.1,
+            None,
+            None
+        ));
+        se.add_real_position(
+            pair.clone(),
+            DexType::V2,
+            "TKN".into(),
+            "WBNB".into(),
+            2.0,
+            0.1,
+            None,
+            None,
+            None,
+        );
+        let update = PairUpdate {
+            price: 3.0,
+            liquidity_usd: None,
+            buy_count: 0,
+            sell_count: 0,
+            unique_buyers: 0,
+            dev_sold_pct: None,
+        };
+        assert!(se.update_or_execute(&pair, &update, false).is_none());
+        let pos = se.open_position(&pair).unwrap();
+        assert_eq!(pos.entry_price, 2.0);
+        assert!((pos.pnl_pct - 50.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,257 @@
+//! Position sizing. `sizing_mode` picks how much WBNB an entry spends:
+//! - `fixed`: `buy_amount_wbnb`
+//! - `balance`: `size_balance_pct` of the spendable WBNB+BNB balance
+//! - `liquidity`: `size_liq_pct` of the pool's USD liquidity
+//! - `risk`: lose `risk_per_trade_pct` of the balance if the stop loss hits
+//! - `compound`: `buy_amount_wbnb` plus `compound_pct` of realized PnL
+//!
+//! Modes that lack their input (no balance yet, no SL, ...) fall back to fixed.
+
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::libs::tui::ConfigStore;
+
+/// Selectable modes, in Auto Trade cycling order.
+pub const SIZING_MODES: [&str; 5] = ["fixed", "balance", "liquidity", "risk", "compound"];
+
+/// Mode that follows `mode` in `SIZING_MODES`.
+pub fn next_sizing_mode(mode: &str) -> &'static str {
+    let idx = SIZING_MODES.iter().position(|m| *m == mode).unwrap_or(0);
+    SIZING_MODES[(idx + 1) % SIZING_MODES.len()]
+}
+
+/// Wallet funds in BNB units.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Funds {
+    pub bnb: f64,
+    pub wbnb: f64,
+}
+
+impl Funds {
+    /// WBNB plus the share of native BNB auto trade may wrap.
+    pub fn spendable(&self, wrap_ratio_pct: u64) -> f64 {
+        self.wbnb + self.bnb * wrap_ratio_pct.min(100) as f64 / 100.0
+    }
+}
+
+/// Last wallet funds and BNB/USD price seen by the balance ticker
+static MARKET: Lazy<Mutex<(Option<Funds>, Option<f64>)>> = Lazy::new(|| Mutex::new((None, None)));
+
+pub fn set_funds(funds: Funds) {
+    MARKET.lock().unwrap().0 = Some(funds);
+}
+
+pub fn cached_funds() -> Option<Funds> {
+    MARKET.lock().unwrap().0
+}
+
+pub fn set_bnb_usd(price: f64) {
+    if price > 0.0 {
+        MARKET.lock().unwrap().1 = Some(price);
+    }
+}
+
+pub fn bnb_usd() -> Option<f64> {
+    MARKET.lock().unwrap().1
+}
+
+/// Inputs for one entry.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SizingInputs {
+    /// Spendable WBNB+BNB (already scaled by the wrap ratio)
+    pub spendable_bnb: Option<f64>,
+    pub liquidity_usd: Option<f64>,
+    pub sl_pct: Option<f64>,
+    /// Realized PnL so far in this mode
+    pub realized_pnl_bnb: f64,
+}
+
+/// Computed size with a short note on how it was derived.
+#[derive(Clone, Debug)]
+pub struct Sizing {
+    pub bnb: f64,
+    pub note: String,
+}
+
+fn cfg_f64(store: &ConfigStore, key: &str, default: f64) -> f64 {
+    store
+        .get(key)
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(default)
+}
+
+pub fn position_size(config_store: &ConfigStore, inputs: &SizingInputs) -> Sizing {
+    let fixed = cfg_f64(config_store, "buy_amount_wbnb", 0.00001);
+    let mode = config_store
+        .get("sizing_mode")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "fixed".to_string());
+    let fallback = |why: &str| Sizing {
+        bnb: fixed,
+        note: format!("{} -> fixed ({})", mode, why),
+    };
+
+    let sized = match mode.as_str() {
+        "balance" => {
+            let Some(spendable) = inputs.spendable_bnb else {
+                return fallback("balance unknown");
+            };
+            let pct = cfg_f64(config_store, "size_balance_pct", 5.0);
+            Sizing {
+                bnb: spendable * pct / 100.0,
+                note: format!("{}% of {:.6} BNB balance", pct, spendable),
+            }
+        }
+        "liquidity" => {
+            let (Some(liq), Some(usd)) = (inputs.liquidity_usd, bnb_usd()) else {
+                return fallback("liquidity or BNB price unknown");
+            };
+            let pct = cfg_f64(config_store, "size_liq_pct", 1.0);
+            Sizing {
+                bnb: liq * pct / 100.0 / usd,
+                note: format!("{}% of ${:.0} liquidity @ ${:.2}/BNB", pct, liq, usd),
+            }
+        }
+        "risk" => {
+            let Some(spendable) = inputs.spendable_bnb else {
+                return fallback("balance unknown");
+            };
+            let Some(sl) = inputs.sl_pct.filter(|sl| *sl > 0.0) else {
+                return fallback("stop loss disabled");
+            };
+            let risk = cfg_f64(config_store, "risk_per_trade_pct", 1.0);
+            Sizing {
+                bnb: spendable * risk / sl,
+                note: format!("risk {}% of {:.6} BNB at SL -{}%", risk, spendable, sl),
+            }
+        }
+        "compound" => {
+            let share = cfg_f64(config_store, "compound_pct", 50.0);
+            // Losses shrink the size, but never below 10% of the base
+            let bnb = (fixed + inputs.realized_pnl_bnb * share / 100.0).max(fixed * 0.1);
+            Sizing {
+                bnb,
+                note: format!(
+                    "{} + {}% of {:+.6} realized",
+                    fixed, share, inputs.realized_pnl_bnb
+                ),
+            }
+        }
+        _ => {
+            return Sizing {
+                bnb: fixed,
+                note: "fixed".to_string(),
+            }
+        }
+    };
+
+    match inputs.spendable_bnb {
+        Some(spendable) if sized.bnb > spendable => Sizing {
+            bnb: spendable,
+            note: format!("{}, capped at balance", sized.note),
+        },
+        _ => sized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dashmap::DashMap;
+    use std::sync::Arc;
+
+    fn store(pairs: &[(&str, &str)]) -> ConfigStore {
+        let store: ConfigStore = Arc::new(DashMap::new());
+        for (k, v) in pairs {
+            store.insert(k.to_string(), v.to_string());
+        }
+        store
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn fixed_uses_buy_amount() {
+        let cfg = store(&[("sizing_mode", "fixed"), ("buy_amount_wbnb", "0.05")]);
+        let inputs = SizingInputs {
+            spendable_bnb: Some(10.0),
+            ..Default::default()
+        };
+        let s = position_size(&cfg, &inputs);
+        assert!(close(s.bnb, 0.05));
+        assert_eq!(s.note, "fixed");
+    }
+
+    #[test]
+    fn balance_takes_a_share_of_spendable() {
+        let cfg = store(&[("sizing_mode", "balance"), ("size_balance_pct", "10")]);
+        let inputs = SizingInputs {
+            spendable_bnb: Some(2.0),
+            ..Default::default()
+        };
+        assert!(close(position_size(&cfg, &inputs).bnb, 0.2));
+    }
+
+    #[test]
+    fn balance_without_funds_falls_back_to_fixed() {
+        let cfg = store(&[("sizing_mode", "balance"), ("buy_amount_wbnb", "0.01")]);
+        let s = position_size(&cfg, &SizingInputs::default());
+        assert!(close(s.bnb, 0.01));
+        assert!(s.note.contains("-> fixed"));
+    }
+
+    #[test]
+    fn risk_sizes_the_loss_at_the_stop() {
+        // Losing 1% of 5 BNB at a 10% stop: 0.5 BNB
+        let cfg = store(&[("sizing_mode", "risk"), ("risk_per_trade_pct", "1")]);
+        let inputs = SizingInputs {
+            spendable_bnb: Some(5.0),
+            sl_pct: Some(10.0),
+            ..Default::default()
+        };
+        assert!(close(position_size(&cfg, &inputs).bnb, 0.5));
+    }
+
+    #[test]
+    fn risk_without_stop_loss_falls_back_to_fixed() {
+        let cfg = store(&[("sizing_mode", "risk"), ("buy_amount_wbnb", "0.02")]);
+        for sl_pct in [None, Some(0.0)] {
+            let inputs = SizingInputs {
+                spendable_bnb: Some(5.0),
+                sl_pct,
+                ..Default::default()
+            };
+            let s = position_size(&cfg, &inputs);
+            assert!(close(s.bnb, 0.02));
+            assert!(s.note.contains("stop loss disabled"));
+        }
+    }
+
+    #[test]
+    fn size_is_capped_at_the_balance() {
+        // 5% risk at a 2% stop would be 2.5x the balance
+        let cfg = store(&[("sizing_mode", "risk"), ("risk_per_trade_pct", "5")]);
+        let inputs = SizingInputs {
+            spendable_bnb: Some(1.0),
+            sl_pct: Some(2.0),
+            ..Default::default()
+        };
+        let s = position_size(&cfg, &inputs);
+        assert!(close(s.bnb, 1.0));
+        assert!(s.note.ends_with("capped at balance"));
+    }
+
+    #[test]
+    fn funds_spendable_applies_wrap_ratio() {
+        let funds = Funds {
+            bnb: 2.0,
+            wbnb: 0.5,
+        };
+        assert!(close(funds.spendable(50), 1.5));
+        assert!(close(funds.spendable(150), 2.5));
+    }
+}
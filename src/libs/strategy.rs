@@ -0,0 +1,282 @@
+//! Pluggable entry/exit strategies. `SimEngine` and the real trader feed every
+//! pair update through the selected strategy and act on the intent it returns,
+//! so a strategy behaves the same in simulation and on-chain.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+use crate::libs::sim::{tp_tier_fraction, trailing_stop_hit};
+use crate::libs::tui::ConfigStore;
+
+/// Market data for one pair update. `price` is in WBNB terms.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PairUpdate {
+    pub price: f64,
+    pub liquidity_usd: Option<f64>,
+    pub buy_count: u32,
+    pub sell_count: u32,
+    pub unique_buyers: u32,
+    /// Share of the creator wallets' holdings they sold (%), once seen
+    pub dev_sold_pct: Option<f64>,
+}
+
+/// Engine-agnostic view of an open position.
+#[derive(Clone, Copy, Debug)]
+pub struct PositionView {
+    pub entry_price: f64,
+    /// Highest price seen since entry
+    pub peak_price: f64,
+    pub held_secs: u64,
+    /// Number of take-profit ladder tiers already sold
+    pub tp_tiers_hit: usize,
+}
+
+/// Exit settings as configured in the Auto Trade tab.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExitParams<'a> {
+    pub tp_pct: Option<f64>,
+    pub sl_pct: Option<f64>,
+    /// (pnl %, sell % of the original size); empty = disabled
+    pub tp_ladder: &'a [(f64, f64)],
+    pub trail_pct: Option<f64>,
+    pub trail_activate_pct: f64,
+    /// 0 = disabled
+    pub max_hold_secs: u64,
+    /// Only apply max hold while PnL <= 50%
+    pub max_hold_pnl: bool,
+    /// Exit once the dev sold this share of their holdings (%)
+    pub dev_sell_pct: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExitReason {
+    TakeProfit(f64),
+    /// Ladder tier (index, trigger pnl %)
+    TakeProfitTier(usize, f64),
+    StopLoss(f64),
+    Trailing(f64),
+    MaxHold(u64),
+    /// Share of holdings the dev sold (%)
+    DevSold(f64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Intent {
+    Hold,
+    Buy,
+    /// Sell `fraction` (0.0..=1.0) of the remaining size
+    Sell {
+        fraction: f64,
+        reason: ExitReason,
+    },
+}
+
+pub trait Strategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Called with `position: None` once the entry filters accepted the pair
+    /// (Buy or Hold), and with the open position on every price update (Sell or Hold).
+    fn on_update(
+        &self,
+        pair_key: &str,
+        update: &PairUpdate,
+        position: Option<&PositionView>,
+        exit: &ExitParams,
+    ) -> Intent;
+}
+
+/// TP, SL, dev dump, TP ladder, trailing stop, then max hold; the first that
+/// fires wins.
+pub fn default_exit(update: &PairUpdate, pos: &PositionView, exit: &ExitParams) -> Intent {
+    if pos.entry_price <= 0.0 {
+        return Intent::Hold;
+    }
+    let pnl_pct = ((update.price / pos.entry_price) - 1.0) * 100.0;
+    let sell_all = |reason| Intent::Sell {
+        fraction: 1.0,
+        reason,
+    };
+
+    if let Some(tp) = exit.tp_pct {
+        if pnl_pct >= tp {
+            return sell_all(ExitReason::TakeProfit(tp));
+        }
+    }
+    if let Some(sl) = exit.sl_pct {
+        if pnl_pct <= -sl {
+            return sell_all(ExitReason::StopLoss(sl));
+        }
+    }
+    if let (Some(limit), Some(sold)) = (exit.dev_sell_pct, update.dev_sold_pct) {
+        if sold >= limit {
+            return sell_all(ExitReason::DevSold(sold));
+        }
+    }
+    let idx = pos.tp_tiers_hit;
+    if let Some((at, _)) = exit.tp_ladder.get(idx) {
+        if pnl_pct >= *at {
+            return Intent::Sell {
+                fraction: tp_tier_fraction(exit.tp_ladder, idx),
+                reason: ExitReason::TakeProfitTier(idx, *at),
+            };
+        }
+    }
+    if let Some(trail) = exit.trail_pct {
+        if trailing_stop_hit(
+            pos.entry_price,
+            pos.peak_price,
+            update.price,
+            trail,
+            exit.trail_activate_pct,
+        ) {
+            return sell_all(ExitReason::Trailing(trail));
+        }
+    }
+    if exit.max_hold_secs > 0
+        && pos.held_secs >= exit.max_hold_secs
+        && (!exit.max_hold_pnl || pnl_pct <= 50.0)
+    {
+        return sell_all(ExitReason::MaxHold(exit.max_hold_secs));
+    }
+    Intent::Hold
+}
+
+/// Buy whatever passes the entry filters; exit via `default_exit`.
+pub struct DefaultStrategy;
+
+impl Strategy for DefaultStrategy {
+    fn name(&self) -> &'static str {
+        "default"
+    }
+
+    fn on_update(
+        &self,
+        _pair_key: &str,
+        update: &PairUpdate,
+        position: Option<&PositionView>,
+        exit: &ExitParams,
+    ) -> Intent {
+        match position {
+            None => Intent::Buy,
+            Some(pos) => default_exit(update, pos, exit),
+        }
+    }
+}
+
+/// `momentum_min_buyers`, pushed by [`apply_strategy_cfg`]
+static MOMENTUM_MIN_UNIQUE_BUYERS: AtomicU32 = AtomicU32::new(10);
+
+/// Enter only once `momentum_min_buyers` distinct wallets bought and buys
+/// outnumber sells.
+pub struct MomentumStrategy;
+
+impl Strategy for MomentumStrategy {
+    fn name(&self) -> &'static str {
+        "momentum"
+    }
+
+    fn on_update(
+        &self,
+        _pair_key: &str,
+        update: &PairUpdate,
+        position: Option<&PositionView>,
+        exit: &ExitParams,
+    ) -> Intent {
+        match position {
+            None if update.unique_buyers >= MOMENTUM_MIN_UNIQUE_BUYERS.load(Ordering::Relaxed)
+                && update.buy_count > update.sell_count =>
+            {
+                Intent::Buy
+            }
+            None => Intent::Hold,
+            Some(pos) => default_exit(update, pos, exit),
+        }
+    }
+}
+
+const DIP_PUMP_PCT: f64 = 50.0;
+const DIP_PULLBACK_PCT: f64 = 20.0;
+
+/// Wait for a first pump of +50% over the first seen price, then buy a 20%
+/// pullback from that peak.
+#[derive(Default)]
+pub struct DipBuyStrategy {
+    /// pair -> (first seen price, highest price since); dropped on entry and
+    /// when the pair leaves the feed (see [`forget`])
+    seen: DashMap<String, (f64, f64)>,
+}
+
+impl Strategy for DipBuyStrategy {
+    fn name(&self) -> &'static str {
+        "dip"
+    }
+
+    fn on_update(
+        &self,
+        pair_key: &str,
+        update: &PairUpdate,
+        position: Option<&PositionView>,
+        exit: &ExitParams,
+    ) -> Intent {
+        if let Some(pos) = position {
+            return default_exit(update, pos, exit);
+        }
+        if update.price <= 0.0 {
+            return Intent::Hold;
+        }
+        let mut entry = self
+            .seen
+            .entry(pair_key.to_string())
+            .or_insert((update.price, update.price));
+        let (first, peak) = *entry;
+        let peak = peak.max(update.price);
+        entry.1 = peak;
+        let pumped = (peak / first - 1.0) * 100.0 >= DIP_PUMP_PCT;
+        let pullback = (1.0 - update.price / peak) * 100.0;
+        if pumped && pullback >= DIP_PULLBACK_PCT {
+            drop(entry);
+            self.seen.remove(pair_key);
+            Intent::Buy
+        } else {
+            Intent::Hold
+        }
+    }
+}
+
+static DEFAULT: DefaultStrategy = DefaultStrategy;
+static MOMENTUM: MomentumStrategy = MomentumStrategy;
+static DIP: Lazy<DipBuyStrategy> = Lazy::new(DipBuyStrategy::default);
+
+/// Selectable strategies, in Auto Trade cycling order.
+pub const STRATEGY_NAMES: [&str; 3] = ["default", "momentum", "dip"];
+
+/// Look up a strategy by config name; unknown names fall back to `default`.
+pub fn strategy_by_name(name: &str) -> &'static dyn Strategy {
+    match name {
+        "momentum" => &MOMENTUM,
+        "dip" => &*DIP,
+        _ => &DEFAULT,
+    }
+}
+
+/// Drop per-pair strategy state of a pair that left the feed.
+pub fn forget(pair_key: &str) {
+    DIP.seen.remove(pair_key);
+}
+
+/// Push the strategy settings from `config_store` to the strategies.
+pub fn apply_strategy_cfg(config_store: &ConfigStore) {
+    let min_buyers = config_store
+        .get("momentum_min_buyers")
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(10);
+    MOMENTUM_MIN_UNIQUE_BUYERS.store(min_buyers, Ordering::Relaxed);
+}
+
+/// Strategy name that follows `name` in `STRATEGY_NAMES`.
+pub fn next_strategy_name(name: &str) -> &'static str {
+    let idx = STRATEGY_NAMES.iter().position(|n| *n == name).unwrap_or(0);
+    STRATEGY_NAMES[(idx + 1) % STRATEGY_NAMES.len()]
+}
@@ -1,4 +1,5 @@
 use super::types::{ConfigAreas, ConfigStore};
+use crate::libs::copytrade::{self, short_wallet};
 use crate::libs::tui::draw_inline_input;
 use crate::shared::ALL_QUOTES;
 use ratatui::{prelude::*, widgets::Paragraph};
@@ -34,7 +35,7 @@ pub fn draw_advanced(
     focused_field: Option<&str>,
     scroll_offset: usize,
 ) -> u16 {
-    let total_rows = 18usize;
+    let total_rows = 55usize;
     let viewport = area.height.max(1) as usize;
     let max_start = total_rows.saturating_sub(1);
     let start = scroll_offset.min(max_start);
@@ -55,14 +56,36 @@ pub fn draw_advanced(
                 draw_line(
                     f,
                     row_rect,
-                    "Slippage: ",
-                    &kv(store, "slippage_pct", "0.5"),
+                    "Buy slippage: ",
+                    &kv(store, "buy_slippage_pct", "0.5"),
                     "%",
-                    focused_field == Some("slippage_pct"),
+                    focused_field == Some("buy_slippage_pct"),
                 );
-                areas.slippage_input = Some(row_rect);
+                areas.buy_slippage_input = Some(row_rect);
             }
             1 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "Sell slippage: ",
+                    &kv(store, "sell_slippage_pct", "0.5"),
+                    "%",
+                    focused_field == Some("sell_slippage_pct"),
+                );
+                areas.sell_slippage_input = Some(row_rect);
+            }
+            2 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Escalate up to: ",
+                    &kv(store, "sell_slippage_max_pct", "10"),
+                    "%",
+                    focused_field == Some("sell_slippage_max_pct"),
+                );
+                areas.sell_slippage_max_input = Some(row_rect);
+            }
+            3 => {
                 draw_line(
                     f,
                     row_rect,
@@ -73,7 +96,7 @@ pub fn draw_advanced(
                 );
                 areas.max_gwei_input = Some(row_rect);
             }
-            2 => {
+            4 => {
                 draw_line(
                     f,
                     row_rect,
@@ -84,7 +107,7 @@ pub fn draw_advanced(
                 );
                 areas.max_positions_input = Some(row_rect);
             }
-            3 => {
+            5 => {
                 draw_line(
                     f,
                     row_rect,
@@ -95,7 +118,7 @@ pub fn draw_advanced(
                 );
                 areas.min_liq_input = Some(row_rect);
             }
-            4 => {
+            6 => {
                 draw_line(
                     f,
                     row_rect,
@@ -106,7 +129,53 @@ pub fn draw_advanced(
                 );
                 areas.min_buys_input = Some(row_rect);
             }
-            5 => {
+            7 => {
+                let hp_en = store
+                    .get("honeypot_check")
+                    .map(|v| v.as_str() == "true")
+                    .unwrap_or(false);
+                draw_checkbox_line(f, row_rect, "Honeypot check", hp_en);
+                areas.honeypot_toggle = Some(row_rect);
+            }
+            8 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Max tax: ",
+                    &kv(store, "max_tax_pct", "10"),
+                    "% round-trip",
+                    focused_field == Some("max_tax_pct"),
+                );
+                areas.max_tax_input = Some(row_rect);
+            }
+            9 => {
+                let quote_ok = store
+                    .get("honeypot_quote_only")
+                    .map(|v| v.as_str() == "true")
+                    .unwrap_or(false);
+                draw_checkbox_line(f, row_rect, "  Accept quote-only checks", quote_ok);
+                areas.honeypot_quote_only_toggle = Some(row_rect);
+            }
+            10 => {
+                let risk_en = store
+                    .get("risk_check")
+                    .map(|v| v.as_str() == "true")
+                    .unwrap_or(false);
+                draw_checkbox_line(f, row_rect, "Risk scan", risk_en);
+                areas.risk_toggle = Some(row_rect);
+            }
+            11 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Max score: ",
+                    &kv(store, "max_risk_score", "50"),
+                    " /100",
+                    focused_field == Some("max_risk_score"),
+                );
+                areas.max_risk_input = Some(row_rect);
+            }
+            12 => {
                 draw_line(
                     f,
                     row_rect,
@@ -117,7 +186,7 @@ pub fn draw_advanced(
                 );
                 areas.max_hold_input = Some(row_rect);
             }
-            6 => {
+            13 => {
                 let mh_pnl_en = store
                     .get("max_hold_pnl")
                     .map(|v| v.as_str() == "true")
@@ -125,7 +194,7 @@ pub fn draw_advanced(
                 draw_checkbox_line(f, row_rect, "Max Hold PnL", mh_pnl_en);
                 areas.max_hold_pnl_toggle = Some(row_rect);
             }
-            7 => {
+            14 => {
                 let tp_en = store
                     .get("tp_enabled")
                     .map(|v| v.as_str() == "true")
@@ -133,7 +202,7 @@ pub fn draw_advanced(
                 draw_checkbox_line(f, row_rect, "Take profit", tp_en);
                 areas.tp_toggle = Some(row_rect);
             }
-            8 => {
+            15 => {
                 draw_line(
                     f,
                     row_rect,
@@ -144,7 +213,26 @@ pub fn draw_advanced(
                 );
                 areas.tp_pct_input = Some(row_rect);
             }
-            9 => {
+            16 => {
+                let ladder_en = store
+                    .get("tp_ladder_enabled")
+                    .map(|v| v.as_str() == "true")
+                    .unwrap_or(false);
+                draw_checkbox_line(f, row_rect, "TP ladder", ladder_en);
+                areas.tp_ladder_toggle = Some(row_rect);
+            }
+            17 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Tiers: ",
+                    &kv(store, "tp_ladder", "50:30,100:30"),
+                    " (pnl%:sell%)",
+                    focused_field == Some("tp_ladder"),
+                );
+                areas.tp_ladder_input = Some(row_rect);
+            }
+            18 => {
                 let sl_en = store
                     .get("sl_enabled")
                     .map(|v| v.as_str() == "true")
@@ -152,7 +240,7 @@ pub fn draw_advanced(
                 draw_checkbox_line(f, row_rect, "Stop loss", sl_en);
                 areas.sl_toggle = Some(row_rect);
             }
-            10 => {
+            19 => {
                 draw_line(
                     f,
                     row_rect,
@@ -163,19 +251,49 @@ pub fn draw_advanced(
                 );
                 areas.sl_pct_input = Some(row_rect);
             }
-            11 => {
+            20 => {
+                let trail_en = store
+                    .get("trail_enabled")
+                    .map(|v| v.as_str() == "true")
+                    .unwrap_or(false);
+                draw_checkbox_line(f, row_rect, "Trailing stop", trail_en);
+                areas.trail_toggle = Some(row_rect);
+            }
+            21 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Distance: ",
+                    &kv(store, "trail_pct", "15"),
+                    "% from peak",
+                    focused_field == Some("trail_pct"),
+                );
+                areas.trail_pct_input = Some(row_rect);
+            }
+            22 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Arm at: +",
+                    &kv(store, "trail_activate_pct", "30"),
+                    "%",
+                    focused_field == Some("trail_activate_pct"),
+                );
+                areas.trail_activate_input = Some(row_rect);
+            }
+            23 => {
                 let label = Line::from(Span::styled(
                     "Accepted Quotes (click to toggle)",
                     Style::default().fg(Color::Gray),
                 ));
                 f.render_widget(Paragraph::new(label), row_rect);
             }
-            12 | 13 => {
+            24 | 25 => {
                 let selected_csv = store
                     .get("accepted_quotes")
                     .map(|v| v.to_string())
                     .unwrap_or_else(|| "BNB,CAKE,USDT,USD1,ASTER,WBNB".to_string());
-                let grid_row = row_index - 12;
+                let grid_row = row_index - 23;
                 let base_idx = grid_row * 3;
                 let cols = Layout::default()
                     .direction(Direction::Horizontal)
@@ -196,7 +314,7 @@ pub fn draw_advanced(
                     accepted_quotes_areas.push(*c);
                 }
             }
-            14 => {
+            26 => {
                 draw_line(
                     f,
                     row_rect,
@@ -207,7 +325,7 @@ pub fn draw_advanced(
                 );
                 areas.wrap_ratio_input = Some(row_rect);
             }
-            15 => {
+            27 => {
                 let avoid_cn = store
                     .get("avoid_chinese")
                     .map(|v| v.as_str() == "true")
@@ -215,7 +333,7 @@ pub fn draw_advanced(
                 draw_checkbox_line(f, row_rect, "Avoid Chinese", avoid_cn);
                 areas.avoid_chinese_toggle = Some(row_rect);
             }
-            16 => {
+            28 => {
                 draw_line(
                     f,
                     row_rect,
@@ -226,7 +344,7 @@ pub fn draw_advanced(
                 );
                 areas.freshness_input = Some(row_rect);
             }
-            17 => {
+            29 => {
                 draw_line(
                     f,
                     row_rect,
@@ -237,6 +355,278 @@ pub fn draw_advanced(
                 );
                 areas.min_pnl_input = Some(row_rect);
             }
+            30 => {
+                let line = Line::from(vec![
+                    Span::styled("Strategy: ", Style::default().fg(Color::White)),
+                    Span::styled(
+                        kv(store, "strategy", "default"),
+                        Style::default().fg(Color::LightCyan),
+                    ),
+                    Span::styled(" (click to cycle)", Style::default().fg(Color::Gray)),
+                ]);
+                f.render_widget(Paragraph::new(line), row_rect);
+                areas.strategy_btn = Some(row_rect);
+            }
+            31 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Momentum buyers: ",
+                    &kv(store, "momentum_min_buyers", "10"),
+                    "",
+                    focused_field == Some("momentum_min_buyers"),
+                );
+                areas.momentum_min_buyers_input = Some(row_rect);
+            }
+            32 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "Max 24h loss: ",
+                    &kv(store, "max_daily_loss_bnb", "0.05"),
+                    " BNB (0 = off; a trip stops sim and trade)",
+                    focused_field == Some("max_daily_loss_bnb"),
+                );
+                areas.max_daily_loss_input = Some(row_rect);
+            }
+            33 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "Max losing streak: ",
+                    &kv(store, "max_losing_streak", "5"),
+                    " (0 = off)",
+                    focused_field == Some("max_losing_streak"),
+                );
+                areas.max_losing_streak_input = Some(row_rect);
+            }
+            34 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "Max deployed: ",
+                    &kv(store, "max_bnb_per_hour", "0.5"),
+                    " BNB/h (0 = off)",
+                    focused_field == Some("max_bnb_per_hour"),
+                );
+                areas.max_bnb_per_hour_input = Some(row_rect);
+            }
+            35 => {
+                let line = Line::from(vec![
+                    Span::styled("Sizing: ", Style::default().fg(Color::White)),
+                    Span::styled(
+                        kv(store, "sizing_mode", "fixed"),
+                        Style::default().fg(Color::LightCyan),
+                    ),
+                    Span::styled(" (click to cycle)", Style::default().fg(Color::Gray)),
+                ]);
+                f.render_widget(Paragraph::new(line), row_rect);
+                areas.sizing_btn = Some(row_rect);
+            }
+            36 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Balance share: ",
+                    &kv(store, "size_balance_pct", "5"),
+                    "%",
+                    focused_field == Some("size_balance_pct"),
+                );
+                areas.size_balance_input = Some(row_rect);
+            }
+            37 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Liquidity share: ",
+                    &kv(store, "size_liq_pct", "1"),
+                    "%",
+                    focused_field == Some("size_liq_pct"),
+                );
+                areas.size_liq_input = Some(row_rect);
+            }
+            38 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Risk per trade: ",
+                    &kv(store, "risk_per_trade_pct", "1"),
+                    "% of balance",
+                    focused_field == Some("risk_per_trade_pct"),
+                );
+                areas.risk_per_trade_input = Some(row_rect);
+            }
+            39 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Reinvest: ",
+                    &kv(store, "compound_pct", "50"),
+                    "% of realized PnL",
+                    focused_field == Some("compound_pct"),
+                );
+                areas.compound_input = Some(row_rect);
+            }
+            40 => {
+                let line = Line::from(vec![
+                    Span::styled("Gas price: ", Style::default().fg(Color::White)),
+                    Span::styled(
+                        kv(store, "gas_mode", "fixed"),
+                        Style::default().fg(Color::LightCyan),
+                    ),
+                    Span::styled(" (click to cycle)", Style::default().fg(Color::Gray)),
+                ]);
+                f.render_widget(Paragraph::new(line), row_rect);
+                areas.gas_mode_btn = Some(row_rect);
+            }
+            41 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Oracle markup: ",
+                    &kv(store, "gas_oracle_pct", "10"),
+                    "%",
+                    focused_field == Some("gas_oracle_pct"),
+                );
+                areas.gas_oracle_pct_input = Some(row_rect);
+            }
+            42 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "Replace stuck tx after: ",
+                    &kv(store, "replace_after_blocks", "5"),
+                    " blocks",
+                    focused_field == Some("replace_after_blocks"),
+                );
+                areas.replace_after_blocks_input = Some(row_rect);
+            }
+            43 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Buy gas bump: ",
+                    &kv(store, "buy_gas_bump_pct", "15"),
+                    "%",
+                    focused_field == Some("buy_gas_bump_pct"),
+                );
+                areas.buy_gas_bump_input = Some(row_rect);
+            }
+            44 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Sell gas bump: ",
+                    &kv(store, "sell_gas_bump_pct", "30"),
+                    "%",
+                    focused_field == Some("sell_gas_bump_pct"),
+                );
+                areas.sell_gas_bump_input = Some(row_rect);
+            }
+            45 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Emergency gas cap: ",
+                    &kv(store, "emergency_max_gwei", "3.0"),
+                    " gwei",
+                    focused_field == Some("emergency_max_gwei"),
+                );
+                areas.emergency_max_gwei_input = Some(row_rect);
+            }
+            46 => {
+                let guard_en = store
+                    .get("liq_guard")
+                    .map(|v| v.as_str() == "true")
+                    .unwrap_or(true);
+                draw_checkbox_line(f, row_rect, "Liquidity-pull exit", guard_en);
+                areas.liq_guard_toggle = Some(row_rect);
+            }
+            47 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Exit when removed: ",
+                    &kv(store, "liq_pull_pct", "30"),
+                    "% of pool",
+                    focused_field == Some("liq_pull_pct"),
+                );
+                areas.liq_pull_input = Some(row_rect);
+            }
+            48 => {
+                let dev_en = store
+                    .get("dev_sell_exit")
+                    .map(|v| v.as_str() == "true")
+                    .unwrap_or(false);
+                draw_checkbox_line(f, row_rect, "Dev-dump exit", dev_en);
+                areas.dev_sell_toggle = Some(row_rect);
+            }
+            49 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Exit when dev sold: ",
+                    &kv(store, "dev_sell_pct", "50"),
+                    "% of holdings",
+                    focused_field == Some("dev_sell_pct"),
+                );
+                areas.dev_sell_input = Some(row_rect);
+            }
+            50 => {
+                let copy_en = store
+                    .get("copy_trade")
+                    .map(|v| v.as_str() == "true")
+                    .unwrap_or(false);
+                draw_checkbox_line(f, row_rect, "Copy trading", copy_en);
+                areas.copy_trade_toggle = Some(row_rect);
+            }
+            51 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Copy size: ",
+                    &kv(store, "copy_buy_bnb", "0.01"),
+                    "BNB",
+                    focused_field == Some("copy_buy_bnb"),
+                );
+                areas.copy_buy_input = Some(row_rect);
+            }
+            52 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Copy delay: ",
+                    &kv(store, "copy_delay_secs", "0"),
+                    "s",
+                    focused_field == Some("copy_delay_secs"),
+                );
+                areas.copy_delay_input = Some(row_rect);
+            }
+            53 => {
+                draw_line(
+                    f,
+                    row_rect,
+                    "  Add/remove wallet: ",
+                    &kv(store, "copy_wallet", ""),
+                    "",
+                    focused_field == Some("copy_wallet"),
+                );
+                areas.copy_wallet_input = Some(row_rect);
+            }
+            54 => {
+                let watched: Vec<String> =
+                    copytrade::wallets().into_iter().map(short_wallet).collect();
+                let list = if watched.is_empty() {
+                    "none".to_string()
+                } else {
+                    watched.join(", ")
+                };
+                let line = Line::from(vec![
+                    Span::raw("  Watching: "),
+                    Span::styled(list, Style::default().fg(Color::Gray)),
+                ]);
+                f.render_widget(Paragraph::new(line), row_rect);
+            }
             _ => {}
         }
     }
@@ -0,0 +1,68 @@
+use super::types::ConfigAreas;
+use crate::libs::governor;
+use ratatui::{prelude::*, widgets::Paragraph};
+
+const RESET: &str = "[Reset]";
+
+/// Circuit breaker status: rolling figures while armed, trip reason and a
+/// reset button once tripped.
+pub fn draw_breaker(f: &mut Frame, area: Rect, areas: &mut ConfigAreas) -> u16 {
+    let line = match governor::tripped() {
+        Some(trip) => {
+            let reset_w = RESET.len() as u16;
+            areas.breaker_reset_btn = Some(Rect {
+                x: area.x + area.width.saturating_sub(reset_w),
+                y: area.y,
+                width: reset_w.min(area.width),
+                height: 1,
+            });
+            Line::from(vec![
+                Span::styled("Breaker: ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    "TRIPPED",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(
+                    " ({}, {}s ago) {} ",
+                    trip.mode,
+                    trip.at.elapsed().as_secs(),
+                    trip.reason
+                )),
+            ])
+        }
+        None => {
+            areas.breaker_reset_btn = None;
+            let sim = governor::stats("sim");
+            let real = governor::stats("trade");
+            Line::from(vec![
+                Span::styled("Breaker: ", Style::default().fg(Color::Gray)),
+                Span::styled("armed", Style::default().fg(Color::Green)),
+                Span::styled(
+                    format!(
+                        "  24h sim {:+.4} / real {:+.4} BNB  streak {}/{}  1h {:.4}/{:.4} BNB",
+                        sim.daily_pnl_bnb,
+                        real.daily_pnl_bnb,
+                        sim.losing_streak,
+                        real.losing_streak,
+                        sim.hourly_deployed_bnb,
+                        real.hourly_deployed_bnb
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        }
+    };
+    f.render_widget(Paragraph::new(line), area);
+    if let Some(btn) = areas.breaker_reset_btn {
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                RESET,
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            btn,
+        );
+    }
+    1
+}
@@ -1,6 +1,7 @@
 use super::types::{ConfigAreas, ConfigStore};
 use super::{
-    advanced::draw_advanced, amount::draw_buy_amount, dexes::draw_dexes, enabled::draw_enabled,
+    advanced::draw_advanced, amount::draw_buy_amount, breaker::draw_breaker, dexes::draw_dexes,
+    enabled::draw_enabled,
 };
 use ratatui::{prelude::*, widgets::Paragraph};
 
@@ -20,6 +21,7 @@ pub fn draw_config_main(
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(1), // Enabled
+            Constraint::Length(1), // Circuit breaker
             Constraint::Length(4), // Dexes title + 3
             Constraint::Length(1), // Buy amount
             Constraint::Min(5),    // Advanced (grows)
@@ -29,11 +31,12 @@ pub fn draw_config_main(
     // Enabled toggle line
     let mut areas = areas_out.clone();
     let _h1 = draw_enabled(f, layout[0], store, &mut areas);
-    let _h2 = draw_dexes(f, layout[1], store, &mut areas);
-    let _h3 = draw_buy_amount(f, layout[2], store, &mut areas, focused_field);
+    let _hb = draw_breaker(f, layout[1], &mut areas);
+    let _h2 = draw_dexes(f, layout[2], store, &mut areas);
+    let _h3 = draw_buy_amount(f, layout[3], store, &mut areas, focused_field);
     let rows_used = draw_advanced(
         f,
-        layout[3],
+        layout[4],
         store,
         &mut areas,
         focused_field,
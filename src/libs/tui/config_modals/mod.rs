@@ -1,5 +1,6 @@
 pub mod advanced;
 pub mod amount;
+pub mod breaker;
 pub mod dexes;
 pub mod enabled;
 pub mod main;
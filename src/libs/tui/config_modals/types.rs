@@ -11,15 +11,27 @@ pub struct ConfigAreas {
     pub buy_amount_input: Option<Rect>,
 
     // extras
-    pub slippage_input: Option<Rect>,
+    pub buy_slippage_input: Option<Rect>,
+    pub sell_slippage_input: Option<Rect>,
+    pub sell_slippage_max_input: Option<Rect>,
     pub max_gwei_input: Option<Rect>,
     pub max_positions_input: Option<Rect>,
     pub tp_toggle: Option<Rect>,
     pub tp_pct_input: Option<Rect>,
+    pub tp_ladder_toggle: Option<Rect>,
+    pub tp_ladder_input: Option<Rect>,
     pub sl_toggle: Option<Rect>,
     pub sl_pct_input: Option<Rect>,
+    pub trail_toggle: Option<Rect>,
+    pub trail_pct_input: Option<Rect>,
+    pub trail_activate_input: Option<Rect>,
     pub min_liq_input: Option<Rect>,
     pub min_buys_input: Option<Rect>,
+    pub honeypot_toggle: Option<Rect>,
+    pub max_tax_input: Option<Rect>,
+    pub honeypot_quote_only_toggle: Option<Rect>,
+    pub risk_toggle: Option<Rect>,
+    pub max_risk_input: Option<Rect>,
     pub max_hold_pnl_toggle: Option<Rect>,
     pub max_hold_input: Option<Rect>,
     pub accepted_quotes: Option<Vec<Rect>>,
@@ -27,35 +39,96 @@ pub struct ConfigAreas {
     pub avoid_chinese_toggle: Option<Rect>,
     pub freshness_input: Option<Rect>,
     pub min_pnl_input: Option<Rect>,
+    pub strategy_btn: Option<Rect>,
+    pub momentum_min_buyers_input: Option<Rect>,
+    pub breaker_reset_btn: Option<Rect>,
+    pub max_daily_loss_input: Option<Rect>,
+    pub max_losing_streak_input: Option<Rect>,
+    pub max_bnb_per_hour_input: Option<Rect>,
+    pub sizing_btn: Option<Rect>,
+    pub size_balance_input: Option<Rect>,
+    pub size_liq_input: Option<Rect>,
+    pub risk_per_trade_input: Option<Rect>,
+    pub compound_input: Option<Rect>,
+    pub gas_mode_btn: Option<Rect>,
+    pub gas_oracle_pct_input: Option<Rect>,
+    pub replace_after_blocks_input: Option<Rect>,
+    pub buy_gas_bump_input: Option<Rect>,
+    pub sell_gas_bump_input: Option<Rect>,
+    pub emergency_max_gwei_input: Option<Rect>,
+    pub liq_guard_toggle: Option<Rect>,
+    pub liq_pull_input: Option<Rect>,
+    pub dev_sell_toggle: Option<Rect>,
+    pub dev_sell_input: Option<Rect>,
+    pub copy_trade_toggle: Option<Rect>,
+    pub copy_buy_input: Option<Rect>,
+    pub copy_delay_input: Option<Rect>,
+    pub copy_wallet_input: Option<Rect>,
 }
 
 pub fn new_store_with_defaults() -> ConfigStore {
     let store: ConfigStore = Arc::new(DashMap::new());
+    insert_defaults(&store);
 
-    // Try to load from cache first
-    if let Ok(cached) = crate::libs::cache::load_autotrade_cache() {
+    // Cached values win; keys the cache predates keep their default
+    if let Ok(mut cached) = crate::libs::cache::load_autotrade_cache() {
+        // The legacy single `slippage_pct` carries over to both sides
+        if let Some(legacy) = cached.remove("slippage_pct") {
+            for key in ["buy_slippage_pct", "sell_slippage_pct"] {
+                cached.entry(key.to_string()).or_insert_with(|| legacy.clone());
+            }
+        }
         if !cached.is_empty() {
+            let mut added: Vec<String> = store
+                .iter()
+                .filter(|e| !cached.contains_key(e.key()))
+                .map(|e| format!("{}={}", e.key(), e.value()))
+                .collect();
             for (k, v) in cached {
                 store.insert(k, v);
             }
-            return store;
+            if !added.is_empty() {
+                added.sort();
+                crate::libs::lookup::save_log_to_file(&format!(
+                    "[config] new settings not in .cache/autotrade.json, using defaults: {}",
+                    added.join(", ")
+                ));
+            }
         }
     }
+    store
+}
 
-    // Default values if no cache
+/// Default for every config key; the fallbacks in the readers match these.
+fn insert_defaults(store: &ConfigStore) {
     store.insert("enabled".into(), "true".into());
     store.insert("dexes".into(), vec!["v2", "v3", "fm"].join(",").into());
     store.insert("buy_amount_wbnb".into(), "0.00001".into());
 
     // extras
-    store.insert("slippage_pct".into(), "0.5".into());
+    store.insert("buy_slippage_pct".into(), "0.5".into());
+    store.insert("sell_slippage_pct".into(), "0.5".into());
+    // Sells that revert on slippage retry with doubled slippage up to this cap
+    store.insert("sell_slippage_max_pct".into(), "10".into());
     store.insert("max_gwei".into(), "1.0".into());
     store.insert("max_positions".into(), "3".into());
     store.insert("tp_enabled".into(), "false".into());
     store.insert("tp_pct".into(), "10".into());
+    // TP ladder tiers as "pnl%:sell%" of the original size, e.g. sell 30% at +50%
+    store.insert("tp_ladder_enabled".into(), "false".into());
+    store.insert("tp_ladder".into(), "50:30,100:30".into());
     store.insert("sl_enabled".into(), "false".into());
     store.insert("sl_pct".into(), "5".into());
+    // Trailing stop: exit when price drops trail_pct below peak, armed at +trail_activate_pct
+    store.insert("trail_enabled".into(), "false".into());
+    store.insert("trail_pct".into(), "15".into());
+    store.insert("trail_activate_pct".into(), "30".into());
     store.insert("min_liquidity".into(), "1000".into()); // Minimum liquidity in USD
+    store.insert("honeypot_check".into(), "false".into()); // Simulated buy+sell before entry
+    store.insert("max_tax_pct".into(), "10".into()); // Max round-trip loss in %
+    store.insert("honeypot_quote_only".into(), "false".into()); // Trust quote-only checks (no eth_simulateV1)
+    store.insert("risk_check".into(), "false".into()); // Bytecode risk scan before entry
+    store.insert("max_risk_score".into(), "50".into()); // Reject tokens scoring above this
     store.insert("min_buys".into(), "3".into()); // Minimum number of buys before trading
                                                  // Max hold in seconds (0 = disabled)
     store.insert("max_hold_secs".into(), "0".into());
@@ -69,5 +142,39 @@ pub fn new_store_with_defaults() -> ConfigStore {
     store.insert("avoid_chinese".into(), "false".into());
     store.insert("freshness_secs".into(), "30".into());
     store.insert("min_pnl_pct".into(), "100".into());
-    store
+    // Entry/exit strategy: default, momentum or dip
+    store.insert("strategy".into(), "default".into());
+    // Distinct buyers the momentum strategy waits for before entering
+    store.insert("momentum_min_buyers".into(), "10".into());
+    // Circuit breaker limits (0 = disabled); tripping turns auto trade off
+    store.insert("max_daily_loss_bnb".into(), "0.05".into());
+    store.insert("max_losing_streak".into(), "5".into());
+    store.insert("max_bnb_per_hour".into(), "0.5".into());
+    // Position sizing: fixed, balance, liquidity, risk or compound
+    store.insert("sizing_mode".into(), "fixed".into());
+    store.insert("size_balance_pct".into(), "5".into());
+    store.insert("size_liq_pct".into(), "1".into());
+    store.insert("risk_per_trade_pct".into(), "1".into());
+    store.insert("compound_pct".into(), "50".into());
+    // Gas price: fixed (max_gwei), oracle or oracle_plus; always capped at max_gwei
+    store.insert("gas_mode".into(), "fixed".into());
+    store.insert("gas_oracle_pct".into(), "10".into());
+    // Rebroadcast txs not mined after this many blocks (0 = never) with bumped gas,
+    // up to max_gwei
+    store.insert("replace_after_blocks".into(), "5".into());
+    store.insert("buy_gas_bump_pct".into(), "15".into());
+    store.insert("sell_gas_bump_pct".into(), "30".into());
+    // Emergency exits outbid the regular price up to this cap instead of max_gwei
+    store.insert("emergency_max_gwei".into(), "3.0".into());
+    // Emergency-sell held V2/V3 positions when this share of pool liquidity is removed
+    store.insert("liq_guard".into(), "true".into());
+    store.insert("liq_pull_pct".into(), "30".into());
+    // Sell open positions once the token's creator wallets sold this share of their holdings
+    store.insert("dev_sell_exit".into(), "false".into());
+    store.insert("dev_sell_pct".into(), "50".into());
+    // Mirror buys/sells of watched wallets; copy_wallet is the add/remove input
+    store.insert("copy_trade".into(), "false".into());
+    store.insert("copy_buy_bnb".into(), "0.01".into());
+    store.insert("copy_delay_secs".into(), "0".into());
+    store.insert("copy_wallet".into(), "".into());
 }
@@ -0,0 +1,177 @@
+//! Gap backfill for log streams. A [`LogCursor`] follows the last block a
+//! stream processed; after a WebSocket reconnect the blocks missed in between
+//! are fetched with `eth_getLogs` over HTTP. Logs are deduplicated by
+//! (tx hash, log index) and logs a reorg marked `removed` are dropped.
+
+use std::collections::{HashSet, VecDeque};
+
+use alloy::primitives::B256;
+use alloy::providers::Provider;
+use alloy::rpc::types::eth::{Filter, Log as RpcLog};
+use anyhow::Result;
+
+use crate::libs::lookup::save_log_to_file;
+
+/// Longest gap backfilled after a reconnect (~6 minutes of BSC blocks)
+pub(crate) const MAX_GAP_BLOCKS: u64 = 500;
+/// Recent (tx hash, log index) pairs remembered for deduplication
+const SEEN_CAP: usize = 4096;
+
+/// First block to backfill after `last` up to `head`, or None when the stream
+/// is not behind. The last block may have been delivered only in part, so it
+/// is fetched again (dedup covers the rest); long gaps are cut to
+/// `MAX_GAP_BLOCKS`.
+fn gap_start(last: u64, head: u64) -> Option<u64> {
+    (last <= head).then(|| last.max(head.saturating_sub(MAX_GAP_BLOCKS)))
+}
+
+#[derive(Debug, Default)]
+pub struct LogCursor {
+    last_block: Option<u64>,
+    seen: HashSet<(B256, u64)>,
+    order: VecDeque<(B256, u64)>,
+}
+
+impl LogCursor {
+    /// Start following from `block` unless the stream already processed logs.
+    pub fn start_at(&mut self, block: u64) {
+        if self.last_block.is_none() {
+            self.last_block = Some(block);
+        }
+    }
+
+    /// Whether `log` should be delivered: false for duplicates and for logs
+    /// removed by a reorg. A removed log is forgotten so its re-mined copy passes.
+    pub fn accept(&mut self, log: &RpcLog) -> bool {
+        let key = log.transaction_hash.zip(log.log_index);
+        if log.removed {
+            if let Some(key) = key {
+                if self.seen.remove(&key) {
+                    self.order.retain(|k| *k != key);
+                }
+            }
+            return false;
+        }
+        if let Some(key) = key {
+            if !self.seen.insert(key) {
+                return false;
+            }
+            self.order.push_back(key);
+            if self.order.len() > SEEN_CAP {
+                if let Some(old) = self.order.pop_front() {
+                    self.seen.remove(&old);
+                }
+            }
+        }
+        if let Some(block) = log.block_number {
+            self.last_block = Some(self.last_block.map_or(block, |last| last.max(block)));
+        }
+        true
+    }
+
+    /// Logs matching `filter` from the last processed block up to `head` that
+    /// were not delivered yet. A cursor that never started just starts at `head`.
+    pub async fn backfill<P: Provider>(
+        &mut self,
+        provider: &P,
+        filter: &Filter,
+        head: u64,
+    ) -> Result<Vec<RpcLog>> {
+        let Some(last) = self.last_block else {
+            self.last_block = Some(head);
+            return Ok(Vec::new());
+        };
+        let Some(from) = gap_start(last, head) else {
+            return Ok(Vec::new());
+        };
+        if from > last {
+            save_log_to_file(&format!(
+                "[ws/backfill] gap of {} blocks, backfilling only the last {}",
+                head - last,
+                MAX_GAP_BLOCKS
+            ));
+        }
+        let logs = provider
+            .get_logs(&filter.clone().from_block(from).to_block(head))
+            .await?;
+        let fresh: Vec<RpcLog> = logs.into_iter().filter(|log| self.accept(log)).collect();
+        self.last_block = Some(head);
+        Ok(fresh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(tx: u8, index: u64, block: u64, removed: bool) -> RpcLog {
+        RpcLog {
+            transaction_hash: Some(B256::repeat_byte(tx)),
+            log_index: Some(index),
+            block_number: Some(block),
+            removed,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn accept_drops_duplicates() {
+        let mut cursor = LogCursor::default();
+        assert!(cursor.accept(&log(1, 0, 10, false)));
+        assert!(!cursor.accept(&log(1, 0, 10, false)));
+        assert!(cursor.accept(&log(1, 1, 10, false)));
+        assert_eq!(cursor.last_block, Some(10));
+    }
+
+    #[test]
+    fn accept_passes_remined_log_after_removal() {
+        let mut cursor = LogCursor::default();
+        assert!(cursor.accept(&log(1, 0, 10, false)));
+        assert!(!cursor.accept(&log(1, 0, 10, true)));
+        assert!(cursor.order.is_empty());
+        assert!(cursor.accept(&log(1, 0, 11, false)));
+        assert!(!cursor.accept(&log(1, 0, 11, false)));
+        assert_eq!(cursor.order.len(), 1);
+    }
+
+    #[test]
+    fn accept_keeps_remined_log_through_eviction() {
+        let mut cursor = LogCursor::default();
+        assert!(cursor.accept(&log(1, 0, 10, false)));
+        assert!(!cursor.accept(&log(1, 0, 10, true)));
+        assert!(cursor.accept(&log(1, 0, 11, false)));
+        // one slot short of evicting the re-mined key
+        for i in 0..SEEN_CAP as u64 - 1 {
+            assert!(cursor.accept(&log(2, i, 12, false)));
+        }
+        assert!(!cursor.accept(&log(1, 0, 11, false)));
+    }
+
+    #[test]
+    fn accept_without_key_always_passes() {
+        let mut cursor = LogCursor::default();
+        let keyless = RpcLog {
+            block_number: Some(5),
+            ..Default::default()
+        };
+        assert!(cursor.accept(&keyless));
+        assert!(cursor.accept(&keyless));
+        assert!(cursor.seen.is_empty());
+    }
+
+    #[test]
+    fn gap_start_refetches_last_block() {
+        assert_eq!(gap_start(100, 100), Some(100));
+        assert_eq!(gap_start(100, 120), Some(100));
+    }
+
+    #[test]
+    fn gap_start_caps_long_gaps() {
+        assert_eq!(gap_start(100, 100 + MAX_GAP_BLOCKS + 50), Some(150));
+    }
+
+    #[test]
+    fn gap_start_skips_when_ahead() {
+        assert_eq!(gap_start(120, 100), None);
+    }
+}
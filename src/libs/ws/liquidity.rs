@@ -0,0 +1,314 @@
+//! Liquidity-pull watch for held pools.
+//!
+//! V2 pairs are watched for `Burn` and LP-token `Transfer`s: an LP transfer
+//! into the pair itself is a `removeLiquidity` in progress, large transfers
+//! elsewhere (out of a lock, to a fresh wallet) often precede one. V3 pools are
+//! watched for `Burn`, which every position-manager `DecreaseLiquidity` ends in.
+//! Removals are sized against the pool's quote holdings before the block.
+
+use crate::libs::lookup::{pct_of, save_log_to_file};
+use crate::libs::ws::subscriptions::SubscriptionManager;
+use alloy::eips::BlockId;
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::eth::{Filter, Log};
+use alloy::sol_types::SolEvent;
+use tokio::sync::mpsc;
+
+alloy::sol! {
+    #[sol(rpc)]
+    interface ILiquidityPool {
+        function token0() view returns (address);
+        function token1() view returns (address);
+        function totalSupply() view returns (uint256);
+    }
+
+    #[sol(rpc)]
+    interface IERC20Balance {
+        function balanceOf(address owner) view returns (uint256);
+    }
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+}
+
+// Both pool versions name their event `Burn`, so each gets its own module
+mod v2 {
+    alloy::sol! {
+        event Burn(address indexed sender, uint256 amount0, uint256 amount1, address indexed to);
+    }
+}
+
+mod v3 {
+    alloy::sol! {
+        event Burn(address indexed owner, int24 indexed tickLower, int24 indexed tickUpper, uint128 amount, uint256 amount0, uint256 amount1);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PullKind {
+    /// Pool `Burn`
+    Burn,
+    /// LP tokens sent into the pair (`removeLiquidity`)
+    RemoveLiquidity,
+    /// Large LP-token transfer between wallets
+    LpTransfer,
+}
+
+impl PullKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PullKind::Burn => "burn",
+            PullKind::RemoveLiquidity => "removeLiquidity",
+            PullKind::LpTransfer => "LP transfer",
+        }
+    }
+}
+
+/// Liquidity taken out of (or moved away from) a held pool.
+#[derive(Clone, Debug)]
+pub struct LiquidityPull {
+    pub pool: Address,
+    pub kind: PullKind,
+    /// Share of the pool's liquidity affected, in %
+    pub removed_pct: f64,
+    pub tx_hash: B256,
+}
+
+/// Block before the one holding `log`, where pre-removal state is read.
+fn parent_block(log: &Log) -> BlockId {
+    match log.block_number {
+        Some(n) if n > 0 => BlockId::number(n - 1),
+        _ => BlockId::latest(),
+    }
+}
+
+/// Quote held by the pool before `log`. Falls back to the latest balance plus
+/// what was removed when the node has pruned the parent state.
+async fn quote_before<P: Provider>(
+    provider: &P,
+    pool: Address,
+    quote: Address,
+    log: &Log,
+    removed: U256,
+) -> U256 {
+    let erc20 = IERC20Balance::new(quote, provider);
+    match erc20.balanceOf(pool).block(parent_block(log)).call().await {
+        Ok(bal) => bal,
+        Err(_) => erc20
+            .balanceOf(pool)
+            .call()
+            .await
+            .map(|b| b + removed)
+            .unwrap_or(removed),
+    }
+}
+
+async fn lp_supply_before<P: Provider>(provider: &P, pool: Address, log: &Log) -> U256 {
+    let lp = ILiquidityPool::new(pool, provider);
+    match lp.totalSupply().block(parent_block(log)).call().await {
+        Ok(s) => s,
+        Err(_) => lp.totalSupply().call().await.unwrap_or(U256::ZERO),
+    }
+}
+
+/// Size a pool log as a liquidity pull, None for logs that are not one.
+async fn parse_pull<P: Provider>(
+    provider: &P,
+    pool: Address,
+    quote_is_token0: bool,
+    quote: Address,
+    log: &Log,
+) -> Option<LiquidityPull> {
+    let topic0 = log.topic0().copied()?;
+    let (kind, part, whole) = if topic0 == v2::Burn::SIGNATURE_HASH {
+        let burn = v2::Burn::decode_log_data(log.data()).ok()?;
+        let removed = if quote_is_token0 {
+            burn.amount0
+        } else {
+            burn.amount1
+        };
+        let before = quote_before(provider, pool, quote, log, removed).await;
+        (PullKind::Burn, removed, before)
+    } else if topic0 == v3::Burn::SIGNATURE_HASH {
+        let burn = v3::Burn::decode_log_data(log.data()).ok()?;
+        if burn.amount == 0 {
+            // zero-liquidity burns only poke fees
+            return None;
+        }
+        let removed = if quote_is_token0 {
+            burn.amount0
+        } else {
+            burn.amount1
+        };
+        let before = quote_before(provider, pool, quote, log, removed).await;
+        (PullKind::Burn, removed, before)
+    } else if topic0 == Transfer::SIGNATURE_HASH {
+        let t = Transfer::decode_log_data(log.data()).ok()?;
+        // mints and the burn leg of a removal are not transfers between holders
+        if t.from == Address::ZERO || t.to == Address::ZERO {
+            return None;
+        }
+        let kind = if t.to == pool {
+            PullKind::RemoveLiquidity
+        } else {
+            PullKind::LpTransfer
+        };
+        let supply = lp_supply_before(provider, pool, log).await;
+        (kind, t.value, supply)
+    } else {
+        return None;
+    };
+    if part.is_zero() {
+        return None;
+    }
+    // the pool read as empty before a nonzero removal: it was all of it
+    let removed_pct = pct_of(part, whole).unwrap_or(100.0);
+    Some(LiquidityPull {
+        pool,
+        kind,
+        removed_pct,
+        tx_hash: log.transaction_hash.unwrap_or_default(),
+    })
+}
+
+/// Whether to forward `pull`, given the largest removal already forwarded
+/// and its tx. A removeLiquidity emits Transfer and Burn in the same tx, so a
+/// removal is only forwarded again when it is larger. LP transfers are always
+/// forwarded and never hide a later removal: an unlock-and-pull tx moves the
+/// LP out of a locker first.
+fn should_forward(forwarded: &mut Option<(B256, f64)>, pull: &LiquidityPull) -> bool {
+    if pull.kind == PullKind::LpTransfer {
+        return true;
+    }
+    if let Some((tx, pct)) = *forwarded {
+        if tx == pull.tx_hash && pull.removed_pct <= pct {
+            return false;
+        }
+    }
+    *forwarded = Some((pull.tx_hash, pull.removed_pct));
+    true
+}
+
+/// Subscribe to liquidity removals on `pool` (held for `token`) and forward
+/// them, a removal at most once per transaction unless a larger one follows. Runs until aborted; the pool's
+/// filter is dropped with it.
+pub async fn watch_pool_liquidity<P: Provider + Clone>(
+    pool: Address,
+    v3: bool,
+    token: Address,
+    subs: SubscriptionManager,
+    provider: P,
+    tx: mpsc::Sender<LiquidityPull>,
+) {
+    let pair = ILiquidityPool::new(pool, &provider);
+    let token0 = loop {
+        match pair.token0().call().await {
+            Ok(t0) => break t0,
+            Err(e) => {
+                save_log_to_file(&format!("[liq] token0 failed for {:#x}: {}", pool, e));
+                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+            }
+        }
+    };
+    let quote_is_token0 = token0 != token;
+    let quote = if quote_is_token0 {
+        token0
+    } else {
+        match pair.token1().call().await {
+            Ok(t1) => t1,
+            Err(e) => {
+                save_log_to_file(&format!("[liq] token1 failed for {:#x}: {}", pool, e));
+                return;
+            }
+        }
+    };
+
+    let topics = if v3 {
+        vec![v3::Burn::SIGNATURE_HASH]
+    } else {
+        vec![v2::Burn::SIGNATURE_HASH, Transfer::SIGNATURE_HASH]
+    };
+    let filter = Filter::new().address(pool).event_signature(topics);
+
+    let mut forwarded: Option<(B256, f64)> = None;
+    let mut logs = subs.subscribe(filter);
+    save_log_to_file(&format!(
+        "[liq] watching {} {:#x}",
+        if v3 { "V3 pool" } else { "V2 pair" },
+        pool
+    ));
+    while let Some(log_item) = logs.recv().await {
+        if log_item.removed {
+            continue;
+        }
+        let Some(pull) = parse_pull(&provider, pool, quote_is_token0, quote, &log_item).await
+        else {
+            continue;
+        };
+        if !should_forward(&mut forwarded, &pull) {
+            continue;
+        }
+        save_log_to_file(&format!(
+            "[liq] {} {:#x} {:.2}% tx={:#x}",
+            pull.kind.label(),
+            pool,
+            pull.removed_pct,
+            pull.tx_hash
+        ));
+        if tx.send(pull).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pull(kind: PullKind, removed_pct: f64, tx: u8) -> LiquidityPull {
+        LiquidityPull {
+            pool: Address::ZERO,
+            kind,
+            removed_pct,
+            tx_hash: B256::repeat_byte(tx),
+        }
+    }
+
+    #[test]
+    fn lp_transfer_does_not_hide_the_removal_that_follows() {
+        let mut forwarded = None;
+        assert!(should_forward(
+            &mut forwarded,
+            &pull(PullKind::LpTransfer, 100.0, 1)
+        ));
+        assert!(should_forward(
+            &mut forwarded,
+            &pull(PullKind::RemoveLiquidity, 90.0, 1)
+        ));
+        assert!(!should_forward(
+            &mut forwarded,
+            &pull(PullKind::Burn, 90.0, 1)
+        ));
+    }
+
+    #[test]
+    fn larger_removal_in_the_same_tx_is_forwarded() {
+        let mut forwarded = None;
+        assert!(should_forward(
+            &mut forwarded,
+            &pull(PullKind::RemoveLiquidity, 5.0, 1)
+        ));
+        assert!(should_forward(
+            &mut forwarded,
+            &pull(PullKind::Burn, 80.0, 1)
+        ));
+        assert!(!should_forward(
+            &mut forwarded,
+            &pull(PullKind::Burn, 10.0, 1)
+        ));
+        assert!(should_forward(
+            &mut forwarded,
+            &pull(PullKind::Burn, 10.0, 2)
+        ));
+    }
+}
@@ -1,3 +1,7 @@
+pub mod backfill;
+pub mod liquidity;
 pub mod pairs;
+pub mod source;
+pub mod subscriptions;
 pub mod swap_aggregator;
 pub mod swaps;
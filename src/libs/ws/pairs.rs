@@ -3,6 +3,7 @@ use anyhow::Result;
 use alloy::primitives::keccak256;
 use alloy::providers::Provider;
 use alloy::rpc::types::eth::Filter;
+use alloy::sol_types::SolEvent;
 use futures_util::future::join3;
 use tokio::sync::{mpsc, Mutex};
 
@@ -11,11 +12,20 @@ use pancakes::pancake::pancake_swap_v2::addresses::PANCAKE_V2_FACTORY;
 use pancakes::plug::v2::{enrich_v2_pair_created, try_parse_v2_pair_topics, v2_pair_created_topic};
 use pancakes::plug::{enrich_v3_pool_created, try_parse_v3_pool_topics};
 
-use crate::libs::bsc::client::BscWsClient;
+use crate::libs::copytrade;
+use crate::libs::devwatch;
+use crate::libs::fills::{TokenPurchase, TokenSale};
+use crate::libs::honeypot::TaxReport;
 use crate::libs::lookup::addr_to_symbol;
 use crate::libs::lookup::{save_log_to_file, trim_chars};
+use crate::libs::ws::source::LogSource;
+use crate::libs::ws::subscriptions::SubscriptionManager;
 use crate::libs::ws::swap_aggregator::SwapAggregator;
-use crate::libs::ws::swaps::{track_v2_pair_swaps, track_v3_pool_swaps, SwapEvent};
+use crate::libs::ws::swaps::{
+    register_swap_tracker, track_v2_pair_swaps, track_v3_pool_swaps, SwapEvent,
+};
+use crate::routy::route::ROUTABLE_QUOTES;
+use crate::shared::WS_CONNECTIONS;
 use alloy::primitives::{Address, B256, U256};
 use pancakes::pancake::pancake_swap::addresses::*;
 use pancakes::pancake::pancake_swap::router::format_token as fmt_token;
@@ -61,6 +71,20 @@ fn extract_price_f64(line: &str) -> Option<f64> {
     trimmed[..end].trim().parse::<f64>().ok()
 }
 
+/// Order a pair as (base, quote), preferring WBNB, then the routable quotes
+/// (USDT, CAKE, USD1, ASTER) so prices are always quoted in a tradable token.
+fn base_quote(token0: Address, token1: Address) -> (Address, Address) {
+    for q in std::iter::once(WBNB).chain(ROUTABLE_QUOTES) {
+        if token0 == q && token1 != q {
+            return (token1, q);
+        }
+        if token1 == q && token0 != q {
+            return (token0, q);
+        }
+    }
+    (token0, token1)
+}
+
 #[derive(Clone, Debug)]
 pub struct PairInfo {
     pub addr1: Address,
@@ -74,12 +98,14 @@ pub struct PairInfo {
     pub buy_count: u32,             // Real buy transactions (from swap events)
     pub sell_count: u32,            // Real sell transactions
     pub unique_buyers: u32,         // Number of unique buyer addresses
+    pub tax: Option<TaxReport>,     // Simulated round-trip tax (None = not checked yet)
 }
 
 pub async fn pancakev2_stream(
     tx_v2: mpsc::Sender<(String, String, PairInfo)>,
-    ws_v2: BscWsClient,
+    logs_v2: LogSource,
     provider_v2: impl Provider + Clone + 'static,
+    subs: SubscriptionManager,
     swap_tx: mpsc::Sender<SwapEvent>,
     swap_agg: SwapAggregator,
 ) {
@@ -94,7 +120,10 @@ pub async fn pancakev2_stream(
 
     loop {
         let filter = base_filter.clone();
-        match ws_v2.subscribe_logs(filter).await {
+        match logs_v2
+            .subscribe_logs_backfilled(filter, provider_v2.clone().erased())
+            .await
+        {
             Ok((mut rx_logs, handle)) => {
                 save_log_to_file("[ws/v2] subscribed to PancakeV2 PairCreated");
                 while let Some(log_item) = rx_logs.recv().await {
@@ -105,7 +134,9 @@ pub async fn pancakev2_stream(
                         let swap_tx_c = swap_tx.clone();
                         let swap_agg_c = swap_agg.clone();
                         let tracked_pairs_c = tracked_pairs.clone();
-                        let ws_for_swaps = ws_v2.clone();
+                        let subs_c = subs.clone();
+                        let creation_tx = log_item.transaction_hash;
+                        let creation_block = log_item.block_number;
                         // Parse non-indexed `pair` field from event data if present:
                         // PairCreated(address indexed token0, address indexed token1, address pair, uint)
                         // -> first 32 bytes of data contain the pair address (right-most 20 bytes)
@@ -127,17 +158,7 @@ pub async fn pancakev2_stream(
                             let _p = permit;
 
                             if let Ok(info) = enrich_v2_pair_created(prov.clone(), t0, t1).await {
-                                let (base, quote) = if info.token0 == WBNB && info.token1 != WBNB {
-                                    (info.token1, WBNB)
-                                } else if info.token1 == WBNB && info.token0 != WBNB {
-                                    (info.token0, WBNB)
-                                } else if info.token0 == USDT && info.token1 != USDT {
-                                    (info.token1, USDT)
-                                } else if info.token1 == USDT && info.token0 != USDT {
-                                    (info.token0, USDT)
-                                } else {
-                                    (info.token0, info.token1)
-                                };
+                                let (base, quote) = base_quote(info.token0, info.token1);
 
                                 let mut pair_addr = if info.pair != Address::ZERO {
                                     info.pair
@@ -170,18 +191,32 @@ pub async fn pancakev2_stream(
                                     guard.insert(pair_addr)
                                 };
                                 if should_track {
-                                    let ws_swaps = ws_for_swaps.clone();
+                                    let subs_swaps = subs_c.clone();
+                                    let prov_swaps = prov.clone();
                                     let swap_tx_pair = swap_tx_c.clone();
-                                    tokio::spawn(async move {
+                                    let tracker = tokio::spawn(async move {
                                         track_v2_pair_swaps(
                                             pair_addr,
                                             base,
                                             is_token0_base,
-                                            ws_swaps,
+                                            subs_swaps,
+                                            prov_swaps,
                                             swap_tx_pair,
                                         )
                                         .await;
                                     });
+                                    register_swap_tracker(pair_addr, tracker);
+                                    let prov_dev = prov.clone();
+                                    tokio::spawn(async move {
+                                        devwatch::learn_creators(
+                                            &prov_dev,
+                                            pair_addr,
+                                            base,
+                                            creation_tx,
+                                            creation_block,
+                                        )
+                                        .await;
+                                    });
                                 }
 
                                 let joined = tokio::time::timeout(
@@ -262,6 +297,7 @@ pub async fn pancakev2_stream(
                                     buy_count,
                                     sell_count,
                                     unique_buyers,
+                                    tax: None,
                                 };
 
                                 // initial delivery must not drop
@@ -332,6 +368,7 @@ pub async fn pancakev2_stream(
                                             buy_count,
                                             sell_count,
                                             unique_buyers,
+                                            tax: None,
                                         };
                                         let _ = tx_r.try_send((line1_r, link.clone(), pair_info_r));
                                     }
@@ -353,8 +390,9 @@ pub async fn pancakev2_stream(
 
 pub async fn pancakev3_stream(
     tx_v3: mpsc::Sender<(String, String, PairInfo)>,
-    ws_v3: BscWsClient,
+    logs_v3: LogSource,
     provider_v3: impl Provider + Clone + 'static,
+    subs: SubscriptionManager,
     swap_tx: mpsc::Sender<SwapEvent>,
     swap_agg: SwapAggregator,
 ) {
@@ -370,7 +408,10 @@ pub async fn pancakev3_stream(
 
     loop {
         let filter = base_filter.clone();
-        match ws_v3.subscribe_logs(filter).await {
+        match logs_v3
+            .subscribe_logs_backfilled(filter, provider_v3.clone().erased())
+            .await
+        {
             Ok((mut rx_logs, handle)) => {
                 save_log_to_file("[ws/v3] subscribed to PancakeV3 PoolCreated");
                 while let Some(log_item) = rx_logs.recv().await {
@@ -381,23 +422,15 @@ pub async fn pancakev3_stream(
                         let swap_tx_c = swap_tx.clone();
                         let swap_agg_c = swap_agg.clone();
                         let tracked_pools_c = tracked_pools.clone();
-                        let ws_for_swaps = ws_v3.clone();
+                        let subs_c = subs.clone();
+                        let creation_tx = log_item.transaction_hash;
+                        let creation_block = log_item.block_number;
 
                         tokio::spawn(async move {
                             let _p = permit;
 
                             if let Ok(info) = enrich_v3_pool_created(prov.clone(), t0, t1).await {
-                                let (base, quote) = if info.token0 == WBNB && info.token1 != WBNB {
-                                    (info.token1, WBNB)
-                                } else if info.token1 == WBNB && info.token0 != WBNB {
-                                    (info.token0, WBNB)
-                                } else if info.token0 == USDT && info.token1 != USDT {
-                                    (info.token1, USDT)
-                                } else if info.token1 == USDT && info.token0 != USDT {
-                                    (info.token0, USDT)
-                                } else {
-                                    (info.token0, info.token1)
-                                };
+                                let (base, quote) = base_quote(info.token0, info.token1);
 
                                 let joined = tokio::time::timeout(
                                     std::time::Duration::from_secs(2),
@@ -452,18 +485,32 @@ pub async fn pancakev3_stream(
                                     guard.insert(pool_addr)
                                 };
                                 if should_track {
-                                    let ws_swaps = ws_for_swaps.clone();
+                                    let subs_swaps = subs_c.clone();
+                                    let prov_swaps = prov.clone();
                                     let swap_tx_pair = swap_tx_c.clone();
-                                    tokio::spawn(async move {
+                                    let tracker = tokio::spawn(async move {
                                         track_v3_pool_swaps(
                                             pool_addr,
                                             base,
                                             is_token0_base,
-                                            ws_swaps,
+                                            subs_swaps,
+                                            prov_swaps,
                                             swap_tx_pair,
                                         )
                                         .await;
                                     });
+                                    register_swap_tracker(pool_addr, tracker);
+                                    let prov_dev = prov.clone();
+                                    tokio::spawn(async move {
+                                        devwatch::learn_creators(
+                                            &prov_dev,
+                                            pool_addr,
+                                            base,
+                                            creation_tx,
+                                            creation_block,
+                                        )
+                                        .await;
+                                    });
                                 }
 
                                 let (liq_q_units, dec_q) =
@@ -507,6 +554,7 @@ pub async fn pancakev3_stream(
                                     buy_count,
                                     sell_count,
                                     unique_buyers,
+                                    tax: None,
                                 };
 
                                 // FIRST publish must never drop
@@ -585,6 +633,7 @@ pub async fn pancakev3_stream(
                                                 buy_count,
                                                 sell_count,
                                                 unique_buyers,
+                                                tax: None,
                                             };
                                             let _ = tx_r.try_send((
                                                 line1_refresh,
@@ -628,7 +677,7 @@ fn addr_from_word(word32: &[u8]) -> Address {
 
 pub async fn fourmeme_stream(
     tx: mpsc::Sender<(String, String, PairInfo)>,
-    ws_fm: BscWsClient,
+    logs_fm: LogSource,
     provider_fm: impl Provider + Clone + 'static,
 ) {
     // Keep it simple: filter by address, then gate by topic0 in code.
@@ -637,7 +686,10 @@ pub async fn fourmeme_stream(
 
     loop {
         let filter = base_filter.clone();
-        match ws_fm.subscribe_logs(filter).await {
+        match logs_fm
+            .subscribe_logs_backfilled(filter, provider_fm.clone().erased())
+            .await
+        {
             Ok((mut rx_logs, handle)) => {
                 save_log_to_file("[ws/fm] subscribed to TOKEN_MANAGER_2 (creation-filtered)");
 
@@ -648,6 +700,35 @@ pub async fn fourmeme_stream(
                     }
                     let topic0 = topics[0];
 
+                    // Bonding-curve trades: copy-trading signals and dev sells
+                    let tx_hash = log_item.transaction_hash.unwrap_or_default();
+                    if topic0 == TokenPurchase::SIGNATURE_HASH {
+                        if let Ok(buy) = TokenPurchase::decode_log_data(log_item.data()) {
+                            copytrade::report_swap(buy.token, buy.account, true, tx_hash);
+                        }
+                        continue;
+                    }
+                    if topic0 == TokenSale::SIGNATURE_HASH {
+                        if let Ok(sale) = TokenSale::decode_log_data(log_item.data()) {
+                            copytrade::report_swap(sale.token, sale.account, false, tx_hash);
+                            if devwatch::has_creators(sale.token) {
+                                let prov = provider_fm.clone();
+                                tokio::spawn(async move {
+                                    devwatch::check_sell(
+                                        &prov,
+                                        sale.token,
+                                        sale.token,
+                                        sale.account,
+                                        tx_hash,
+                                        sale.amount,
+                                    )
+                                    .await;
+                                });
+                            }
+                        }
+                        continue;
+                    }
+
                     // Only handle the two creation-ish topics we identified
                     if topic0 != TM2_TOPIC_TOKEN_CREATE && topic0 != TM2_TOPIC_ALT_CREATE {
                         continue;
@@ -793,6 +874,7 @@ pub async fn fourmeme_stream(
                             buy_count: 0,
                             sell_count: 0,
                             unique_buyers: 0,
+                            tax: None,
                         };
 
                         let _ = tx.try_send((line1.clone(), link.clone(), pair_info.clone()));
@@ -869,6 +951,7 @@ pub async fn fourmeme_stream(
                                         buy_count,
                                         sell_count,
                                         unique_buyers: 0,
+                                        tax: None,
                                     };
                                     let _ = tx_refresh.try_send((
                                         line1_refresh,
@@ -879,6 +962,20 @@ pub async fn fourmeme_stream(
                             }
                         });
 
+                        let prov_dev = provider_fm.clone();
+                        let creation_tx = log_item.transaction_hash;
+                        let creation_block = log_item.block_number;
+                        tokio::spawn(async move {
+                            devwatch::learn_creators(
+                                &prov_dev,
+                                base,
+                                base,
+                                creation_tx,
+                                creation_block,
+                            )
+                            .await;
+                        });
+
                         seen_bases.insert(base);
                         break 'cand;
                     }
@@ -897,7 +994,7 @@ pub async fn fourmeme_stream(
 
 pub fn spawn_pair_streams<P: Provider + Clone + Send + Sync + 'static>(
     provider: P,
-    ws: BscWsClient,
+    logs: LogSource,
 ) -> Result<(
     mpsc::Receiver<(String, String, PairInfo)>,
     tokio::task::JoinHandle<()>,
@@ -910,15 +1007,18 @@ pub fn spawn_pair_streams<P: Provider + Clone + Send + Sync + 'static>(
         let tx_v3 = tx.clone();
         let tx_fm = tx.clone();
 
-        let ws_v2 = ws.clone();
-        let ws_v3 = ws.clone();
-        let ws_fm = ws.clone();
+        let logs_v2 = logs.clone();
+        let logs_v3 = logs.clone();
+        let logs_fm = logs.clone();
         let provider_v2 = provider.clone();
         let provider_v3 = provider.clone();
         let provider_fm = provider.clone();
         let swap_agg = SwapAggregator::new();
         let (swap_tx, swap_rx) = mpsc::channel::<SwapEvent>(4096);
         swap_agg.spawn_processor(swap_rx);
+        let subs = SubscriptionManager::new(&logs, *WS_CONNECTIONS, provider.clone().erased());
+        let subs_v2 = subs.clone();
+        let subs_v3 = subs;
         let swap_tx_v2 = swap_tx.clone();
         let swap_tx_v3 = swap_tx.clone();
         let swap_agg_v2 = swap_agg.clone();
@@ -927,8 +1027,9 @@ pub fn spawn_pair_streams<P: Provider + Clone + Send + Sync + 'static>(
         let v2_task = tokio::spawn(async move {
             pancakev2_stream(
                 tx_v2.clone(),
-                ws_v2,
+                logs_v2,
                 provider_v2,
+                subs_v2,
                 swap_tx_v2,
                 swap_agg_v2,
             )
@@ -938,8 +1039,9 @@ pub fn spawn_pair_streams<P: Provider + Clone + Send + Sync + 'static>(
         let v3_task = tokio::spawn(async move {
             pancakev3_stream(
                 tx_v3.clone(),
-                ws_v3,
+                logs_v3,
                 provider_v3,
+                subs_v3,
                 swap_tx_v3,
                 swap_agg_v3,
             )
@@ -947,7 +1049,7 @@ pub fn spawn_pair_streams<P: Provider + Clone + Send + Sync + 'static>(
         });
 
         let fm_task = tokio::spawn(async move {
-            fourmeme_stream(tx_fm.clone(), ws_fm, provider_fm).await;
+            fourmeme_stream(tx_fm.clone(), logs_fm, provider_fm).await;
         });
 
         // Lightweight heartbeat: send a no-op (Price: ?) line periodically so the
@@ -972,6 +1074,7 @@ pub fn spawn_pair_streams<P: Provider + Clone + Send + Sync + 'static>(
                     buy_count: 0,
                     sell_count: 0,
                     unique_buyers: 0,
+                    tax: None,
                 };
                 let _ = tx_hb.try_send((line1, link, pair_info));
             }
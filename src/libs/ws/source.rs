@@ -0,0 +1,76 @@
+//! Where chain logs come from. [`LogSource::Ws`] subscribes over the
+//! `BSC_WSS` WebSocket; [`LogSource::Poll`] polls `eth_getLogs` over the
+//! `BSC_RPC` HTTP endpoint for plans without WebSocket access. The pair, swap
+//! and price streams take a `LogSource` and run the same on either.
+
+use std::time::Duration;
+
+use alloy::providers::DynProvider;
+use alloy::rpc::types::eth::{Filter, Log as RpcLog};
+use anyhow::{Context, Result};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::libs::bsc::client::{BscClient, BscWsClient};
+use crate::libs::config::Config;
+use crate::libs::ws::subscriptions::poll_logs;
+
+#[derive(Clone, Debug)]
+pub enum LogSource {
+    /// `eth_subscribe` over WebSocket
+    Ws(BscWsClient),
+    /// `eth_getLogs` over HTTP every `every`
+    Poll { client: BscClient, every: Duration },
+}
+
+impl LogSource {
+    /// Backend picked by `LOG_SOURCE` (see [`Config`]).
+    pub async fn from_config(cfg: &Config) -> Result<Self> {
+        if cfg.log_source == "poll" {
+            let client = BscClient::new(cfg.bsc_rpc.clone(), cfg.private_key.clone()).await?;
+            return Ok(Self::Poll {
+                client,
+                every: Duration::from_millis(cfg.poll_interval_ms.max(100)),
+            });
+        }
+        let wss = cfg
+            .bsc_wss
+            .clone()
+            .context("BSC_WSS is not set; set it or use LOG_SOURCE=poll")?;
+        Ok(Self::Ws(
+            BscWsClient::new(wss, cfg.private_key.clone()).await?,
+        ))
+    }
+
+    /// Short name for logs and the TUI.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ws(_) => "ws",
+            Self::Poll { .. } => "http poll",
+        }
+    }
+
+    /// Logs matching `filter` until the receiver is dropped or the handle aborted.
+    pub async fn subscribe_logs(
+        &self,
+        filter: Filter,
+    ) -> Result<(mpsc::Receiver<RpcLog>, JoinHandle<()>)> {
+        match self {
+            Self::Ws(ws) => ws.subscribe_logs(filter).await,
+            Self::Poll { client, every } => Ok(poll_logs(client.clone(), *every, filter)),
+        }
+    }
+
+    /// Like [`subscribe_logs`](Self::subscribe_logs), with WebSocket reconnect
+    /// gaps backfilled through `backfill`. Polling never skips blocks.
+    pub async fn subscribe_logs_backfilled(
+        &self,
+        filter: Filter,
+        backfill: DynProvider,
+    ) -> Result<(mpsc::Receiver<RpcLog>, JoinHandle<()>)> {
+        match self {
+            Self::Ws(ws) => ws.subscribe_logs_backfilled(filter, backfill).await,
+            Self::Poll { .. } => self.subscribe_logs(filter).await,
+        }
+    }
+}
@@ -0,0 +1,554 @@
+//! Shared log subscriptions. Where [`BscWsClient::subscribe_logs`] opens one
+//! WebSocket per filter and [`BscWsClient::subscribe_logs_tagged`] fixes its
+//! filters up front, a [`SubscriptionManager`] keeps a small pool of
+//! connections and adds/removes `eth_subscribe` filters on them while running.
+//! Each filter gets its own [`LogSubscription`]; dropping it unsubscribes.
+//! Filters are resubscribed after a reconnect and the blocks missed meanwhile
+//! are backfilled over HTTP (see [`LogCursor`]).
+//!
+//! On a [`LogSource::Poll`] source the same filters are served by one poller
+//! that fetches new blocks with `eth_getLogs` instead.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy::primitives::{Address, B256};
+use alloy::providers::{DynProvider, Provider};
+use alloy::rpc::types::eth::{Filter, Log as RpcLog};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+use crate::libs::bsc::client::BscClient;
+use crate::libs::bsc::endpoints;
+use crate::libs::lookup::save_log_to_file;
+use crate::libs::ws::backfill::{LogCursor, MAX_GAP_BLOCKS};
+use crate::libs::ws::source::LogSource;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const RETRY_AFTER: Duration = Duration::from_secs(3);
+/// Logs buffered per subscription before new ones are dropped
+const SUB_BUFFER: usize = 1024;
+/// JSON-RPC id of `eth_unsubscribe` requests; subscribe requests use the filter id
+const UNSUBSCRIBE_REQ: u64 = 0;
+
+enum Command {
+    Subscribe {
+        id: u64,
+        filter: Box<Filter>,
+        tx: mpsc::Sender<RpcLog>,
+    },
+    Unsubscribe {
+        id: u64,
+    },
+}
+
+/// A filter placed on a connection and where its consumer is at.
+struct Stream {
+    filter: Filter,
+    tx: mpsc::Sender<RpcLog>,
+    cursor: LogCursor,
+}
+
+impl Stream {
+    fn new(filter: Filter, tx: mpsc::Sender<RpcLog>) -> Self {
+        Self {
+            filter,
+            tx,
+            cursor: LogCursor::default(),
+        }
+    }
+
+    /// Hand `log` to the consumer unless it was delivered already or reorged out.
+    fn deliver(&mut self, log: RpcLog, id: u64) {
+        if !self.cursor.accept(&log) {
+            return;
+        }
+        if let Err(mpsc::error::TrySendError::Full(_)) = self.tx.try_send(log) {
+            save_log_to_file(&format!(
+                "[ws/subs] consumer of filter {id} lagging, log dropped"
+            ));
+        }
+    }
+
+    /// Per-address filters of the poller are merged into one request.
+    fn mergeable(&self) -> bool {
+        !self.filter.address.is_empty() && self.filter.topics[1..].iter().all(|t| t.is_empty())
+    }
+}
+
+struct Connection {
+    cmd_tx: mpsc::UnboundedSender<Command>,
+    /// Filters currently placed on this connection
+    active: Arc<AtomicUsize>,
+}
+
+/// Pool of WebSocket connections (or one poller) that log filters are spread over.
+#[derive(Clone)]
+pub struct SubscriptionManager {
+    conns: Arc<Vec<Connection>>,
+    next_id: Arc<AtomicU64>,
+}
+
+/// Logs matching one filter. The filter is removed when this is dropped.
+pub struct LogSubscription {
+    id: u64,
+    rx: mpsc::Receiver<RpcLog>,
+    cmd_tx: mpsc::UnboundedSender<Command>,
+    active: Arc<AtomicUsize>,
+}
+
+impl SubscriptionManager {
+    /// Spawn `connections` WebSocket connections to the source's endpoint, or
+    /// a single poller on a polling source. Gaps after WebSocket reconnects
+    /// are backfilled through `backfill`.
+    pub fn new(source: &LogSource, connections: usize, backfill: DynProvider) -> Self {
+        let connection = |cmd_tx| Connection {
+            cmd_tx,
+            active: Arc::new(AtomicUsize::new(0)),
+        };
+        let conns = match source {
+            LogSource::Ws(ws) => (0..connections.max(1))
+                .map(|n| {
+                    let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+                    tokio::spawn(run_connection(
+                        ws.urls().to_vec(),
+                        n,
+                        backfill.clone(),
+                        cmd_rx,
+                    ));
+                    connection(cmd_tx)
+                })
+                .collect(),
+            LogSource::Poll { client, every } => {
+                let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+                tokio::spawn(run_poller(client.clone(), *every, cmd_rx));
+                vec![connection(cmd_tx)]
+            }
+        };
+        Self {
+            conns: Arc::new(conns),
+            // 0 is reserved for eth_unsubscribe requests
+            next_id: Arc::new(AtomicU64::new(UNSUBSCRIBE_REQ + 1)),
+        }
+    }
+
+    /// Subscribe `filter` on the least loaded connection.
+    pub fn subscribe(&self, filter: Filter) -> LogSubscription {
+        let conn = self
+            .conns
+            .iter()
+            .min_by_key(|c| c.active.load(Ordering::Relaxed))
+            .expect("at least one connection");
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::channel(SUB_BUFFER);
+        conn.active.fetch_add(1, Ordering::Relaxed);
+        let _ = conn.cmd_tx.send(Command::Subscribe {
+            id,
+            filter: Box::new(filter),
+            tx,
+        });
+        LogSubscription {
+            id,
+            rx,
+            cmd_tx: conn.cmd_tx.clone(),
+            active: conn.active.clone(),
+        }
+    }
+}
+
+impl LogSubscription {
+    /// Next log; None once the manager is gone.
+    pub async fn recv(&mut self) -> Option<RpcLog> {
+        self.rx.recv().await
+    }
+}
+
+impl Drop for LogSubscription {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::Relaxed);
+        let _ = self.cmd_tx.send(Command::Unsubscribe { id: self.id });
+    }
+}
+
+async fn send_json(ws: &mut WsStream, req: Value) -> bool {
+    match ws.send(Message::Text(req.to_string().into())).await {
+        Ok(()) => true,
+        Err(e) => {
+            save_log_to_file(&format!("[ws/subs] send failed: {e}"));
+            false
+        }
+    }
+}
+
+async fn send_subscribe(ws: &mut WsStream, id: u64, filter: &Filter) -> bool {
+    send_json(
+        ws,
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "eth_subscribe",
+            "params": ["logs", filter],
+        }),
+    )
+    .await
+}
+
+async fn send_unsubscribe(ws: &mut WsStream, sub_id: &str) -> bool {
+    send_json(
+        ws,
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": UNSUBSCRIBE_REQ,
+            "method": "eth_unsubscribe",
+            "params": [sub_id],
+        }),
+    )
+    .await
+}
+
+/// Wait out the reconnect delay while still taking commands. Returns false
+/// once the manager is gone.
+async fn wait_offline(
+    cmd_rx: &mut mpsc::UnboundedReceiver<Command>,
+    filters: &mut HashMap<u64, Stream>,
+) -> bool {
+    let deadline = tokio::time::Instant::now() + RETRY_AFTER;
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(deadline) => return true,
+            cmd = cmd_rx.recv() => match cmd {
+                None => return false,
+                Some(Command::Subscribe { id, filter, tx }) => {
+                    filters.insert(id, Stream::new(*filter, tx));
+                }
+                Some(Command::Unsubscribe { id }) => {
+                    filters.remove(&id);
+                }
+            },
+        }
+    }
+}
+
+async fn run_connection(
+    urls: Vec<Url>,
+    n: usize,
+    backfill: DynProvider,
+    mut cmd_rx: mpsc::UnboundedReceiver<Command>,
+) {
+    let mut filters: HashMap<u64, Stream> = HashMap::new();
+    loop {
+        let url = endpoints::best(&urls);
+        let mut ws = match connect_async(url.as_str()).await {
+            Ok((ws, _)) => ws,
+            Err(e) => {
+                endpoints::report_failure(&url, false);
+                save_log_to_file(&format!(
+                    "[ws/subs#{n}] connect error: {e}, retrying in 3s …"
+                ));
+                if !wait_offline(&mut cmd_rx, &mut filters).await {
+                    return;
+                }
+                continue;
+            }
+        };
+        save_log_to_file(&format!(
+            "[ws/subs#{n}] connected, subscribing {} filters",
+            filters.len()
+        ));
+
+        // subscription id -> filter id, and back
+        let mut subs: HashMap<String, u64> = HashMap::new();
+        let mut sub_of: HashMap<u64, String> = HashMap::new();
+        let mut healthy = true;
+        for (id, stream) in filters.iter() {
+            if !send_subscribe(&mut ws, *id, &stream.filter).await {
+                healthy = false;
+                break;
+            }
+        }
+
+        // live logs queue up on the socket while the gap is fetched; filters
+        // added later start following from the newest block seen
+        let mut head = match backfill.get_block_number().await {
+            Ok(head) => Some(head),
+            Err(e) => {
+                save_log_to_file(&format!("[ws/subs#{n}] head block unavailable: {e}"));
+                None
+            }
+        };
+        if let Some(head) = head.filter(|_| healthy) {
+            let mut missed = 0usize;
+            for (id, stream) in filters.iter_mut() {
+                match stream
+                    .cursor
+                    .backfill(&backfill, &stream.filter, head)
+                    .await
+                {
+                    Ok(logs) => {
+                        missed += logs.len();
+                        for log in logs {
+                            stream.deliver(log, *id);
+                        }
+                    }
+                    Err(e) => save_log_to_file(&format!(
+                        "[ws/subs#{n}] backfill of filter {id} failed: {e}"
+                    )),
+                }
+            }
+            if missed > 0 {
+                save_log_to_file(&format!("[ws/subs#{n}] backfilled {missed} missed logs"));
+            }
+        }
+
+        while healthy {
+            tokio::select! {
+                cmd = cmd_rx.recv() => match cmd {
+                    None => return,
+                    Some(Command::Subscribe { id, filter, tx }) => {
+                        healthy = send_subscribe(&mut ws, id, &filter).await;
+                        let mut stream = Stream::new(*filter, tx);
+                        if let Some(head) = head {
+                            stream.cursor.start_at(head);
+                        }
+                        filters.insert(id, stream);
+                    }
+                    Some(Command::Unsubscribe { id }) => {
+                        filters.remove(&id);
+                        if let Some(sub_id) = sub_of.remove(&id) {
+                            subs.remove(&sub_id);
+                            healthy = send_unsubscribe(&mut ws, &sub_id).await;
+                        }
+                    }
+                },
+                msg = ws.next() => match msg {
+                    Some(Ok(Message::Text(txt))) => {
+                        let Ok(v) = serde_json::from_str::<Value>(&txt) else {
+                            continue;
+                        };
+                        if v.get("method").and_then(|m| m.as_str()) == Some("eth_subscription") {
+                            let Some(params) = v.get("params") else {
+                                continue;
+                            };
+                            let Some(id) = params
+                                .get("subscription")
+                                .and_then(|s| s.as_str())
+                                .and_then(|s| subs.get(s))
+                            else {
+                                continue;
+                            };
+                            let id = *id;
+                            let Some(stream) = filters.get_mut(&id) else {
+                                continue;
+                            };
+                            let Some(result) = params.get("result") else {
+                                continue;
+                            };
+                            match serde_json::from_value::<RpcLog>(result.clone()) {
+                                Ok(log) => {
+                                    if let Some(block) = log.block_number {
+                                        head = Some(head.map_or(block, |h| h.max(block)));
+                                    }
+                                    stream.deliver(log, id);
+                                }
+                                Err(e) => save_log_to_file(&format!(
+                                    "[ws/subs#{n}] failed to decode log: {e}"
+                                )),
+                            }
+                            continue;
+                        }
+                        // {"id":N,"result":"0xsub…"} acks eth_subscribe for filter N
+                        let Some(id) = v.get("id").and_then(|i| i.as_u64()) else {
+                            continue;
+                        };
+                        if id == UNSUBSCRIBE_REQ {
+                            continue;
+                        }
+                        if let Some(err) = v.get("error") {
+                            save_log_to_file(&format!(
+                                "[ws/subs#{n}] subscribe of filter {id} failed: {err}"
+                            ));
+                            continue;
+                        }
+                        let Some(sub_id) = v.get("result").and_then(|r| r.as_str()) else {
+                            continue;
+                        };
+                        if filters.contains_key(&id) {
+                            subs.insert(sub_id.to_string(), id);
+                            sub_of.insert(id, sub_id.to_string());
+                        } else {
+                            // dropped before the ack came in
+                            healthy = send_unsubscribe(&mut ws, sub_id).await;
+                        }
+                    }
+                    Some(Ok(Message::Ping(p))) => {
+                        let _ = ws.send(Message::Pong(p)).await;
+                    }
+                    Some(Ok(Message::Close(frame))) => {
+                        if let Some(f) = frame {
+                            save_log_to_file(&format!(
+                                "[ws/subs#{n}] CLOSE code={} reason={}",
+                                f.code, f.reason
+                            ));
+                        } else {
+                            save_log_to_file(&format!("[ws/subs#{n}] CLOSE (no frame)"));
+                        }
+                        healthy = false;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        save_log_to_file(&format!("[ws/subs#{n}] ws error: {e}"));
+                        healthy = false;
+                    }
+                    None => healthy = false,
+                },
+            }
+        }
+
+        save_log_to_file(&format!(
+            "[ws/subs#{n}] stream ended, resubscribing {} filters in 3s …",
+            filters.len()
+        ));
+        if !wait_offline(&mut cmd_rx, &mut filters).await {
+            return;
+        }
+    }
+}
+
+/// Poll `filter` on its own, for [`LogSource::subscribe_logs`] on a polling
+/// source. Stops once the receiver is dropped.
+pub fn poll_logs(
+    client: BscClient,
+    every: Duration,
+    filter: Filter,
+) -> (mpsc::Receiver<RpcLog>, JoinHandle<()>) {
+    let (tx, rx) = mpsc::channel(SUB_BUFFER);
+    let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+    let _ = cmd_tx.send(Command::Subscribe {
+        id: 1,
+        filter: Box::new(filter),
+        tx,
+    });
+    (rx, tokio::spawn(run_poller(client, every, cmd_rx)))
+}
+
+/// Polling counterpart of [`run_connection`]: every `every`, the blocks mined
+/// since the last poll are fetched for all filters (in `MAX_GAP_BLOCKS`
+/// chunks) and routed to their consumers. Runs until no commands can arrive and every consumer is gone.
+async fn run_poller(
+    client: BscClient,
+    every: Duration,
+    mut cmd_rx: mpsc::UnboundedReceiver<Command>,
+) {
+    let mut filters: HashMap<u64, Stream> = HashMap::new();
+    let mut commands_open = true;
+    // last block delivered
+    let mut last: Option<u64> = None;
+    let mut ticker = tokio::time::interval(every);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            cmd = cmd_rx.recv(), if commands_open => match cmd {
+                None => commands_open = false,
+                Some(Command::Subscribe { id, filter, tx }) => {
+                    filters.insert(id, Stream::new(*filter, tx));
+                }
+                Some(Command::Unsubscribe { id }) => {
+                    filters.remove(&id);
+                }
+            },
+            _ = ticker.tick() => {
+                filters.retain(|_, stream| !stream.tx.is_closed());
+                if filters.is_empty() {
+                    if !commands_open {
+                        return;
+                    }
+                    continue;
+                }
+                let head = match client.block_number().await {
+                    Ok(head) => head,
+                    Err(e) => {
+                        save_log_to_file(&format!("[ws/poll] eth_blockNumber failed: {e}"));
+                        continue;
+                    }
+                };
+                let mut from = match last {
+                    Some(last) => last + 1,
+                    None => head,
+                };
+                // long gaps are paged in `MAX_GAP_BLOCKS` chunks so none is skipped
+                while from <= head {
+                    let to = head.min(from + MAX_GAP_BLOCKS - 1);
+                    match poll_range(&client, &mut filters, from, to).await {
+                        Ok(()) => {
+                            last = Some(to);
+                            from = to + 1;
+                        }
+                        // the rest is polled again on the next tick
+                        Err(e) => {
+                            save_log_to_file(&format!(
+                                "[ws/poll] eth_getLogs {from}..={to} failed: {e}"
+                            ));
+                            break;
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Fetch the logs of blocks `from..=to` for every filter and deliver them.
+/// Per-address filters share one request and are told apart locally.
+async fn poll_range(
+    client: &BscClient,
+    filters: &mut HashMap<u64, Stream>,
+    from: u64,
+    to: u64,
+) -> anyhow::Result<()> {
+    let merged: Vec<u64> = filters
+        .iter()
+        .filter(|(_, stream)| stream.mergeable())
+        .map(|(id, _)| *id)
+        .collect();
+    if !merged.is_empty() {
+        let mut addresses: HashSet<Address> = HashSet::new();
+        let mut events: HashSet<B256> = HashSet::new();
+        let mut any_event = false;
+        for stream in merged.iter().filter_map(|id| filters.get(id)) {
+            addresses.extend(stream.filter.address.iter().copied());
+            any_event |= stream.filter.topics[0].is_empty();
+            events.extend(stream.filter.topics[0].iter().copied());
+        }
+        let mut combined = Filter::new()
+            .address(addresses.into_iter().collect::<Vec<_>>())
+            .from_block(from)
+            .to_block(to);
+        if !any_event {
+            combined = combined.event_signature(events.into_iter().collect::<Vec<_>>());
+        }
+        for log in client.get_logs(&combined).await? {
+            for id in merged.iter() {
+                if let Some(stream) = filters.get_mut(id) {
+                    if stream.filter.matches(&log.inner) {
+                        stream.deliver(log.clone(), *id);
+                    }
+                }
+            }
+        }
+    }
+    for (id, stream) in filters.iter_mut().filter(|(_, stream)| !stream.mergeable()) {
+        let filter = stream.filter.clone().from_block(from).to_block(to);
+        for log in client.get_logs(&filter).await? {
+            stream.deliver(log, *id);
+        }
+    }
+    Ok(())
+}
@@ -47,7 +47,11 @@ impl SwapAggregator {
     /// Process incoming swap events
     pub async fn process_event(&self, event: SwapEvent) {
         let key = format!("{:#x}", event.pair);
-        let direction = if event.is_buy { "BUY" } else { "SELL" };
+        let direction = match (event.is_buy, event.by_dev) {
+            (true, _) => "BUY",
+            (false, true) => "DEV SELL",
+            (false, false) => "SELL",
+        };
 
         let mut stats = self.stats.write().await;
 
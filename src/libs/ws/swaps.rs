@@ -1,12 +1,18 @@
 // UNUSED
-use crate::libs::bsc::client::BscWsClient;
+use crate::libs::copytrade;
+use crate::libs::devwatch;
 use crate::libs::lookup::save_log_to_file;
+use crate::libs::ws::subscriptions::SubscriptionManager;
+use alloy::network::TransactionResponse;
 use alloy::primitives::{keccak256, Address, B256, U256};
 use alloy::providers::Provider;
 use alloy::rpc::types::eth::{Filter, Log};
 use anyhow::Result;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 /// Represents a swap transaction (buy or sell)
 #[derive(Clone, Debug)]
@@ -18,6 +24,8 @@ pub struct SwapEvent {
     pub amount_out: U256,
     pub timestamp: u64,
     pub tx_hash: B256,
+    /// Sell sent by the token's creator or a wallet it funded
+    pub by_dev: bool,
 }
 
 /// Aggregated swap info for a pair
@@ -29,6 +37,26 @@ pub struct SwapStats {
     pub last_buyer: Option<Address>,
 }
 
+/// Running swap trackers by pair/pool, so a dropped pair can release its filter
+static TRACKERS: Lazy<DashMap<Address, JoinHandle<()>>> = Lazy::new(DashMap::new);
+
+/// Remember the task tracking swaps on `pair` (see [`stop_swap_tracker`]).
+pub fn register_swap_tracker(pair: Address, handle: JoinHandle<()>) {
+    if let Some(old) = TRACKERS.insert(pair, handle) {
+        old.abort();
+    }
+}
+
+/// Stop tracking swaps on `pair`; its log filter is unsubscribed and its dev
+/// wallet state dropped.
+pub fn stop_swap_tracker(pair: Address) {
+    devwatch::forget_pair(pair);
+    if let Some((_, handle)) = TRACKERS.remove(&pair) {
+        handle.abort();
+        save_log_to_file(&format!("[swap-tracker] stopped tracking {:#x}", pair));
+    }
+}
+
 // V2 Swap event: Swap(address indexed sender, uint amount0In, uint amount1In, uint amount0Out, uint amount1Out, address indexed to)
 fn v2_swap_topic() -> B256 {
     keccak256("Swap(address,uint256,uint256,uint256,uint256,address)".as_bytes())
@@ -90,15 +118,85 @@ fn parse_v2_swap(log: &Log, _base_token: Address, is_token0: bool) -> Option<Swa
         amount_out,
         timestamp,
         tx_hash: log.transaction_hash.unwrap_or_default(),
+        by_dev: false,
     })
 }
 
+/// Log `swap` and hand it to the aggregator.
+fn forward_swap(kind: &str, swap: SwapEvent, tx: &mpsc::Sender<SwapEvent>) {
+    let direction = match (swap.is_buy, swap.by_dev) {
+        (true, _) => "BUY",
+        (false, true) => "DEV SELL",
+        (false, false) => "SELL",
+    };
+    save_log_to_file(&format!(
+        "[swap-tracker] {} {} from {:#x} | tx: {:#x}",
+        kind, direction, swap.trader, swap.tx_hash
+    ));
+
+    if tx.try_send(swap).is_err() {
+        save_log_to_file("[swap-tracker] ERROR: Failed to send swap to aggregator (channel full)");
+    }
+}
+
+/// Start the task that forwards the swaps of `pair` in the order they were
+/// mined. When a dev-sell or copy-trading check needs a swap's tx sender (the
+/// Swap event only names the router/recipient), it is looked up there, so the
+/// log stream is never held up by RPC. The task ends once the sender is dropped.
+fn spawn_dispatcher<P: Provider + Clone + 'static>(
+    provider: P,
+    kind: &'static str,
+    pair: Address,
+    base_token: Address,
+    tx: mpsc::Sender<SwapEvent>,
+) -> mpsc::UnboundedSender<SwapEvent> {
+    let (swap_tx, mut swaps) = mpsc::unbounded_channel::<SwapEvent>();
+    tokio::spawn(async move {
+        while let Some(mut swap) = swaps.recv().await {
+            let dev_check = !swap.is_buy && devwatch::has_creators(base_token);
+            if dev_check || copytrade::watching() {
+                inspect_sender(&provider, pair, base_token, dev_check, &mut swap).await;
+            }
+            forward_swap(kind, swap, &tx);
+        }
+    });
+    swap_tx
+}
+
+/// Resolve the tx sender of `swap` and run the dev-sell and copy-trading checks.
+async fn inspect_sender<P: Provider>(
+    provider: &P,
+    pair: Address,
+    base_token: Address,
+    dev_check: bool,
+    swap: &mut SwapEvent,
+) {
+    let Ok(Some(tx)) = provider.get_transaction_by_hash(swap.tx_hash).await else {
+        return;
+    };
+    let sender = tx.from();
+    if dev_check {
+        swap.by_dev = devwatch::check_sell(
+            provider,
+            pair,
+            base_token,
+            sender,
+            swap.tx_hash,
+            swap.amount_in,
+        )
+        .await
+        .is_some();
+    }
+    copytrade::report_swap(pair, sender, swap.is_buy, swap.tx_hash);
+}
+
 /// Subscribe to swap events for a specific pair (V2)
-pub async fn track_v2_pair_swaps(
+pub async fn track_v2_pair_swaps<P: Provider + Clone + 'static>(
     pair: Address,
     base_token: Address,
     is_token0: bool,
-    ws: BscWsClient,
+    subs: SubscriptionManager,
+    provider: P,
     tx: mpsc::Sender<SwapEvent>,
 ) {
     let swap_topic = v2_swap_topic();
@@ -111,55 +209,30 @@ pub async fn track_v2_pair_swaps(
         pair
     ));
 
-    loop {
-        match ws.subscribe_logs(filter.clone()).await {
-            Ok((mut rx_logs, handle)) => {
-                save_log_to_file(&format!(
-                    "[swap-tracker] ✓ V2 subscription active for {:#x}",
-                    pair
-                ));
-
-                let mut event_count = 0u32;
-                while let Some(log_item) = rx_logs.recv().await {
-                    event_count += 1;
-                    save_log_to_file(&format!(
-                        "[swap-tracker] V2 raw log #{} received for {:#x}",
-                        event_count, pair
-                    ));
-
-                    if let Some(swap) = parse_v2_swap(&log_item, base_token, is_token0) {
-                        let direction = if swap.is_buy { "BUY" } else { "SELL" };
-                        save_log_to_file(&format!(
-                            "[swap-tracker] V2 {} from {:#x} | tx: {:#x}",
-                            direction, swap.trader, swap.tx_hash
-                        ));
-
-                        if tx.try_send(swap).is_err() {
-                            save_log_to_file("[swap-tracker] ERROR: Failed to send swap to aggregator (channel full)");
-                        }
-                    } else {
-                        save_log_to_file(&format!(
-                            "[swap-tracker] V2 failed to parse log for {:#x}",
-                            pair
-                        ));
-                    }
-                }
-
-                handle.abort();
-                save_log_to_file(&format!(
-                    "[swap-tracker] Stream ended for {:#x}, retrying...",
-                    pair
-                ));
-            }
-            Err(e) => {
-                save_log_to_file(&format!(
-                    "[swap-tracker] Subscribe failed for {:#x}: {}",
-                    pair, e
-                ));
-                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-            }
+    let mut logs = subs.subscribe(filter);
+    let dispatch = spawn_dispatcher(provider, "V2", pair, base_token, tx);
+    let mut event_count = 0u32;
+    while let Some(log_item) = logs.recv().await {
+        event_count += 1;
+        save_log_to_file(&format!(
+            "[swap-tracker] V2 raw log #{} received for {:#x}",
+            event_count, pair
+        ));
+
+        if let Some(swap) = parse_v2_swap(&log_item, base_token, is_token0) {
+            let _ = dispatch.send(swap);
+        } else {
+            save_log_to_file(&format!(
+                "[swap-tracker] V2 failed to parse log for {:#x}",
+                pair
+            ));
         }
     }
+
+    save_log_to_file(&format!(
+        "[swap-tracker] Subscription closed for {:#x}",
+        pair
+    ));
 }
 
 /// Parse V3 swap event
@@ -220,15 +293,17 @@ fn parse_v3_swap(log: &Log, _base_token: Address, is_token0: bool) -> Option<Swa
         amount_out,
         timestamp,
         tx_hash: log.transaction_hash.unwrap_or_default(),
+        by_dev: false,
     })
 }
 
 /// Subscribe to swap events for a specific pool (V3)
-pub async fn track_v3_pool_swaps(
+pub async fn track_v3_pool_swaps<P: Provider + Clone + 'static>(
     pool: Address,
     base_token: Address,
     is_token0: bool,
-    ws: BscWsClient,
+    subs: SubscriptionManager,
+    provider: P,
     tx: mpsc::Sender<SwapEvent>,
 ) {
     let swap_topic = v3_swap_topic();
@@ -241,53 +316,28 @@ pub async fn track_v3_pool_swaps(
         pool
     ));
 
-    loop {
-        match ws.subscribe_logs(filter.clone()).await {
-            Ok((mut rx_logs, handle)) => {
-                save_log_to_file(&format!(
-                    "[swap-tracker] ✓ V3 subscription active for {:#x}",
-                    pool
-                ));
-
-                let mut event_count = 0u32;
-                while let Some(log_item) = rx_logs.recv().await {
-                    event_count += 1;
-                    save_log_to_file(&format!(
-                        "[swap-tracker] V3 raw log #{} received for {:#x}",
-                        event_count, pool
-                    ));
-
-                    if let Some(swap) = parse_v3_swap(&log_item, base_token, is_token0) {
-                        let direction = if swap.is_buy { "BUY" } else { "SELL" };
-                        save_log_to_file(&format!(
-                            "[swap-tracker] V3 {} from {:#x} | tx: {:#x}",
-                            direction, swap.trader, swap.tx_hash
-                        ));
-
-                        if tx.try_send(swap).is_err() {
-                            save_log_to_file("[swap-tracker] ERROR: Failed to send swap to aggregator (channel full)");
-                        }
-                    } else {
-                        save_log_to_file(&format!(
-                            "[swap-tracker] V3 failed to parse log for {:#x}",
-                            pool
-                        ));
-                    }
-                }
-
-                handle.abort();
-                save_log_to_file(&format!(
-                    "[swap-tracker] Stream ended for {:#x}, retrying...",
-                    pool
-                ));
-            }
-            Err(e) => {
-                save_log_to_file(&format!(
-                    "[swap-tracker] Subscribe failed for {:#x}: {}",
-                    pool, e
-                ));
-                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-            }
+    let mut logs = subs.subscribe(filter);
+    let dispatch = spawn_dispatcher(provider, "V3", pool, base_token, tx);
+    let mut event_count = 0u32;
+    while let Some(log_item) = logs.recv().await {
+        event_count += 1;
+        save_log_to_file(&format!(
+            "[swap-tracker] V3 raw log #{} received for {:#x}",
+            event_count, pool
+        ));
+
+        if let Some(swap) = parse_v3_swap(&log_item, base_token, is_token0) {
+            let _ = dispatch.send(swap);
+        } else {
+            save_log_to_file(&format!(
+                "[swap-tracker] V3 failed to parse log for {:#x}",
+                pool
+            ));
         }
     }
+
+    save_log_to_file(&format!(
+        "[swap-tracker] Subscription closed for {:#x}",
+        pool
+    ));
 }
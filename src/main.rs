@@ -24,7 +24,7 @@ mod tests {
     use std::str::FromStr;
     use url::Url;
 
-    use anyhow::Result;
+    use anyhow::{Context, Result};
 
     use alloy::providers::ProviderBuilder;
     use alloy::providers::WalletProvider;
@@ -40,6 +40,7 @@ mod tests {
     use jimmyb::libs::config::load_env;
     use jimmyb::libs::config::Config;
     use jimmyb::libs::price::{subscribe_v2_price, subscribe_v3_price};
+    use jimmyb::libs::ws::source::LogSource;
     use jimmyb::log;
     use jimmyb::routy::v2 as routy_v2;
     use jimmyb::routy::v3 as routy_v3;
@@ -190,7 +191,7 @@ mod tests {
 
         let router = jimmyb::router::FmRouter::new(provider.clone());
         let (est_funds, tx) = router
-            .sell_percent_pct(from, token, pct_points, U256::ZERO.try_into().unwrap())
+            .sell_percent_pct(from, token, pct_points, 100, U256::ZERO.try_into().unwrap())
             .await?;
 
         let est_funds_str = fourmeme::price::format_units(est_funds, 18);
@@ -348,7 +349,7 @@ mod tests {
             .wallet(signer)
             .connect_http(url);
 
-        let ws = BscWsClient::new(wss, std::env::var("PRIVATE_KEY")?).await?;
+        let ws = LogSource::Ws(BscWsClient::new(wss, std::env::var("PRIVATE_KEY")?).await?);
 
         let (mut v2_rx, _) = subscribe_v2_price(
             provider.clone(),
@@ -403,6 +404,7 @@ mod tests {
             from,
             "0x7130d2A12B9BCbFAe4f2634d864A1Ee1Ce3EaD9c", // BTCB
             1_000,                                        // 10%
+            50,                                           // 0.50% slippage
             None,
         )
         .await?;
@@ -445,6 +447,7 @@ mod tests {
             from,
             BTCB.to_string().as_str(), // BTCB
             format_bnb_hum(bnb_in_amount_str),
+            50, // 0.50% slippage
             None,
         )
         .await?;
@@ -488,6 +491,7 @@ mod tests {
             WBNB.to_string().as_str(),
             USDT.to_string().as_str(),
             format_bnb_hum(bnb_in_amount_str),
+            50, // 0.50% slippage
             None,
         )
         .await?;
@@ -531,6 +535,7 @@ mod tests {
             WBNB.to_string().as_str(),
             USDT.to_string().as_str(),
             format_bnb_hum(bnb_in_amount_str),
+            50, // 0.50% slippage
             None,
         )
         .await?;
@@ -574,6 +579,7 @@ mod tests {
             BTCB.to_string().as_str(),
             USDT.to_string().as_str(),
             format_bnb_hum(btcb_in_amount_str),
+            50, // 0.50% slippage
             None,
         )
         .await?;
@@ -617,6 +623,7 @@ mod tests {
             BTCB.to_string().as_str(),
             USDT.to_string().as_str(),
             format_bnb_hum(btcb_in_amount_str),
+            50, // 0.50% slippage
             None,
         )
         .await?;
@@ -635,7 +642,8 @@ mod tests {
         load_env();
 
         let cfg = Config::new();
-        let ws = BscWsClient::new(cfg.bsc_wss.clone(), cfg.private_key.clone()).await?;
+        let wss = cfg.bsc_wss.clone().context("BSC_WSS is not set")?;
+        let ws = BscWsClient::new(wss, cfg.private_key.clone()).await?;
 
         let topic0 = keccak256("PoolCreated(address,address,uint24,int24,address)".as_bytes());
         let filter = Filter::new()
@@ -688,7 +696,8 @@ mod tests {
         load_env();
 
         let cfg = Config::new();
-        let ws = BscWsClient::new(cfg.bsc_wss.clone(), cfg.private_key.clone()).await?;
+        let wss = cfg.bsc_wss.clone().context("BSC_WSS is not set")?;
+        let ws = BscWsClient::new(wss, cfg.private_key.clone()).await?;
 
         let topic0 = v2_pair_created_topic();
         let filter = Filter::new()
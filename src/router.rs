@@ -5,6 +5,7 @@ use anyhow::{bail, Result};
 
 use fourmeme::abi::{ITokenManager2, ITokenManagerHelper3};
 use fourmeme::addresses::TOKEN_MANAGER_HELPER_3;
+use pancakes::tx::{send_replaceable, TxSide};
 
 alloy::sol! {
     #[sol(rpc)]
@@ -18,6 +19,35 @@ alloy::sol! {
 
 pub type TxHash = B256;
 
+/// Revert codes documented by four.meme (abis/API-Documents) and their meaning.
+const FM_REVERT_CODES: [(&str, &str); 8] = [
+    ("GW", "amount not aligned to GWEI"),
+    ("ZA", "recipient is the zero address"),
+    ("TO", "recipient is the PancakePair"),
+    ("Slippage", "slippage limit exceeded"),
+    ("More BNB", "insufficient BNB sent"),
+    ("A", "X Mode token, use the X Mode buy method"),
+    ("FR", "fee rate above 5%"),
+    ("SO", "order too small"),
+];
+
+/// Spell out a four.meme revert code in a send/revert error.
+fn describe_fm_revert(e: anyhow::Error) -> anyhow::Error {
+    let msg = e.to_string();
+    let code = msg
+        .rsplit("reverted: ")
+        .next()
+        .and_then(|rest| rest.split(',').next())
+        .map(str::trim)
+        .unwrap_or_default();
+    match FM_REVERT_CODES.iter().find(|(c, _)| *c == code) {
+        Some((_, meaning)) if msg.contains("reverted: ") => {
+            anyhow::anyhow!("{} (four.meme: {})", msg, meaning)
+        }
+        _ => e,
+    }
+}
+
 #[derive(Clone)]
 pub struct FmRouter<P: Provider + Clone> {
     pub provider: P,
@@ -77,9 +107,10 @@ impl<P: Provider + Clone> FmRouter<P> {
                 call = call.gas_price(gp.try_into().unwrap());
             }
 
-            let pending = call.send().await?;
-            let tx = *pending.tx_hash();
-            let _ = pending.get_receipt().await;
+            let (tx, _) =
+                send_replaceable(&self.provider, call.into_transaction_request(), TxSide::Buy)
+                    .await
+                    .map_err(describe_fm_revert)?;
             Ok((estimated_amount, tx))
         } else {
             let mut call = helper
@@ -91,9 +122,10 @@ impl<P: Provider + Clone> FmRouter<P> {
                 call = call.gas_price(gp.try_into().unwrap());
             }
 
-            let pending = call.send().await?;
-            let tx = *pending.tx_hash();
-            let _ = pending.get_receipt().await;
+            let (tx, _) =
+                send_replaceable(&self.provider, call.into_transaction_request(), TxSide::Buy)
+                    .await
+                    .map_err(describe_fm_revert)?;
             Ok((estimated_amount, tx))
         }
     }
@@ -102,11 +134,13 @@ impl<P: Provider + Clone> FmRouter<P> {
     /// Path based on quote token:
     /// - quote == Address::ZERO: call TM2.sellToken(token, amount)
     /// - quote != Address::ZERO: call Helper3.sellForEth with minFunds derived from trySell
+    /// - slippage_bps: e.g. 100 = 1% (only applies to the Helper3 path)
     pub async fn sell_percent(
         &self,
         from: Address,
         token: Address,
         percent_bps: u32, // 1..=10000
+        slippage_bps: u32,
         gas_price_wei: Option<U256>,
     ) -> Result<(U256, TxHash)> {
         if percent_bps == 0 || percent_bps > 10_000 {
@@ -140,12 +174,17 @@ impl<P: Provider + Clone> FmRouter<P> {
             if let Some(gp) = gas_price_wei {
                 call = call.gas_price(gp.try_into().unwrap());
             }
-            let pending = call.send().await?;
-            let tx = *pending.tx_hash();
-            let _ = pending.get_receipt().await;
+            let (tx, _) = send_replaceable(
+                &self.provider,
+                call.into_transaction_request(),
+                TxSide::Sell,
+            )
+            .await
+            .map_err(describe_fm_revert)?;
             Ok((est_funds, tx))
         } else {
-            let min_funds = align_to_gwei(apply_negative_slippage(est_funds, 100)); // 1% negative slippage default + gwei alignment
+            // negative slippage + gwei alignment; slippage reverts bubble up so callers can escalate
+            let min_funds = align_to_gwei(apply_negative_slippage(est_funds, slippage_bps));
             let mut call = helper
                 .sellForEth(
                     U256::ZERO,
@@ -159,33 +198,13 @@ impl<P: Provider + Clone> FmRouter<P> {
             if let Some(gp) = gas_price_wei {
                 call = call.gas_price(gp.try_into().unwrap());
             }
-            let pending = match call.send().await {
-                Ok(p) => p,
-                Err(e) => {
-                    let msg = e.to_string();
-                    if msg.contains("Slippage") || msg.contains("slippage") {
-                        let mut retry = helper
-                            .sellForEth(
-                                U256::ZERO,
-                                token,
-                                amount,
-                                U256::ZERO,
-                                U256::ZERO,
-                                Address::ZERO,
-                            )
-                            .from(from);
-                        if let Some(gp) = gas_price_wei {
-                            retry = retry.gas_price(gp.try_into().unwrap());
-                        }
-                        retry.send().await?
-                    } else {
-                        return Err(e.into());
-                    }
-                }
-            };
-
-            let tx = *pending.tx_hash();
-            let _ = pending.get_receipt().await;
+            let (tx, _) = send_replaceable(
+                &self.provider,
+                call.into_transaction_request(),
+                TxSide::Sell,
+            )
+            .await
+            .map_err(describe_fm_revert)?;
             Ok((est_funds, tx))
         }
     }
@@ -195,13 +214,15 @@ impl<P: Provider + Clone> FmRouter<P> {
         from: Address,
         token: Address,
         percent_points: u32, // 1..=100
+        slippage_bps: u32,
         gas_price_wei: Option<U256>,
     ) -> Result<(U256, TxHash)> {
         if percent_points == 0 || percent_points > 100 {
             bail!("percent must be 1..=100");
         }
         let bps = percent_points.saturating_mul(100);
-        self.sell_percent(from, token, bps, gas_price_wei).await
+        self.sell_percent(from, token, bps, slippage_bps, gas_price_wei)
+            .await
     }
 }
 
@@ -209,6 +230,7 @@ fn apply_negative_slippage(amount: U256, bps: u32) -> U256 {
     if amount.is_zero() || bps == 0 {
         return amount;
     }
+    let bps = bps.min(10_000);
     let num = amount.saturating_mul(U256::from(10_000u64 - bps as u64));
     num / U256::from(10_000u64)
 }
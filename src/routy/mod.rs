@@ -1,5 +1,6 @@
 //! Routy: thin, ergonomic wrappers over Pancake v3 flows.
 
+pub mod route;
 pub mod swap;
 pub mod v2;
 pub mod v3;
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+use pancakes::pancake::addresses::{ASTER, CAKE, USD1, USDT, WBNB};
+use pancakes::pancake::pancake_swap_v2::{path2, path3};
+use pancakes::plug::price::{get_price_v2, get_price_v3, PriceQuote};
+
+use crate::libs::lookup::save_log_to_file;
+
+/// Non-WBNB quotes we can route through with a WBNB -> quote -> token hop.
+pub const ROUTABLE_QUOTES: [Address; 4] = [USDT, CAKE, USD1, ASTER];
+
+/// How a token is reached from WBNB: directly, or through an intermediate quote.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Route {
+    pub token: Address,
+    /// Intermediate quote token; `None` trades against WBNB directly
+    pub via: Option<Address>,
+    /// V3 fee tier of the token/quote pool (None = unknown or v2)
+    pub fee: Option<u32>,
+}
+
+impl Route {
+    /// Resolve the route for a pair made of `addr1`/`addr2`. WBNB pairs are direct,
+    /// pairs against a routable quote hop through it; anything else is not tradable.
+    pub fn for_pair(addr1: Address, addr2: Address, fee: Option<u32>) -> Option<Self> {
+        let (token, quote) = if addr1 == WBNB || ROUTABLE_QUOTES.contains(&addr1) {
+            (addr2, addr1)
+        } else if addr2 == WBNB || ROUTABLE_QUOTES.contains(&addr2) {
+            (addr1, addr2)
+        } else {
+            return None;
+        };
+        if token == WBNB || ROUTABLE_QUOTES.contains(&token) {
+            return None;
+        }
+        Some(Self {
+            token,
+            via: (quote != WBNB).then_some(quote),
+            fee,
+        })
+    }
+
+    pub fn quote(&self) -> Address {
+        self.via.unwrap_or(WBNB)
+    }
+
+    /// v2 path WBNB -> [quote] -> token
+    pub fn v2_buy_path(&self) -> Vec<Address> {
+        match self.via {
+            Some(q) => path3(WBNB, q, self.token),
+            None => path2(WBNB, self.token),
+        }
+    }
+
+    /// v2 path token -> [quote] -> WBNB
+    pub fn v2_sell_path(&self) -> Vec<Address> {
+        match self.via {
+            Some(q) => path3(self.token, q, WBNB),
+            None => path2(self.token, WBNB),
+        }
+    }
+}
+
+/// WBNB per 1 whole unit of each routable quote, refreshed in the background.
+static QUOTE_WBNB_RATES: Lazy<DashMap<Address, f64>> = Lazy::new(DashMap::new);
+
+/// WBNB value of one unit of `quote`. WBNB (and the four.meme native sentinel) is 1.
+pub fn quote_wbnb_rate(quote: Address) -> Option<f64> {
+    if quote == WBNB || quote == Address::ZERO {
+        return Some(1.0);
+    }
+    QUOTE_WBNB_RATES.get(&quote).map(|r| *r)
+}
+
+/// Convert a token price quoted in the route's quote into WBNB terms.
+pub fn price_in_wbnb(route: &Route, price_in_quote: f64) -> Option<f64> {
+    quote_wbnb_rate(route.quote()).map(|rate| price_in_quote * rate)
+}
+
+fn quote_to_f64(q: &PriceQuote) -> Option<f64> {
+    let out = q.amount_out_base_units.to_string().parse::<f64>().ok()?;
+    let scale = 10f64.powi(q.decimals_out as i32);
+    (out > 0.0).then_some(out / scale)
+}
+
+/// Fetch the current WBNB rate of every routable quote (v2 first, v3 fallback).
+pub async fn refresh_quote_rates<P: Provider + Clone>(provider: P) {
+    for quote in ROUTABLE_QUOTES {
+        let price = match get_price_v2(provider.clone(), quote, WBNB).await {
+            Ok(q) => Some(q),
+            Err(_) => get_price_v3(provider.clone(), quote, WBNB, None).await.ok(),
+        };
+        match price.as_ref().and_then(quote_to_f64) {
+            Some(rate) => {
+                QUOTE_WBNB_RATES.insert(quote, rate);
+            }
+            None => save_log_to_file(&format!(
+                "[route] no WBNB rate for quote {:#x}; keeping last value",
+                quote
+            )),
+        }
+    }
+}
+
+/// Keep `QUOTE_WBNB_RATES` fresh every `every`.
+pub fn spawn_quote_rate_refresher<P>(provider: P, every: Duration)
+where
+    P: Provider + Clone + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            refresh_quote_rates(provider.clone()).await;
+        }
+    });
+}
@@ -5,13 +5,16 @@ use alloy::providers::Provider;
 
 use pancakes::pancake::pancake_swap_v2::{PancakeV2, TxHash};
 
+use super::route::Route;
+
 /// Sell a percentage of an ERC20 into WBNB with sane defaults (v2).
-/// Defaults: fee=500 (kept for parity), slippage=50 (0.50%), recipient=from, deadline=300s, no sim
+/// Defaults: fee=500 (kept for parity), recipient=from, deadline=300s, no sim
 pub async fn sell_pct_to_wbnb<P: Provider + Clone>(
     pancake: &PancakeV2<P>,
     from: Address,
     token_in: &str,
     percent_bps: u16,
+    slippage_bps: u32,
     gas_price_wei: Option<u128>,
 ) -> Result<(U256, TxHash)> {
     pancake
@@ -20,7 +23,7 @@ pub async fn sell_pct_to_wbnb<P: Provider + Clone>(
             token_in,
             percent_bps,
             500,
-            50,
+            slippage_bps,
             from,
             300,
             false,
@@ -30,12 +33,13 @@ pub async fn sell_pct_to_wbnb<P: Provider + Clone>(
 }
 
 /// Swap WBNB into a token with sane defaults (v2).
-/// Defaults: fee=500 (kept for parity), slippage=50 (0.50%), recipient=from, deadline=300s, no sim
+/// Defaults: fee=500 (kept for parity), recipient=from, deadline=300s, no sim
 pub async fn swap_wbnb_to<P: Provider + Clone>(
     pancake: &PancakeV2<P>,
     from: Address,
     token_out: &str,
     bnb_in_amount: U256,
+    slippage_bps: u32,
     gas_price_wei: Option<u128>,
 ) -> Result<(U256, TxHash)> {
     pancake
@@ -44,7 +48,7 @@ pub async fn swap_wbnb_to<P: Provider + Clone>(
             token_out,
             bnb_in_amount,
             500,
-            50,
+            slippage_bps,
             from,
             300,
             false,
@@ -54,13 +58,14 @@ pub async fn swap_wbnb_to<P: Provider + Clone>(
 }
 
 /// Swap token -> token (v2) with sane defaults.
-/// Defaults: fee=500 (kept for parity), slippage=50 (0.50%), recipient=from, deadline=300s, no sim
+/// Defaults: fee=500 (kept for parity), recipient=from, deadline=300s, no sim
 pub async fn swap_token_to<P: Provider + Clone>(
     pancake: &PancakeV2<P>,
     from: Address,
     token_in: &str,
     token_out: &str,
     amount_in: U256,
+    slippage_bps: u32,
     gas_price_wei: Option<u128>,
 ) -> Result<(U256, TxHash)> {
     pancake
@@ -70,7 +75,7 @@ pub async fn swap_token_to<P: Provider + Clone>(
             token_out,
             amount_in,
             500,
-            50,
+            slippage_bps,
             from,
             300,
             false,
@@ -78,3 +83,75 @@ pub async fn swap_token_to<P: Provider + Clone>(
         )
         .await
 }
+
+/// Sell a percentage of `route.token` into WBNB, hopping through the route's
+/// quote when the pair is not WBNB-quoted (v2).
+/// Defaults: recipient=from, deadline=300s, no sim
+pub async fn sell_pct_via<P: Provider + Clone>(
+    pancake: &PancakeV2<P>,
+    from: Address,
+    route: &Route,
+    percent_bps: u16,
+    slippage_bps: u32,
+    gas_price_wei: Option<u128>,
+) -> Result<(U256, TxHash)> {
+    if route.via.is_none() {
+        let token_in = format!("{:#x}", route.token);
+        return sell_pct_to_wbnb(
+            pancake,
+            from,
+            token_in.as_str(),
+            percent_bps,
+            slippage_bps,
+            gas_price_wei,
+        )
+        .await;
+    }
+    pancake
+        .sell_percent_via_path(
+            from,
+            route.v2_sell_path(),
+            percent_bps,
+            slippage_bps,
+            from,
+            300,
+            gas_price_wei,
+        )
+        .await
+}
+
+/// Swap WBNB into `route.token`, hopping WBNB -> quote -> token when the pair
+/// is not WBNB-quoted (v2).
+/// Defaults: recipient=from, deadline=300s, no sim
+pub async fn swap_wbnb_via<P: Provider + Clone>(
+    pancake: &PancakeV2<P>,
+    from: Address,
+    route: &Route,
+    bnb_in_amount: U256,
+    slippage_bps: u32,
+    gas_price_wei: Option<u128>,
+) -> Result<(U256, TxHash)> {
+    if route.via.is_none() {
+        let token_out = format!("{:#x}", route.token);
+        return swap_wbnb_to(
+            pancake,
+            from,
+            token_out.as_str(),
+            bnb_in_amount,
+            slippage_bps,
+            gas_price_wei,
+        )
+        .await;
+    }
+    pancake
+        .swap_exact_in_path(
+            from,
+            route.v2_buy_path(),
+            bnb_in_amount,
+            slippage_bps,
+            from,
+            300,
+            gas_price_wei,
+        )
+        .await
+}
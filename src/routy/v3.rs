@@ -1,26 +1,80 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
+use alloy::primitives::aliases::U24;
 use alloy::primitives::{Address, U256};
 use alloy::providers::Provider;
 
+use pancakes::pancake::pancake_swap::addresses::{PANCAKE_V3_FACTORY, WBNB};
 use pancakes::pancake::pancake_swap::{PancakeV3, TxHash};
+use pancakes::plug::price::{IERC20View, IPancakeV3FactoryView};
+
+use super::route::Route;
+
+const V3_FEE_TIERS: [u32; 4] = [100, 500, 2500, 10000];
+
+/// Probe every fee tier via the factory and return the one whose `token`/`quote`
+/// pool holds the most `quote` (deepest pool).
+pub async fn deepest_fee_tier<P: Provider + Clone>(
+    provider: P,
+    token: Address,
+    quote: Address,
+) -> Result<u32> {
+    let factory = IPancakeV3FactoryView::new(PANCAKE_V3_FACTORY, provider.clone());
+    let quote_erc20 = IERC20View::new(quote, provider.clone());
+    let mut best: Option<(u32, U256)> = None;
+    for fee in V3_FEE_TIERS {
+        let pool = factory.getPool(token, quote, U24::from(fee)).call().await?;
+        if pool == Address::ZERO {
+            continue;
+        }
+        let depth = quote_erc20
+            .balanceOf(pool)
+            .call()
+            .await
+            .unwrap_or(U256::ZERO);
+        if best.map(|(_, d)| depth > d).unwrap_or(true) {
+            best = Some((fee, depth));
+        }
+    }
+    best.map(|(fee, _)| fee)
+        .ok_or_else(|| anyhow!("no v3 pool found for {:#x}/{:#x}", token, quote))
+}
+
+async fn resolve_fee<P: Provider + Clone>(
+    pancake: &PancakeV3<P>,
+    fee: Option<u32>,
+    token: &str,
+    quote: Address,
+) -> Result<u32> {
+    match fee {
+        Some(f) => Ok(f),
+        None => {
+            let token: Address = token.parse()?;
+            deepest_fee_tier(pancake.provider.clone(), token, quote).await
+        }
+    }
+}
 
 /// Sell a percentage of an ERC20 into WBNB with sane defaults (v3).
-/// Defaults: fee=500 (0.05%), slippage=50 (0.50%), recipient=from, deadline=300s, no sim
+/// `fee` is the pool tier; `None` picks the deepest token/WBNB pool.
+/// Defaults: recipient=from, deadline=300s, no sim
 pub async fn sell_pct_to_wbnb<P: Provider + Clone>(
     pancake: &PancakeV3<P>,
     from: Address,
     token_in: &str,
     percent_bps: u16,
+    fee: Option<u32>,
+    slippage_bps: u32,
     gas_price_wei: Option<u128>,
 ) -> Result<(U256, TxHash)> {
+    let fee = resolve_fee(pancake, fee, token_in, WBNB).await?;
     pancake
         .sell_percent_to_wbnb(
             from,
             token_in,
             percent_bps,
-            500,
-            50,
+            fee,
+            slippage_bps,
             from,
             300,
             false,
@@ -30,21 +84,25 @@ pub async fn sell_pct_to_wbnb<P: Provider + Clone>(
 }
 
 /// Swap WBNB into a token with sane defaults (v3).
-/// Defaults: fee=500 (0.05%), slippage=50 (0.50%), recipient=from, deadline=300s, no sim
+/// `fee` is the pool tier; `None` picks the deepest token/WBNB pool.
+/// Defaults: recipient=from, deadline=300s, no sim
 pub async fn swap_wbnb_to<P: Provider + Clone>(
     pancake: &PancakeV3<P>,
     from: Address,
     token_out: &str,
     bnb_in_amount: U256,
+    fee: Option<u32>,
+    slippage_bps: u32,
     gas_price_wei: Option<u128>,
 ) -> Result<(U256, TxHash)> {
+    let fee = resolve_fee(pancake, fee, token_out, WBNB).await?;
     pancake
         .swap_wbnb_to_token(
             from,
             token_out,
             bnb_in_amount,
-            500,
-            50,
+            fee,
+            slippage_bps,
             from,
             300,
             false,
@@ -54,13 +112,14 @@ pub async fn swap_wbnb_to<P: Provider + Clone>(
 }
 
 /// Swap token -> token (v3) with sane defaults.
-/// Defaults: fee=500 (0.05%), slippage=50 (0.50%), recipient=from, deadline=300s, no sim
+/// Defaults: fee=500 (0.05%), recipient=from, deadline=300s, no sim
 pub async fn swap_token_to<P: Provider + Clone>(
     pancake: &PancakeV3<P>,
     from: Address,
     token_in: &str,
     token_out: &str,
     amount_in: U256,
+    slippage_bps: u32,
     gas_price_wei: Option<u128>,
 ) -> Result<(U256, TxHash)> {
     pancake
@@ -70,7 +129,7 @@ pub async fn swap_token_to<P: Provider + Clone>(
             token_out,
             amount_in,
             500,
-            50,
+            slippage_bps,
             from,
             300,
             false,
@@ -78,3 +137,95 @@ pub async fn swap_token_to<P: Provider + Clone>(
         )
         .await
 }
+
+/// Fee tiers for a routed swap as (token/quote pool, quote/WBNB pool).
+pub async fn route_fees<P: Provider + Clone>(
+    pancake: &PancakeV3<P>,
+    route: &Route,
+) -> Result<(u32, u32)> {
+    let quote = route.quote();
+    let token_fee = match route.fee {
+        Some(f) => f,
+        None => deepest_fee_tier(pancake.provider.clone(), route.token, quote).await?,
+    };
+    let quote_fee = deepest_fee_tier(pancake.provider.clone(), quote, WBNB).await?;
+    Ok((token_fee, quote_fee))
+}
+
+/// Sell a percentage of `route.token` into WBNB, hopping token -> quote -> WBNB
+/// when the pool is not WBNB-quoted (v3).
+/// Defaults: recipient=from, deadline=300s, no sim
+pub async fn sell_pct_via<P: Provider + Clone>(
+    pancake: &PancakeV3<P>,
+    from: Address,
+    route: &Route,
+    percent_bps: u16,
+    slippage_bps: u32,
+    gas_price_wei: Option<u128>,
+) -> Result<(U256, TxHash)> {
+    let Some(quote) = route.via else {
+        let token_in = format!("{:#x}", route.token);
+        return sell_pct_to_wbnb(
+            pancake,
+            from,
+            token_in.as_str(),
+            percent_bps,
+            route.fee,
+            slippage_bps,
+            gas_price_wei,
+        )
+        .await;
+    };
+    let (token_fee, quote_fee) = route_fees(pancake, route).await?;
+    pancake
+        .sell_percent_via_path(
+            from,
+            &[route.token, quote, WBNB],
+            &[token_fee, quote_fee],
+            percent_bps,
+            slippage_bps,
+            from,
+            300,
+            gas_price_wei,
+        )
+        .await
+}
+
+/// Swap WBNB into `route.token`, hopping WBNB -> quote -> token when the pool
+/// is not WBNB-quoted (v3).
+/// Defaults: recipient=from, deadline=300s, no sim
+pub async fn swap_wbnb_via<P: Provider + Clone>(
+    pancake: &PancakeV3<P>,
+    from: Address,
+    route: &Route,
+    bnb_in_amount: U256,
+    slippage_bps: u32,
+    gas_price_wei: Option<u128>,
+) -> Result<(U256, TxHash)> {
+    let Some(quote) = route.via else {
+        let token_out = format!("{:#x}", route.token);
+        return swap_wbnb_to(
+            pancake,
+            from,
+            token_out.as_str(),
+            bnb_in_amount,
+            route.fee,
+            slippage_bps,
+            gas_price_wei,
+        )
+        .await;
+    };
+    let (token_fee, quote_fee) = route_fees(pancake, route).await?;
+    pancake
+        .swap_exact_in_path(
+            from,
+            &[WBNB, quote, route.token],
+            &[quote_fee, token_fee],
+            bnb_in_amount,
+            slippage_bps,
+            from,
+            300,
+            gas_price_wei,
+        )
+        .await
+}
@@ -5,6 +5,7 @@ use alloy::providers::Provider;
 
 use pancakes::pancake::pancake_swap::addresses::WBNB;
 use pancakes::pancake::pancake_swap::TxHash;
+use pancakes::tx::{send_replaceable, TxSide};
 
 alloy::sol! {
     #[sol(rpc)]
@@ -22,9 +23,12 @@ pub async fn wrap_bnb<P: Provider + Clone>(
     amount: U256,
 ) -> Result<TxHash> {
     let wbnb = IWBNB::new(WBNB, provider.clone());
-    let pending = wbnb.deposit().from(from).value(amount).send().await?;
-    let tx = *pending.tx_hash();
-    let _ = pending.get_receipt().await;
+    let request = wbnb
+        .deposit()
+        .from(from)
+        .value(amount)
+        .into_transaction_request();
+    let (tx, _) = send_replaceable(&provider, request, TxSide::Buy).await?;
     Ok(tx)
 }
 
@@ -35,8 +39,7 @@ pub async fn unwrap_wbnb<P: Provider + Clone>(
     amount: U256,
 ) -> Result<TxHash> {
     let wbnb = IWBNB::new(WBNB, provider.clone());
-    let pending = wbnb.withdraw(amount).from(from).send().await?;
-    let tx = *pending.tx_hash();
-    let _ = pending.get_receipt().await;
+    let request = wbnb.withdraw(amount).from(from).into_transaction_request();
+    let (tx, _) = send_replaceable(&provider, request, TxSide::Sell).await?;
     Ok(tx)
 }